@@ -0,0 +1,3 @@
+pub mod aiming;
+pub mod behaviour_tree;
+pub mod steering;
@@ -0,0 +1,33 @@
+use glam::Vec3;
+
+// Projectiles inherit the shooter's velocity, so the intercept is solved in the shooter's frame
+pub fn lead_target(
+    shooter: Vec3,
+    shooter_velocity: Vec3,
+    target: Vec3,
+    target_velocity: Vec3,
+    projectile_speed: f32,
+) -> Option<Vec3> {
+    let offset = target - shooter;
+    let velocity = target_velocity - shooter_velocity;
+
+    let a = velocity.length_squared() - projectile_speed * projectile_speed;
+    let b = 2.0 * offset.dot(velocity);
+    let c = offset.length_squared();
+
+    let time = if a.abs() < f32::EPSILON {
+        (b.abs() > f32::EPSILON).then(|| -c / b)?
+    } else {
+        let discriminant = b * b - 4.0 * a * c;
+        if discriminant < 0.0 {
+            return None;
+        }
+        let root = discriminant.sqrt();
+        [(-b - root) / (2.0 * a), (-b + root) / (2.0 * a)]
+            .into_iter()
+            .filter(|time| *time > 0.0)
+            .reduce(f32::min)?
+    };
+
+    (time > 0.0).then(|| (offset + velocity * time).normalize())
+}
@@ -0,0 +1,223 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use glam::Vec3;
+
+use crate::{
+    game_root::GameError,
+    resource_manager::{try_get_file, ResourceLoader},
+};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Status {
+    Success,
+    Failure,
+    Running,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum BlackboardValue {
+    Bool(bool),
+    Float(f32),
+    Entity(usize),
+    Vector(Vec3),
+}
+
+#[derive(Clone, Default)]
+pub struct Blackboard {
+    values: HashMap<String, BlackboardValue>,
+}
+
+impl Blackboard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&mut self, key: &str, value: BlackboardValue) {
+        self.values.insert(String::from(key), value);
+    }
+
+    pub fn remove(&mut self, key: &str) {
+        self.values.remove(key);
+    }
+
+    pub fn get(&self, key: &str) -> Option<BlackboardValue> {
+        self.values.get(key).copied()
+    }
+
+    pub fn bool(&self, key: &str) -> bool {
+        matches!(self.get(key), Some(BlackboardValue::Bool(true)))
+    }
+
+    pub fn float(&self, key: &str) -> Option<f32> {
+        match self.get(key)? {
+            BlackboardValue::Float(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn entity(&self, key: &str) -> Option<usize> {
+        match self.get(key)? {
+            BlackboardValue::Entity(entity) => Some(entity),
+            _ => None,
+        }
+    }
+
+    pub fn vector(&self, key: &str) -> Option<Vec3> {
+        match self.get(key)? {
+            BlackboardValue::Vector(vector) => Some(vector),
+            _ => None,
+        }
+    }
+}
+
+pub trait BehaviourAgent {
+    fn condition(&self, name: &str, args: &[f32], blackboard: &Blackboard) -> bool;
+    fn action(&mut self, name: &str, args: &[f32], blackboard: &mut Blackboard) -> Status;
+}
+
+#[derive(Clone, Debug)]
+pub enum Node {
+    Sequence(Vec<Node>),
+    Selector(Vec<Node>),
+    Invert(Box<Node>),
+    Succeed(Box<Node>),
+    Condition(String, Vec<f32>),
+    Action(String, Vec<f32>),
+}
+
+impl Node {
+    pub fn tick(&self, agent: &mut impl BehaviourAgent, blackboard: &mut Blackboard) -> Status {
+        match self {
+            Node::Sequence(children) => children
+                .iter()
+                .map(|child| child.tick(agent, blackboard))
+                .find(|status| *status != Status::Success)
+                .unwrap_or(Status::Success),
+            Node::Selector(children) => children
+                .iter()
+                .map(|child| child.tick(agent, blackboard))
+                .find(|status| *status != Status::Failure)
+                .unwrap_or(Status::Failure),
+            Node::Invert(child) => match child.tick(agent, blackboard) {
+                Status::Success => Status::Failure,
+                Status::Failure => Status::Success,
+                Status::Running => Status::Running,
+            },
+            Node::Succeed(child) => match child.tick(agent, blackboard) {
+                Status::Running => Status::Running,
+                _ => Status::Success,
+            },
+            Node::Condition(name, args) => match agent.condition(name, args, blackboard) {
+                true => Status::Success,
+                false => Status::Failure,
+            },
+            Node::Action(name, args) => agent.action(name, args, blackboard),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BehaviourTree {
+    root: Node,
+}
+
+impl BehaviourTree {
+    pub fn new(root: Node) -> Self {
+        Self { root }
+    }
+
+    pub fn tick(&self, agent: &mut impl BehaviourAgent, blackboard: &mut Blackboard) -> Status {
+        self.root.tick(agent, blackboard)
+    }
+
+    pub fn parse(source: &str) -> Result<Self, GameError> {
+        let lines: Vec<_> = source
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && !line.trim().starts_with('#'))
+            .map(|(number, line)| (number + 1, indentation(line), line.trim()))
+            .collect();
+
+        let mut position = 0;
+        let root = parse_node(&lines, &mut position)?;
+        match lines.get(position) {
+            Some((number, _, _)) => GameError::err(format!(
+                "Unexpected node at line {}, a behaviour tree has a single root",
+                number
+            )),
+            None => Ok(Self { root }),
+        }
+    }
+}
+
+impl Default for BehaviourTree {
+    fn default() -> Self {
+        Self::new(Node::Action(String::from("idle"), vec![]))
+    }
+}
+
+impl ResourceLoader for BehaviourTree {
+    type Resource = BehaviourTree;
+
+    fn is_resource(path: &PathBuf) -> bool {
+        path.extension().map_or(false, |e| e == "behaviour")
+    }
+
+    fn load_resource(contents: &[PathBuf]) -> Result<Self::Resource, GameError> {
+        let source = fs::read_to_string(try_get_file("tree", contents)?)?;
+        Self::parse(&source)
+    }
+}
+
+fn indentation(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum()
+}
+
+fn parse_node(lines: &[(usize, usize, &str)], position: &mut usize) -> Result<Node, GameError> {
+    let Some(&(number, depth, line)) = lines.get(*position) else {return GameError::err(String::from("Expected a behaviour tree node"))};
+    *position += 1;
+
+    let mut tokens = line.split_whitespace();
+    let kind = tokens.next().unwrap_or_default();
+    let name = tokens.next().map(String::from);
+    let args = tokens
+        .map(|arg| arg.parse::<f32>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| GameError::new(&format!("Invalid argument at line {}", number)))?;
+
+    let mut children = vec![];
+    while let Some(&(_, child_depth, _)) = lines.get(*position) {
+        if child_depth <= depth {
+            break;
+        }
+        children.push(parse_node(lines, position)?);
+    }
+
+    let single_child = |mut children: Vec<Node>| match children.len() {
+        1 => Ok(Box::new(children.remove(0))),
+        _ => GameError::err(format!(
+            "'{}' at line {} takes exactly one child",
+            kind, number
+        )),
+    };
+    let leaf = |name: Option<String>| match (name, children.is_empty()) {
+        (Some(name), true) => Ok(name),
+        _ => GameError::err(format!(
+            "'{}' at line {} needs a name and no children",
+            kind, number
+        )),
+    };
+
+    match kind {
+        "sequence" => Ok(Node::Sequence(children)),
+        "selector" => Ok(Node::Selector(children)),
+        "invert" => Ok(Node::Invert(single_child(children)?)),
+        "succeed" => Ok(Node::Succeed(single_child(children)?)),
+        "condition" => Ok(Node::Condition(leaf(name)?, args)),
+        "action" => Ok(Node::Action(leaf(name)?, args)),
+        _ => GameError::err(format!("Unknown node '{}' at line {}", kind, number)),
+    }
+}
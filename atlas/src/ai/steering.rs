@@ -0,0 +1,131 @@
+use glam::Vec3;
+
+#[derive(Clone, Copy, Debug)]
+pub struct SquadParameters {
+    pub max_speed: f32,
+    pub max_acceleration: f32,
+    pub neighbour_radius: f32,
+    pub separation_radius: f32,
+    pub separation_weight: f32,
+    pub alignment_weight: f32,
+    pub cohesion_weight: f32,
+    pub goal_weight: f32,
+    pub avoidance_weight: f32,
+    pub look_ahead: f32,
+}
+
+impl Default for SquadParameters {
+    fn default() -> Self {
+        Self {
+            max_speed: 40.0,
+            max_acceleration: 20.0,
+            neighbour_radius: 40.0,
+            separation_radius: 10.0,
+            separation_weight: 1.5,
+            alignment_weight: 0.8,
+            cohesion_weight: 0.6,
+            goal_weight: 1.0,
+            avoidance_weight: 3.0,
+            look_ahead: 2.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Boid {
+    pub position: Vec3,
+    pub velocity: Vec3,
+}
+
+pub struct Obstacle {
+    pub position: Vec3,
+    pub radius: f32,
+}
+
+pub fn seek(boid: &Boid, target: Vec3, max_speed: f32) -> Vec3 {
+    (target - boid.position).normalize_or_zero() * max_speed - boid.velocity
+}
+
+pub fn flee(boid: &Boid, threat: Vec3, max_speed: f32) -> Vec3 {
+    (boid.position - threat).normalize_or_zero() * max_speed - boid.velocity
+}
+
+pub fn arrive(boid: &Boid, target: Vec3, max_speed: f32, slowing_radius: f32) -> Vec3 {
+    let offset = target - boid.position;
+    let distance = offset.length();
+    let speed = max_speed * (distance / slowing_radius).min(1.0);
+    offset.normalize_or_zero() * speed - boid.velocity
+}
+
+pub fn pursue(boid: &Boid, target: &Boid, max_speed: f32) -> Vec3 {
+    seek(boid, predict(boid, target, max_speed), max_speed)
+}
+
+pub fn evade(boid: &Boid, threat: &Boid, max_speed: f32) -> Vec3 {
+    flee(boid, predict(boid, threat, max_speed), max_speed)
+}
+
+fn predict(boid: &Boid, target: &Boid, max_speed: f32) -> Vec3 {
+    let time = (target.position - boid.position).length() / max_speed.max(f32::EPSILON);
+    target.position + target.velocity * time
+}
+
+pub fn separation(boid: &Boid, neighbours: &[Boid], radius: f32) -> Vec3 {
+    neighbours
+        .iter()
+        .map(|neighbour| boid.position - neighbour.position)
+        .filter(|offset| offset.length() < radius && offset.length() > 0.0)
+        .map(|offset| offset / offset.length_squared())
+        .sum::<Vec3>()
+        * radius
+}
+
+pub fn alignment(boid: &Boid, neighbours: &[Boid]) -> Vec3 {
+    if neighbours.is_empty() {
+        return Vec3::ZERO;
+    }
+    let heading = neighbours.iter().map(|n| n.velocity).sum::<Vec3>() / neighbours.len() as f32;
+    heading - boid.velocity
+}
+
+pub fn cohesion(boid: &Boid, neighbours: &[Boid], max_speed: f32) -> Vec3 {
+    if neighbours.is_empty() {
+        return Vec3::ZERO;
+    }
+    let center = neighbours.iter().map(|n| n.position).sum::<Vec3>() / neighbours.len() as f32;
+    seek(boid, center, max_speed)
+}
+
+pub fn avoid_obstacles(boid: &Boid, obstacles: &[Obstacle], look_ahead: f32, margin: f32) -> Vec3 {
+    let speed = boid.velocity.length();
+    if speed == 0.0 {
+        return Vec3::ZERO;
+    }
+    let heading = boid.velocity / speed;
+    let horizon = speed * look_ahead;
+
+    obstacles
+        .iter()
+        .filter_map(|obstacle| {
+            let offset = obstacle.position - boid.position;
+            let along = offset.dot(heading);
+            let lateral = offset - heading * along;
+            let clearance = obstacle.radius + margin;
+
+            if along > 0.0 && along < horizon && lateral.length() < clearance {
+                Some((along, lateral, clearance))
+            } else {
+                None
+            }
+        })
+        .min_by(|(a, _, _), (b, _, _)| a.total_cmp(b))
+        .map(|(along, lateral, clearance)| {
+            let away = if lateral.length() > 0.0 {
+                -lateral.normalize()
+            } else {
+                heading.any_orthonormal_vector()
+            };
+            away * speed * (clearance - lateral.length()) / clearance * (1.0 - along / horizon)
+        })
+        .unwrap_or(Vec3::ZERO)
+}
@@ -1,5 +1,6 @@
 pub mod button_handler;
 pub mod button_trigger;
+pub mod focus;
 pub mod camera;
 pub mod mesh_renderer;
 pub mod particle_emitter;
@@ -12,6 +13,13 @@ pub mod particle_renderer;
 pub mod collider;
 pub mod unit;
 pub mod health_renderer;
+pub mod swarm_agent;
+pub mod behaviour;
+pub mod weapon;
+pub mod countermeasures;
+pub mod lives;
+pub mod ship_systems;
+pub mod layout;
 
 pub trait ComponentType {
     fn component_type_id() -> usize;
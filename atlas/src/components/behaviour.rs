@@ -0,0 +1,15 @@
+use crate::ai::behaviour_tree::{BehaviourTree, Blackboard};
+
+pub struct Behaviour {
+    pub tree: BehaviourTree,
+    pub blackboard: Blackboard,
+}
+
+impl Behaviour {
+    pub fn new(tree: BehaviourTree) -> Self {
+        Self {
+            tree,
+            blackboard: Blackboard::new(),
+        }
+    }
+}
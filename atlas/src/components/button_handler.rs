@@ -3,3 +3,10 @@ use crate::event_bus::EventSender;
 pub trait ButtonHandler {
     fn on_click(&self, event_sender: &mut EventSender);
 }
+
+// For buttons the scene reacts to through `UiEvent::Pressed`
+pub struct NoHandler;
+
+impl ButtonHandler for NoHandler {
+    fn on_click(&self, _: &mut EventSender) {}
+}
@@ -3,31 +3,68 @@ use glam::Vec2;
 use crate::{
     entity_manager::EntityManager,
     event_bus::{EventReader, EventSender},
-    graphics::graphics_context::IoEvent,
+    game_entities::widget::Widget,
+    graphics::graphics_context::{GraphicsContext, IoEvent},
 };
 
+use super::focus::Focus;
+
+#[derive(Clone, Copy)]
 pub struct Rect {
     pos: Vec2,
     size: Vec2,
 }
 
 impl Rect {
+    pub fn new(pos: (f32, f32), size: (f32, f32)) -> Self {
+        Rect {
+            pos: Vec2::new(pos.0, pos.1),
+            size: Vec2::new(size.0, size.1),
+        }
+    }
+
     pub fn centered_quad(pos: (f32, f32), sides: (f32, f32)) -> Self {
         Rect {
             pos: Vec2::new(pos.0 - sides.0, pos.1 - sides.1),
             size: Vec2::new(sides.0 * 2.0, sides.1 * 2.0),
         }
     }
+
+    pub fn pos(&self) -> Vec2 {
+        self.pos
+    }
+
+    pub fn size(&self) -> Vec2 {
+        self.size
+    }
 }
 
 #[derive(Clone)]
 pub enum UiEvent {
     Pressed(String),
+    Changed(String, f32),
+    Toggled(String, bool),
+    Selected(String, usize),
+    // A list row was clicked, or Enter was pressed on the selected one
+    Activated(String, usize),
+    // Escape or the B button with nothing left to close
+    Back,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ButtonState {
+    Normal,
+    Hovered,
+    Pressed,
+    Disabled,
+}
+
+// Triggers on a higher level sit on top and get the cursor first
 pub struct ButtonTrigger {
     bounding_rect: Rect,
     level: usize,
+    pub enabled: bool,
+    pub state: ButtonState,
 }
 
 impl ButtonTrigger {
@@ -35,6 +72,8 @@ impl ButtonTrigger {
         ButtonTrigger {
             level,
             bounding_rect: Rect::centered_quad(pos, size),
+            enabled: true,
+            state: ButtonState::Normal,
         }
     }
 
@@ -44,33 +83,137 @@ impl ButtonTrigger {
         y -= self.bounding_rect.pos.y;
         x >= 0.0 && y >= 0.0 && x <= self.bounding_rect.size.x && y <= self.bounding_rect.size.y
     }
+
+    pub fn rect(&self) -> Rect {
+        self.bounding_rect
+    }
+
+    pub fn set_rect(&mut self, rect: Rect) {
+        self.bounding_rect = rect;
+    }
+
+    pub fn level(&self) -> usize {
+        self.level
+    }
+
+    pub fn set_level(&mut self, level: usize) {
+        self.level = level;
+    }
 }
 
-pub struct ButtonTriggerSystem {}
+// Tracks the widget under the cursor, the widget a press started on and the focused one
+pub struct ButtonTriggerSystem {
+    hovered: Option<usize>,
+    captured: Option<usize>,
+    focus: Focus,
+}
 
 impl ButtonTriggerSystem {
     pub fn new() -> Self {
-        ButtonTriggerSystem {}
+        ButtonTriggerSystem {
+            hovered: None,
+            captured: None,
+            focus: Focus::new(),
+        }
     }
 
     pub fn check_buttons<'a>(
-        &self,
-        entity_manager: &EntityManager,
+        &mut self,
+        entity_manager: &mut EntityManager,
         event_reader: &mut EventReader,
         event_sender: &mut EventSender,
+        graphics_context: &GraphicsContext,
     ) {
+        // Widgets are laid out under a UI camera spanning the whole window
+        let (width, height) = graphics_context.dimensions();
+        let to_ui = |pos| graphics_context.to_ui(pos, (width as f32, height as f32)).into();
+
         event_reader.read(|e| match e {
+            IoEvent::MousePositionChange(pos) => {
+                let pos = to_ui(pos);
+                self.hovered = Self::pick(entity_manager, pos);
+                if let Some(widget) = self.captured_widget(entity_manager) {
+                    widget.drag(pos, event_sender);
+                }
+            }
             IoEvent::LeftMousePress(click_pos) => {
-                Self::check_buttons_for_event(entity_manager, click_pos, event_sender);
+                let click_pos = to_ui(click_pos);
+                self.captured = Self::pick(entity_manager, click_pos);
+                let captured = self.captured;
+                entity_manager
+                    .iter_mut::<Widget>()
+                    .filter(|widget| Some(widget.id) != captured)
+                    .for_each(|widget| {
+                        widget.entity.dismiss();
+                    });
+                if captured.is_some() {
+                    self.focus.set(captured);
+                }
+                if let Some(widget) = self.captured_widget(entity_manager) {
+                    widget.drag(click_pos, event_sender);
+                }
             }
-            _ => {}
+            IoEvent::LeftMouseRelease(click_pos) => {
+                let click_pos = to_ui(click_pos);
+                Self::check_buttons_for_event(
+                    entity_manager,
+                    click_pos,
+                    self.captured.take(),
+                    event_sender,
+                );
+                self.hovered = Self::pick(entity_manager, click_pos);
+            }
+            IoEvent::MouseScroll((_, lines)) => {
+                if let Some(widget) = Self::find(entity_manager, self.hovered) {
+                    widget.scroll(-lines.round() as i32);
+                }
+            }
+            event => self.focus.handle(&event, entity_manager, event_sender),
+        });
+
+        let (hovered, captured, focused) = (self.hovered, self.captured, self.focus.focused());
+        entity_manager.iter_mut::<Widget>().for_each(|widget| {
+            let state = match (captured == Some(widget.id), hovered == Some(widget.id)) {
+                (true, _) => ButtonState::Pressed,
+                (false, true) => ButtonState::Hovered,
+                _ => ButtonState::Normal,
+            };
+            widget.entity.set_focused(focused == Some(widget.id));
+            widget.entity.set_state(state);
         });
     }
 
-    pub fn check_buttons_for_event<'a>(
-        _entity_manager: &EntityManager,
-        (_x, _y): (f32, f32),
-        _event_sender: &mut EventSender,
+    // A click counts when the press and the release land on the same widget
+    pub fn check_buttons_for_event(
+        entity_manager: &mut EntityManager,
+        (x, y): (f32, f32),
+        captured: Option<usize>,
+        event_sender: &mut EventSender,
     ) {
+        let Some(widget) = Self::find(entity_manager, captured) else {return};
+        if widget.trigger.intersects(x, y) {
+            widget.activate((x, y), event_sender);
+        }
+    }
+
+    fn pick(entity_manager: &EntityManager, (x, y): (f32, f32)) -> Option<usize> {
+        entity_manager
+            .iter::<Widget>()
+            .filter(|widget| widget.entity.trigger.enabled)
+            .filter(|widget| widget.entity.trigger.intersects(x, y))
+            .max_by_key(|widget| widget.entity.trigger.level())
+            .map(|widget| widget.id)
+    }
+
+    fn captured_widget<'a>(&self, entity_manager: &'a mut EntityManager) -> Option<&'a mut Widget> {
+        Self::find(entity_manager, self.captured)
+    }
+
+    fn find(entity_manager: &mut EntityManager, id: Option<usize>) -> Option<&mut Widget> {
+        let id = id?;
+        entity_manager
+            .iter_mut::<Widget>()
+            .find(|widget| widget.id == id)
+            .map(|widget| &mut widget.entity)
     }
 }
@@ -9,7 +9,7 @@ use super::transform::Transform;
 
 #[derive(Debug, Clone, Copy)]
 pub enum Frustrum {
-    Perspective((f32, f32, f32, f32, f32, f32)),
+    Perspective((f32, f32, f32, f32, f32, f32, f32)),
     Orthogonal((f32, f32)),
 }
 
@@ -19,7 +19,13 @@ impl Frustrum {
     }
 
     pub fn perspective(width: f32, height: f32, near: f32, far: f32) -> Self {
+        Self::perspective_fov(1.0, width, height, near, far)
+    }
+
+    // Vertical field of view in radians
+    pub fn perspective_fov(fov: f32, width: f32, height: f32, near: f32, far: f32) -> Self {
         Frustrum::Perspective((
+            fov,
             -width * 0.5,
             width * 0.5,
             -height * 0.5,
@@ -52,21 +58,42 @@ pub struct CameraMatrix {
 
 impl Camera {
     pub fn new(frustrum: Frustrum, position: Vec3, direction: Quat) -> Self {
-        let projection = match frustrum {
-            Frustrum::Perspective(persp) => Self::build_perspective_matrix(persp),
-            Frustrum::Orthogonal(orth) => Self::build_orth_matrix(orth),
-        };
         Self {
-            projection,
+            projection: Self::build_projection(frustrum),
             position,
             direction,
         }
     }
 
+    pub fn set_frustrum(&mut self, frustrum: Frustrum) {
+        self.projection = Self::build_projection(frustrum);
+    }
+
+    // Orthogonal camera over the whole window, top left origin with y down
+    pub fn ui((width, height): (i32, i32)) -> Self {
+        Self::new(
+            Frustrum::orthogonal(width as f32, height as f32),
+            Vec3::ZERO,
+            Quat::IDENTITY,
+        )
+    }
+
+        pub fn position(&self) -> Vec3 {
+        self.position
+    }
+
+    pub fn set_position(&mut self, position: Vec3) {
+        self.position = position;
+    }
+
     pub fn rotation(&self) -> Quat{
          self.direction
     }
 
+    pub fn set_rotation(&mut self, direction: Quat) {
+        self.direction = direction;
+    }
+
     pub fn apply_rotation(&mut self, quat: Quat) {
         self.direction = quat * self.direction;
     }
@@ -98,6 +125,13 @@ impl Camera {
             * Mat4::from_translation(-obj.position)
     }
 
+    fn build_projection(frustrum: Frustrum) -> Mat4 {
+        match frustrum {
+            Frustrum::Perspective(persp) => Self::build_perspective_matrix(persp),
+            Frustrum::Orthogonal(orth) => Self::build_orth_matrix(orth),
+        }
+    }
+
     fn build_orth_matrix((width, height): (f32, f32)) -> Mat4 {
         let mut proj = Mat4::IDENTITY;
 
@@ -110,8 +144,8 @@ impl Camera {
     }
 
     fn build_perspective_matrix(
-        (left, right, top, bottom, near, far): (f32, f32, f32, f32, f32, f32),
+        (fov, left, right, top, bottom, near, far): (f32, f32, f32, f32, f32, f32, f32),
     ) -> Mat4 {
-        Mat4::perspective_rh_gl(1.0, (left - right) / (top - bottom), near, far)
+        Mat4::perspective_rh_gl(fov, (left - right) / (top - bottom), near, far)
     }
 }
@@ -0,0 +1,34 @@
+pub struct Countermeasures {
+    pub charges: u32,
+    pub salvo: u32,
+    pub reload: f32,
+    pub signature: f32,
+    pub trigger: bool,
+    cooldown: f32,
+}
+
+impl Countermeasures {
+    pub fn new(charges: u32) -> Self {
+        Self {
+            charges,
+            salvo: 3,
+            reload: 2.0,
+            signature: 3.0,
+            trigger: false,
+            cooldown: 0.0,
+        }
+    }
+
+    // Returns the number of decoys to launch this tick
+    pub fn release(&mut self, delta: f32) -> u32 {
+        self.cooldown = (self.cooldown - delta).max(0.0);
+        if !self.trigger || self.cooldown > 0.0 || self.charges == 0 {
+            return 0;
+        }
+
+        let salvo = self.salvo.min(self.charges);
+        self.charges -= salvo;
+        self.cooldown = self.reload;
+        salvo
+    }
+}
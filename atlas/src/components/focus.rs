@@ -0,0 +1,194 @@
+use glam::Vec2;
+
+use crate::{
+    entity_manager::EntityManager,
+    event_bus::EventSender,
+    game_entities::widget::Widget,
+    graphics::graphics_context::IoEvent,
+    input::key::{Axis, GamepadButton, Key},
+};
+
+use super::button_trigger::UiEvent;
+
+// Stick deflection that counts as one push in that direction
+const STICK_PUSH: f32 = 0.5;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Navigation {
+    Next,
+    Previous,
+    Up,
+    Down,
+    Left,
+    Right,
+    Activate,
+    Back,
+}
+
+// Keyboard and gamepad focus over the enabled widgets, the mouse keeps working alongside it
+pub struct Focus {
+    focused: Option<usize>,
+    shift: bool,
+    stick: Vec2,
+}
+
+impl Focus {
+    pub fn new() -> Self {
+        Focus {
+            focused: None,
+            shift: false,
+            stick: Vec2::ZERO,
+        }
+    }
+
+    pub fn focused(&self) -> Option<usize> {
+        self.focused
+    }
+
+    pub fn set(&mut self, focused: Option<usize>) {
+        self.focused = focused;
+    }
+
+    pub fn handle(
+        &mut self,
+        event: &IoEvent,
+        entity_manager: &mut EntityManager,
+        event_sender: &mut EventSender,
+    ) {
+        if let Some(navigation) = self.navigation(event) {
+            self.navigate(navigation, entity_manager, event_sender);
+        }
+    }
+
+    fn navigation(&mut self, event: &IoEvent) -> Option<Navigation> {
+        match event {
+            IoEvent::KeyPressed(Key::LeftShift | Key::RightShift) => self.shift = true,
+            IoEvent::KeyReleased(Key::LeftShift | Key::RightShift) => self.shift = false,
+            IoEvent::KeyPressed(Key::Tab) if self.shift => return Some(Navigation::Previous),
+            IoEvent::KeyPressed(Key::Tab) => return Some(Navigation::Next),
+            IoEvent::KeyPressed(Key::Up) => return Some(Navigation::Up),
+            IoEvent::KeyPressed(Key::Down) => return Some(Navigation::Down),
+            IoEvent::KeyPressed(Key::Left) => return Some(Navigation::Left),
+            IoEvent::KeyPressed(Key::Right) => return Some(Navigation::Right),
+            IoEvent::KeyPressed(Key::Enter | Key::Space) => return Some(Navigation::Activate),
+            IoEvent::KeyPressed(Key::Escape) => return Some(Navigation::Back),
+            IoEvent::GamepadPressed(button) => {
+                return match button {
+                    GamepadButton::DpadUp => Some(Navigation::Up),
+                    GamepadButton::DpadDown => Some(Navigation::Down),
+                    GamepadButton::DpadLeft => Some(Navigation::Left),
+                    GamepadButton::DpadRight => Some(Navigation::Right),
+                    GamepadButton::LeftBumper => Some(Navigation::Previous),
+                    GamepadButton::RightBumper => Some(Navigation::Next),
+                    GamepadButton::A => Some(Navigation::Activate),
+                    GamepadButton::B => Some(Navigation::Back),
+                    _ => None,
+                }
+            }
+            // The left stick moves focus once per push past the threshold
+            IoEvent::AxisMoved(Axis::LeftX, value) => {
+                let previous = std::mem::replace(&mut self.stick.x, *value);
+                return match (previous.abs() < STICK_PUSH, *value) {
+                    (true, value) if value <= -STICK_PUSH => Some(Navigation::Left),
+                    (true, value) if value >= STICK_PUSH => Some(Navigation::Right),
+                    _ => None,
+                };
+            }
+            IoEvent::AxisMoved(Axis::LeftY, value) => {
+                let previous = std::mem::replace(&mut self.stick.y, *value);
+                return match (previous.abs() < STICK_PUSH, *value) {
+                    (true, value) if value <= -STICK_PUSH => Some(Navigation::Up),
+                    (true, value) if value >= STICK_PUSH => Some(Navigation::Down),
+                    _ => None,
+                };
+            }
+            _ => {}
+        }
+        None
+    }
+
+    pub fn navigate(
+        &mut self,
+        navigation: Navigation,
+        entity_manager: &mut EntityManager,
+        event_sender: &mut EventSender,
+    ) {
+        let current = self.focused.filter(|focused| {
+            entity_manager
+                .iter::<Widget>()
+                .any(|widget| widget.id == *focused && widget.entity.trigger.enabled)
+        });
+        let Some(current) = current else {
+            // The first key press only shows where the focus is
+            match navigation {
+                Navigation::Back => event_sender.write(UiEvent::Back),
+                _ => self.focused = tab_order(entity_manager).first().copied(),
+            }
+            return;
+        };
+        let Some(widget) = entity_manager.iter_mut::<Widget>().find(|widget| widget.id == current) else {return};
+        let widget = &mut widget.entity;
+
+        let direction = match navigation {
+            Navigation::Next | Navigation::Previous => {
+                let order = tab_order(entity_manager);
+                let index = order.iter().position(|id| *id == current).unwrap_or(0);
+                let step = if navigation == Navigation::Next { 1 } else { order.len() - 1 };
+                self.focused = order.get((index + step) % order.len().max(1)).copied();
+                return;
+            }
+            Navigation::Activate => return widget.press(event_sender),
+            Navigation::Back => {
+                if !widget.dismiss() {
+                    event_sender.write(UiEvent::Back);
+                }
+                return;
+            }
+            Navigation::Up => (0, -1),
+            Navigation::Down => (0, 1),
+            Navigation::Left => (-1, 0),
+            Navigation::Right => (1, 0),
+        };
+
+        if !widget.step(direction, event_sender) {
+            let direction = Vec2::new(direction.0 as f32, direction.1 as f32);
+            if let Some(next) = nearest(entity_manager, current, direction) {
+                self.focused = Some(next);
+            }
+        }
+    }
+}
+
+fn tab_order(entity_manager: &EntityManager) -> Vec<usize> {
+    let mut widgets: Vec<_> = entity_manager
+        .iter::<Widget>()
+        .filter(|widget| widget.entity.trigger.enabled)
+        .map(|widget| (widget.entity.tab_order, widget.id))
+        .collect();
+    widgets.sort();
+    widgets.into_iter().map(|(_, id)| id).collect()
+}
+
+// Closest enabled widget on that side, widgets off to the side count as further away
+fn nearest(entity_manager: &EntityManager, current: usize, direction: Vec2) -> Option<usize> {
+    let center = |widget: &Widget| {
+        let rect = widget.trigger.rect();
+        rect.pos() + rect.size() * 0.5
+    };
+    let from = entity_manager
+        .iter::<Widget>()
+        .find(|widget| widget.id == current)
+        .map(|widget| center(&widget.entity))?;
+
+    entity_manager
+        .iter::<Widget>()
+        .filter(|widget| widget.id != current && widget.entity.trigger.enabled)
+        .filter_map(|widget| {
+            let offset = center(&widget.entity) - from;
+            let along = offset.dot(direction);
+            let across = offset.perp_dot(direction).abs();
+            (along > 0.0).then(|| (along + across * 2.0, widget.id))
+        })
+        .min_by(|(a, _), (b, _)| a.total_cmp(b))
+        .map(|(_, id)| id)
+}
@@ -6,6 +6,7 @@ use crate::graphics::{
 pub struct HealthRenderer {
     pub mesh: Mesh<P2TVertex, TriangleGeometry>,
     pub health: f32,
+    pub shield: f32,
     pub enabled: bool,
 }
 
@@ -16,6 +17,7 @@ impl HealthRenderer {
         Self {
             mesh,
             health,
+            shield: 0.0,
             enabled: false,
         }
     }
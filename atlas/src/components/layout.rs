@@ -0,0 +1,83 @@
+use glam::Vec2;
+
+// Anchors and pivots are fractions of a rect, (0, 0) is its top left corner
+pub const TOP_LEFT: Vec2 = Vec2::new(0.0, 0.0);
+pub const TOP: Vec2 = Vec2::new(0.5, 0.0);
+pub const TOP_RIGHT: Vec2 = Vec2::new(1.0, 0.0);
+pub const LEFT: Vec2 = Vec2::new(0.0, 0.5);
+pub const CENTER: Vec2 = Vec2::new(0.5, 0.5);
+pub const RIGHT: Vec2 = Vec2::new(1.0, 0.5);
+pub const BOTTOM_LEFT: Vec2 = Vec2::new(0.0, 1.0);
+pub const BOTTOM: Vec2 = Vec2::new(0.5, 1.0);
+pub const BOTTOM_RIGHT: Vec2 = Vec2::new(1.0, 1.0);
+
+#[derive(Clone, Copy, Debug)]
+pub enum Length {
+    // Unscaled UI units, multiplied by the UI scale
+    Px(f32),
+    // Share of the parent's inner size
+    Percent(f32),
+}
+
+impl Length {
+    pub fn resolve(&self, parent: f32, scale: f32) -> f32 {
+        match self {
+            Length::Px(px) => px * scale,
+            Length::Percent(percent) => parent * percent / 100.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Direction {
+    Horizontal,
+    Vertical,
+}
+
+// Where an element sits inside its parent, the pivot point of the element is put on the anchor
+#[derive(Clone, Copy, Debug)]
+pub struct Layout {
+    pub anchor: Vec2,
+    pub pivot: Vec2,
+    pub offset: (Length, Length),
+    pub size: (Length, Length),
+    pub padding: f32,
+    // Children of a stack are laid one after another instead of by their anchors
+    pub stack: Option<(Direction, f32)>,
+}
+
+impl Layout {
+    pub fn new(anchor: Vec2, size: (Length, Length)) -> Self {
+        Layout {
+            anchor,
+            pivot: anchor,
+            offset: (Length::Px(0.0), Length::Px(0.0)),
+            size,
+            padding: 0.0,
+            stack: None,
+        }
+    }
+
+    pub fn anchored(anchor: Vec2, (x, y): (f32, f32), (width, height): (f32, f32)) -> Self {
+        Layout {
+            offset: (Length::Px(x), Length::Px(y)),
+            ..Self::new(anchor, (Length::Px(width), Length::Px(height)))
+        }
+    }
+
+    pub fn stack(
+        anchor: Vec2,
+        size: (Length, Length),
+        direction: Direction,
+        spacing: f32,
+    ) -> Self {
+        Layout {
+            stack: Some((direction, spacing)),
+            ..Self::new(anchor, size)
+        }
+    }
+
+    pub fn full() -> Self {
+        Self::new(TOP_LEFT, (Length::Percent(100.0), Length::Percent(100.0)))
+    }
+}
@@ -0,0 +1,66 @@
+use glam::Vec3;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum PilotState {
+    Alive,
+    Respawning(f32),
+    GameOver(f32),
+}
+
+pub struct Lives {
+    pub remaining: u32,
+    pub lost: u32,
+    pub checkpoint: Vec3,
+    pub respawn_delay: f32,
+    pub game_over_delay: f32,
+    pub spawn_protection: f32,
+    state: PilotState,
+}
+
+impl Lives {
+    pub fn new(remaining: u32, checkpoint: Vec3) -> Self {
+        Self {
+            remaining,
+            lost: 0,
+            checkpoint,
+            respawn_delay: 3.0,
+            game_over_delay: 4.0,
+            spawn_protection: 2.0,
+            state: PilotState::Alive,
+        }
+    }
+
+    pub fn state(&self) -> PilotState {
+        self.state
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.state == PilotState::Alive
+    }
+
+    pub fn kill(&mut self) {
+        self.remaining = self.remaining.saturating_sub(1);
+        self.lost += 1;
+        self.state = match self.remaining {
+            0 => PilotState::GameOver(self.game_over_delay),
+            _ => PilotState::Respawning(self.respawn_delay),
+        };
+    }
+
+    // Returns true on the tick the current death timer runs out
+    pub fn update(&mut self, delta: f32) -> bool {
+        let (PilotState::Respawning(timer) | PilotState::GameOver(timer)) = &mut self.state else {return false};
+        if *timer <= 0.0 {
+            return false;
+        }
+        *timer -= delta;
+        if *timer > 0.0 {
+            return false;
+        }
+
+        if let PilotState::Respawning(_) = self.state {
+            self.state = PilotState::Alive;
+        }
+        true
+    }
+}
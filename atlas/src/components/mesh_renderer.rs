@@ -33,6 +33,7 @@ impl<'a> ComponentIteratorGenerator<'a, (&'a Transform, &'a Model)> for EntityMa
             .map(|ship| (&ship.transform, &ship.entity.mesh));
         let players = self
             .iter::<PlayerShip>()
+            .filter(|ship| ship.entity.lives.is_alive())
             .map(|ship| (&ship.transform, &ship.entity.mesh));
         let asteroids = self
             .iter::<AsteroidEntity>()
@@ -46,6 +46,8 @@ pub struct ParticleEmitter {
     pub particles: Vec<Particle>,
     pub particle_instances: Vec<ParticleInstance>,
     pub since_last_spawn: f32,
+    // Scales the spawn rate, capped by the particle count of the definition
+    pub intensity: f32,
 }
 
 impl ParticleEmitter {
@@ -62,6 +64,7 @@ impl ParticleEmitter {
             mesh,
             definition,
             since_last_spawn: 0.0,
+            intensity: 1.0,
             spawner,
         }
     }
@@ -1,6 +1,9 @@
 use crate::{
     entity_manager::{ComponentIteratorGenerator, EntityManager},
-    game_entities::{enemy_ship::EnemyShip, explosion::Explosion, player_ship::PlayerShip},
+    game_entities::{
+        bullet::BulletEntity, decoy::Decoy, enemy_ship::EnemyShip, explosion::Explosion,
+        pickup::Pickup, player_ship::PlayerShip,
+    },
     graphics::{context::Context, shaders::particle_shader::ParticleShaderDefinition},
 };
 
@@ -21,8 +24,25 @@ impl<'a> ComponentIteratorGenerator<'a, (&'a Transform, &'a ParticleEmitter)> fo
         let explosions = self
             .iter::<Explosion>()
             .map(|explosion| (&explosion.transform, &explosion.entity.explosion));
+        let missiles = self.iter::<BulletEntity>().filter_map(|bullet| {
+            let missile = bullet.entity.missile.as_ref()?;
+            Some((&bullet.transform, &missile.exhaust))
+        });
+        let decoys = self
+            .iter::<Decoy>()
+            .map(|decoy| (&decoy.transform, &decoy.entity.flare));
+        let pickups = self
+            .iter::<Pickup>()
+            .map(|pickup| (&pickup.transform, &pickup.entity.glow));
 
-        Box::new(players.chain(enemies).chain(explosions))
+        Box::new(
+            players
+                .chain(enemies)
+                .chain(explosions)
+                .chain(missiles)
+                .chain(decoys)
+                .chain(pickups),
+        )
     }
 }
 
@@ -1,4 +1,4 @@
-use glam::Vec3;
+use glam::{Quat, Vec3};
 
 use super::transform::Transform;
 
@@ -7,6 +7,7 @@ pub enum PhysicalInteraction {
     ForceApplied(f32, Vec3),
 }
 
+#[derive(Clone)]
 pub struct PhysicalBody {
     pub mass: f32,
     pub momentum: Vec3,
@@ -49,6 +50,10 @@ impl PhysicalBody {
 
     pub fn update<'a>(&mut self, delta: f32, transform: &mut Transform) {
         transform.position += self.position_delta(delta);
+        let spin = self.angular_velocity() * delta;
+        if spin != Vec3::ZERO {
+            transform.rotation = (Quat::from_scaled_axis(spin) * transform.rotation).normalize();
+        }
 
         self.momentum += self.resultant_force ;
         self.angular_momentum += self.resultant_ang_force * delta;
@@ -57,6 +62,10 @@ impl PhysicalBody {
         self.resultant_ang_force = Vec3::ZERO;
     }
 
+    pub fn torque(&mut self, torque: Vec3) {
+        self.resultant_ang_force += torque;
+    }
+
     pub fn impulse(&mut self, force: Vec3) {
         self.resultant_force += force;
         //self.momentum += force;
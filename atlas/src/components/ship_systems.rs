@@ -0,0 +1,165 @@
+use std::path::PathBuf;
+
+use crate::{
+    game_root::GameError,
+    resource_manager::{definition::Definition, try_get_file, ResourceLoader},
+};
+
+// Capacitor, heat sink and afterburner of a ship. Weapons and the boost draw energy,
+// boosting and firing heat the ship up and an overheated ship has to cool down completely
+#[derive(Clone)]
+pub struct ShipSystems {
+    pub thrust: f32,
+    pub strafe: f32,
+    pub torque: f32,
+    pub turn_rate: f32,
+    pub energy: f32,
+    pub capacity: f32,
+    pub recharge: f32,
+    pub heat: f32,
+    pub heat_capacity: f32,
+    pub cooling: f32,
+    pub boost_multiplier: f32,
+    pub boost_drain: f32,
+    pub boost_heat: f32,
+    pub shot_heat: f32,
+    pub boost: bool,
+    pub flight_assist: bool,
+    overheated: bool,
+}
+
+impl ShipSystems {
+    pub fn new(thrust: f32, capacity: f32, recharge: f32, heat_capacity: f32, cooling: f32) -> Self {
+        Self {
+            thrust,
+            strafe: 1.0,
+            torque: 0.0,
+            turn_rate: 0.0,
+            energy: capacity,
+            capacity,
+            recharge,
+            heat: 0.0,
+            heat_capacity,
+            cooling,
+            boost_multiplier: 1.0,
+            boost_drain: 0.0,
+            boost_heat: 0.0,
+            shot_heat: 0.0,
+            boost: false,
+            flight_assist: true,
+            overheated: false,
+        }
+    }
+
+    pub fn restore(&mut self) {
+        self.energy = self.capacity;
+        self.heat = 0.0;
+        self.overheated = false;
+    }
+
+    pub fn is_overheated(&self) -> bool {
+        self.overheated
+    }
+
+    pub fn is_boosting(&self) -> bool {
+        self.boost && !self.overheated && self.energy > 0.0
+    }
+
+    pub fn thrust(&self) -> f32 {
+        match self.is_boosting() {
+            true => self.thrust * self.boost_multiplier,
+            false => self.thrust,
+        }
+    }
+
+    pub fn energy_fraction(&self) -> f32 {
+        fraction(self.energy, self.capacity)
+    }
+
+    pub fn heat_fraction(&self) -> f32 {
+        fraction(self.heat, self.heat_capacity)
+    }
+
+    // Takes the energy for a volley, returns false when the capacitor or the heat sink can't take it
+    pub fn draw(&mut self, energy: f32) -> bool {
+        if self.overheated || self.energy < energy {
+            return false;
+        }
+        self.energy -= energy;
+        self.add_heat(self.shot_heat);
+        true
+    }
+
+    // The capacitor only recharges while the afterburner is off
+    pub fn update(&mut self, delta: f32) {
+        match self.is_boosting() {
+            true => {
+                self.energy = (self.energy - self.boost_drain * delta).max(0.0);
+                self.add_heat(self.boost_heat * delta);
+            }
+            false => self.energy = (self.energy + self.recharge * delta).min(self.capacity),
+        }
+
+        self.heat = (self.heat - self.cooling * delta).max(0.0);
+        if self.heat == 0.0 {
+            self.overheated = false;
+        }
+    }
+
+    fn add_heat(&mut self, heat: f32) {
+        self.heat = (self.heat + heat).min(self.heat_capacity);
+        if self.heat >= self.heat_capacity {
+            self.overheated = true;
+        }
+    }
+}
+
+impl Default for ShipSystems {
+    fn default() -> Self {
+        let mut systems = Self::new(2.5, 100.0, 20.0, 100.0, 15.0);
+        systems.strafe = 0.5;
+        systems.torque = 600.0;
+        systems.turn_rate = 2.0;
+        systems.boost_multiplier = 2.5;
+        systems.boost_drain = 25.0;
+        systems.boost_heat = 20.0;
+        systems.shot_heat = 4.0;
+        systems
+    }
+}
+
+impl ResourceLoader for ShipSystems {
+    type Resource = ShipSystems;
+
+    fn is_resource(path: &PathBuf) -> bool {
+        path.extension().map_or(false, |e| e == "ship")
+    }
+
+    fn load_resource(contents: &[PathBuf]) -> Result<Self::Resource, GameError> {
+        let definition = Definition::load(try_get_file("systems", contents)?)?;
+        let default = Self::default();
+
+        let mut systems = Self::new(
+            definition.value_or("thrust", default.thrust)?,
+            definition.value_or("energy", default.capacity)?,
+            definition.value_or("recharge", default.recharge)?,
+            definition.value_or("heat", default.heat_capacity)?,
+            definition.value_or("cooling", default.cooling)?,
+        );
+        systems.strafe = definition.value_or("strafe", default.strafe)?;
+        systems.torque = definition.value_or("torque", default.torque)?;
+        systems.turn_rate = definition.value_or("turn_rate", default.turn_rate)?;
+        systems.boost_multiplier = definition.value_or("boost", default.boost_multiplier)?;
+        systems.boost_drain = definition.value_or("boost_drain", default.boost_drain)?;
+        systems.boost_heat = definition.value_or("boost_heat", default.boost_heat)?;
+        systems.shot_heat = definition.value_or("shot_heat", default.shot_heat)?;
+        Ok(systems)
+    }
+}
+
+fn fraction(value: f32, capacity: f32) -> f32 {
+    match capacity > 0.0 {
+        true => value / capacity,
+        false => 0.0,
+    }
+}
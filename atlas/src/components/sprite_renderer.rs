@@ -1,6 +1,8 @@
+use glam::Vec4;
+
 use crate::{
     entity_manager::{ComponentIteratorGenerator, EntityManager},
-    game_entities::{hud::HudEntity, sprite::Sprite},
+    game_entities::{hud::HudEntity, sprite::Sprite, widget::Widget},
     graphics::{
         context::Context,
         material::sprite_material::SpriteMaterial,
@@ -20,6 +22,7 @@ use super::{camera::Camera, transform::Transform};
 pub struct SpriteRenderer {
     pub quad: Mesh<P2TVertex, TriangleGeometry>,
     pub material: SpriteMaterial,
+    pub tint: Vec4,
 }
 
 impl SpriteRenderer {
@@ -28,15 +31,19 @@ impl SpriteRenderer {
         SpriteRenderer {
             quad: Mesh::new(&vertices, &indices),
             material,
+            tint: Vec4::ONE,
         }
     }
 
     pub fn crosshair(material: SpriteMaterial) -> Self {
         let (vertices, indices) = crosshair::crosshair();
         let quad = Mesh::new(&vertices, &indices);
-        Self { quad, material }
+        Self {
+            quad,
+            material,
+            tint: Vec4::ONE,
+        }
     }
-
 }
 
 pub struct SpriteRendererSystem {
@@ -49,13 +56,25 @@ impl SpriteRendererSystem {
     }
 }
 
-impl<'a> ComponentIteratorGenerator<'a, (&'a Transform, &'a SpriteRenderer)> for EntityManager {
-    fn get_view(&'a self) -> Box<dyn Iterator<Item = (&'a Transform, &'a SpriteRenderer)> + 'a> {
+// Painter's order, plain sprites first and widgets from the lowest level up
+impl<'a> ComponentIteratorGenerator<'a, (Transform, &'a SpriteRenderer)> for EntityManager {
+    fn get_view(&'a self) -> Box<dyn Iterator<Item = (Transform, &'a SpriteRenderer)> + 'a> {
         let huds = self
             .iter::<HudEntity>()
-            .map(|hud_entity| (&hud_entity.transform, &hud_entity.entity.crosshair));
+            .filter(|hud_entity| hud_entity.entity.visible)
+            .map(|hud_entity| (hud_entity.transform, &hud_entity.entity.crosshair));
+
+        let sprites = self
+            .iter::<Sprite>()
+            .map(|sprite| (sprite.transform, &sprite.entity.renderer));
+
+        let mut widgets: Vec<_> = self.iter::<Widget>().map(|widget| &widget.entity).collect();
+        widgets.sort_by_key(|widget| widget.trigger.level());
+        let widgets = widgets
+            .into_iter()
+            .map(|widget| (widget.background_transform(), &widget.background));
 
-        Box::new(huds)
+        Box::new(sprites.chain(widgets).chain(huds))
     }
 }
 
@@ -65,11 +84,12 @@ impl SpriteRendererSystem {
         let sprites = entity_manager.get_view();
 
         context.use_shader(&self.shader, |context| {
-            sprites.for_each(|(transform, shape): (&Transform, &SpriteRenderer)| {
+            sprites.for_each(|(transform, shape): (Transform, &SpriteRenderer)| {
                 context.use_material(&shape.material, |context| {
                     let mvp = projection * transform.model();
                     context.shader.projection_view(&mvp);
                     context.shader.sprite(0);
+                    context.shader.tint(shape.tint);
                     shape.quad.render();
                 });
             });
@@ -0,0 +1,22 @@
+use glam::Vec3;
+
+#[derive(Clone, Copy)]
+pub enum SteeringGoal {
+    Idle,
+    Seek(Vec3),
+    Arrive(Vec3),
+    Flee(Vec3),
+    Pursue(usize),
+    Evade(usize),
+}
+
+pub struct SwarmAgent {
+    pub squad: usize,
+    pub goal: SteeringGoal,
+}
+
+impl SwarmAgent {
+    pub fn new(squad: usize, goal: SteeringGoal) -> Self {
+        Self { squad, goal }
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     entity_manager::{ComponentIteratorGenerator, EntityManager},
-    game_entities::{hud::HudEntity, ui_label::UiLabel},
+    game_entities::{hud::HudEntity, ui_label::UiLabel, widget::Widget},
     graphics::{
         context::{Context, MaterialContext},
         mesh::Mesh,
@@ -46,6 +46,10 @@ impl TextRenderer {
         &self.primitive
     }
 
+    pub fn set_transform(&mut self, transform: Transform) {
+        self.transform = transform;
+    }
+
     pub fn get_text(&self) -> String {
         self.text.clone()
     }
@@ -65,7 +69,7 @@ impl<'a> ComponentIteratorGenerator<'a, (Transform, &'a TextRenderer)> for Entit
             )
         });
 
-        let huds = self.iter::<HudEntity>().map(|hud| {
+        let huds = self.iter::<HudEntity>().filter(|hud| hud.entity.visible).map(|hud| {
             [
                 (
                     hud.transform.compose(&hud.entity.velocity.transform),
@@ -79,10 +83,34 @@ impl<'a> ComponentIteratorGenerator<'a, (Transform, &'a TextRenderer)> for Entit
                     hud.transform.compose(&hud.entity.mass.transform),
                     &hud.entity.mass,
                 ),
+                (
+                    hud.transform.compose(&hud.entity.status.transform),
+                    &hud.entity.status,
+                ),
+                (
+                    hud.transform.compose(&hud.entity.systems.transform),
+                    &hud.entity.systems,
+                ),
+                (
+                    hud.transform.compose(&hud.entity.score.transform),
+                    &hud.entity.score,
+                ),
+                (
+                    hud.transform.compose(&hud.entity.mission.transform),
+                    &hud.entity.mission,
+                ),
             ]
         });
 
-        Box::new(labels.chain(huds.flatten()))
+        let mut widgets: Vec<_> = self.iter::<Widget>().map(|widget| &widget.entity).collect();
+        widgets.sort_by_key(|widget| widget.trigger.level());
+        let rows = widgets.into_iter().flat_map(|widget| {
+            widget.rows.iter().enumerate().map(move |(index, row)| {
+                (widget.row_transform(index).compose(&row.transform), row)
+            })
+        });
+
+        Box::new(labels.chain(huds.flatten()).chain(rows))
     }
 }
 
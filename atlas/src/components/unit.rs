@@ -1,8 +1,158 @@
+use glam::Vec3;
+
+use super::transform::Transform;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum DamageType {
+    Kinetic,
+    Energy,
+    Explosive,
+}
+
+#[derive(Clone, Copy)]
+pub struct Damage {
+    pub amount: f32,
+    pub kind: DamageType,
+    pub position: Vec3,
+    pub source: Option<usize>,
+}
+
+#[derive(Clone, Copy)]
+pub struct Shield {
+    pub strength: f32,
+    pub capacity: f32,
+    pub recharge_rate: f32,
+    pub recharge_delay: f32,
+    cooldown: f32,
+}
+
+// Fraction of hull damage ignored for each damage type
+#[derive(Clone, Copy, Default)]
+pub struct Armour {
+    pub kinetic: f32,
+    pub energy: f32,
+    pub explosive: f32,
+}
+
+#[derive(Clone, Copy)]
+pub struct HitZones {
+    pub front: f32,
+    pub side: f32,
+    pub rear: f32,
+}
+
+#[derive(Clone)]
 pub struct Unit {
     pub name: String,
     pub unit_type: String,
     pub health: f32,
     pub max_health: f32,
+    pub shield: Shield,
+    pub armour: Armour,
+    pub hit_zones: HitZones,
+    pub invulnerability: f32,
+    pub last_attacker: Option<usize>,
+    invulnerable: f32,
+}
+
+impl Damage {
+    pub fn new(amount: f32, kind: DamageType, position: Vec3, source: Option<usize>) -> Self {
+        Self {
+            amount,
+            kind,
+            position,
+            source,
+        }
+    }
+}
+
+impl DamageType {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "kinetic" => Some(Self::Kinetic),
+            "energy" => Some(Self::Energy),
+            "explosive" => Some(Self::Explosive),
+            _ => None,
+        }
+    }
+}
+
+impl Shield {
+    pub fn new(capacity: f32, recharge_rate: f32, recharge_delay: f32) -> Self {
+        Self {
+            strength: capacity,
+            capacity,
+            recharge_rate,
+            recharge_delay,
+            cooldown: 0.0,
+        }
+    }
+
+    pub fn none() -> Self {
+        Self::new(0.0, 0.0, 0.0)
+    }
+
+    pub fn fraction(&self) -> f32 {
+        match self.capacity > 0.0 {
+            true => self.strength / self.capacity,
+            false => 0.0,
+        }
+    }
+
+    // Returns whatever the shield could not soak up
+    fn absorb(&mut self, amount: f32) -> f32 {
+        self.cooldown = self.recharge_delay;
+        let absorbed = amount.min(self.strength);
+        self.strength -= absorbed;
+        amount - absorbed
+    }
+
+    fn update(&mut self, delta: f32) {
+        self.cooldown = (self.cooldown - delta).max(0.0);
+        if self.cooldown == 0.0 {
+            self.strength = (self.strength + self.recharge_rate * delta).min(self.capacity);
+        }
+    }
+}
+
+impl Armour {
+    pub fn new(kinetic: f32, energy: f32, explosive: f32) -> Self {
+        Self {
+            kinetic,
+            energy,
+            explosive,
+        }
+    }
+
+    pub fn resistance(&self, kind: DamageType) -> f32 {
+        match kind {
+            DamageType::Kinetic => self.kinetic,
+            DamageType::Energy => self.energy,
+            DamageType::Explosive => self.explosive,
+        }
+    }
+}
+
+impl HitZones {
+    // Hits are classified by the angle between the unit's nose and the point of impact
+    pub fn multiplier(&self, transform: &Transform, position: Vec3) -> f32 {
+        let facing = transform.rotation * Vec3::NEG_Z;
+        match facing.dot((position - transform.position).normalize_or_zero()) {
+            alignment if alignment > 0.5 => self.front,
+            alignment if alignment < -0.5 => self.rear,
+            _ => self.side,
+        }
+    }
+}
+
+impl Default for HitZones {
+    fn default() -> Self {
+        Self {
+            front: 1.0,
+            side: 1.0,
+            rear: 1.0,
+        }
+    }
 }
 
 impl Unit {
@@ -12,6 +162,49 @@ impl Unit {
             unit_type: String::from(unit_type),
             health: max_health,
             max_health,
+            shield: Shield::none(),
+            armour: Armour::default(),
+            hit_zones: HitZones::default(),
+            invulnerability: 0.0,
+            last_attacker: None,
+            invulnerable: 0.0,
+        }
+    }
+
+    // Back to full hull and shields, the previous attacker is forgotten
+    pub fn restore(&mut self) {
+        self.health = self.max_health;
+        self.shield.strength = self.shield.capacity;
+        self.last_attacker = None;
+    }
+
+    pub fn protect(&mut self, duration: f32) {
+        self.invulnerable = self.invulnerable.max(duration);
+    }
+
+    pub fn is_invulnerable(&self) -> bool {
+        self.invulnerable > 0.0
+    }
+
+    // Shields take the raw damage, only the remainder is reduced by armour and hits the hull.
+    // Returns true when the hit destroyed the unit
+    pub fn damage(&mut self, damage: &Damage, transform: &Transform) -> bool {
+        if self.is_invulnerable() || self.health < 0.0 {
+            return false;
         }
+        self.last_attacker = damage.source.or(self.last_attacker);
+
+        let amount = damage.amount * self.hit_zones.multiplier(transform, damage.position);
+        let hull = self.shield.absorb(amount) * (1.0 - self.armour.resistance(damage.kind));
+        if hull > 0.0 {
+            self.health -= hull;
+            self.invulnerable = self.invulnerability;
+        }
+        self.health < 0.0
+    }
+
+    pub fn update(&mut self, delta: f32) {
+        self.invulnerable = (self.invulnerable - delta).max(0.0);
+        self.shield.update(delta);
     }
 }
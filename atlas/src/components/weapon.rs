@@ -0,0 +1,309 @@
+use std::path::PathBuf;
+
+use glam::Vec3;
+
+use crate::{
+    components::unit::DamageType,
+    game_root::GameError,
+    resource_manager::{definition::Definition, try_get_file, ResourceLoader},
+};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ProjectileKind {
+    Plasma,
+    Rail,
+    Missile,
+}
+
+#[derive(Clone, Copy)]
+pub struct Guidance {
+    pub navigation: f32,
+    pub turn_rate: f32,
+    pub thrust: f32,
+    pub fuel: f32,
+    pub proximity: f32,
+    pub blast_radius: f32,
+}
+
+#[derive(Clone)]
+pub struct Projectile {
+    pub kind: ProjectileKind,
+    pub speed: f32,
+    pub damage: f32,
+    pub damage_type: DamageType,
+    pub lifetime: f32,
+    pub radius: f32,
+    pub pellets: u32,
+    pub guidance: Guidance,
+    pub colour: Vec3,
+    pub explosion: String,
+}
+
+#[derive(Clone)]
+pub struct Weapon {
+    pub projectile: Projectile,
+    pub fire_rate: f32,
+    pub muzzles: Vec<Vec3>,
+    pub spread: f32,
+    pub ammo: Option<u32>,
+    pub heat: f32,
+    pub heat_per_shot: f32,
+    pub cooling_rate: f32,
+    pub energy: f32,
+    pub trigger: bool,
+    pub aim: Option<Vec3>,
+    pub target: Option<usize>,
+    cooldown: f32,
+    overheated: bool,
+}
+
+pub struct Arsenal {
+    pub lock: Option<usize>,
+    pub fired: u32,
+    overcharge: f32,
+    weapons: Vec<Weapon>,
+    selected: usize,
+}
+
+impl Default for Guidance {
+    fn default() -> Self {
+        Self {
+            navigation: 4.0,
+            turn_rate: 2.5,
+            thrust: 60.0,
+            fuel: 4.0,
+            proximity: 3.0,
+            blast_radius: 8.0,
+        }
+    }
+}
+
+impl Guidance {
+    fn load(definition: &Definition) -> Result<Self, GameError> {
+        let default = Self::default();
+        Ok(Self {
+            navigation: definition.value_or("navigation", default.navigation)?,
+            turn_rate: definition.value_or("turn_rate", default.turn_rate)?,
+            thrust: definition.value_or("thrust", default.thrust)?,
+            fuel: definition.value_or("fuel", default.fuel)?,
+            proximity: definition.value_or("proximity", default.proximity)?,
+            blast_radius: definition.value_or("blast_radius", default.blast_radius)?,
+        })
+    }
+}
+
+impl Projectile {
+    pub fn plasma() -> Self {
+        Self {
+            kind: ProjectileKind::Plasma,
+            speed: 256.0,
+            damage: 10.0,
+            damage_type: DamageType::Energy,
+            lifetime: 5.0,
+            radius: 0.5,
+            pellets: 1,
+            guidance: Guidance::default(),
+            colour: Vec3::new(0.0, 1.0, 0.0),
+            explosion: String::from("plasma"),
+        }
+    }
+
+    // Hitscan projectiles cover their whole flight in a single tick
+    pub fn range(&self) -> f32 {
+        self.speed * self.lifetime
+    }
+
+    fn load(definition: &Definition) -> Result<Self, GameError> {
+        let default = Self::plasma();
+        let kind = match definition
+            .value_or("projectile", String::from("plasma"))?
+            .as_str()
+        {
+            "plasma" => ProjectileKind::Plasma,
+            "rail" => ProjectileKind::Rail,
+            "missile" => ProjectileKind::Missile,
+            kind => return GameError::err(format!("Unknown projectile kind '{}'", kind)),
+        };
+        let damage_type = match definition.has("damage_type") {
+            true => {
+                let name = definition.string("damage_type")?;
+                DamageType::parse(name)
+                    .ok_or(GameError::new(&format!("Unknown damage type '{}'", name)))?
+            }
+            false => match kind {
+                ProjectileKind::Plasma => DamageType::Energy,
+                ProjectileKind::Rail => DamageType::Kinetic,
+                ProjectileKind::Missile => DamageType::Explosive,
+            },
+        };
+
+        Ok(Self {
+            kind,
+            speed: definition.value("speed")?,
+            damage: definition.value("damage")?,
+            damage_type,
+            lifetime: definition.value("lifetime")?,
+            radius: definition.value_or("radius", default.radius)?,
+            pellets: definition.value_or("pellets", default.pellets)?,
+            guidance: Guidance::load(definition)?,
+            colour: match definition.has("colour") {
+                true => definition.vec3("colour")?,
+                false => default.colour,
+            },
+            explosion: definition.value_or("explosion", default.explosion)?,
+        })
+    }
+}
+
+impl Weapon {
+    pub fn new(projectile: Projectile, fire_rate: f32, muzzles: Vec<Vec3>) -> Self {
+        Self {
+            projectile,
+            fire_rate,
+            muzzles,
+            spread: 0.0,
+            ammo: None,
+            heat: 0.0,
+            heat_per_shot: 0.0,
+            cooling_rate: 0.0,
+            energy: 0.0,
+            trigger: false,
+            aim: None,
+            target: None,
+            cooldown: 0.0,
+            overheated: false,
+        }
+    }
+
+    pub fn update(&mut self, delta: f32) {
+        self.cooldown = (self.cooldown - delta).max(0.0);
+        self.heat = (self.heat - self.cooling_rate * delta).max(0.0);
+        if self.heat == 0.0 {
+            self.overheated = false;
+        }
+    }
+
+    pub fn is_overheated(&self) -> bool {
+        self.overheated
+    }
+
+    pub fn is_firing(&self) -> bool {
+        self.trigger && self.can_fire()
+    }
+
+    pub fn can_fire(&self) -> bool {
+        self.cooldown == 0.0 && !self.overheated && self.ammo != Some(0)
+    }
+
+    // Consumes a volley worth of ammo and heat, the caller spawns one projectile per muzzle
+    pub fn fire(&mut self) -> bool {
+        if !self.is_firing() {
+            return false;
+        }
+
+        self.cooldown = 1.0 / self.fire_rate;
+        self.ammo = self.ammo.map(|ammo| ammo - 1);
+        self.heat += self.heat_per_shot;
+        if self.heat >= 1.0 {
+            self.overheated = true;
+        }
+        true
+    }
+}
+
+impl Arsenal {
+    pub fn new(weapons: Vec<Weapon>) -> Self {
+        Self {
+            lock: None,
+            fired: 0,
+            overcharge: 0.0,
+            weapons,
+            selected: 0,
+        }
+    }
+
+    pub fn select(&mut self, slot: usize) {
+        if slot < self.weapons.len() && slot != self.selected {
+            self.weapons[self.selected].trigger = false;
+            self.selected = slot;
+        }
+    }
+
+    // Steps through the slots, wrapping around at either end
+    pub fn cycle(&mut self, step: i32) {
+        let count = self.weapons.len() as i32;
+        self.select((self.selected as i32 + step).rem_euclid(count) as usize);
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn current(&self) -> &Weapon {
+        &self.weapons[self.selected]
+    }
+
+    pub fn current_mut(&mut self) -> &mut Weapon {
+        &mut self.weapons[self.selected]
+    }
+
+    // Only weapons with limited ammo take the extra volleys
+    pub fn resupply(&mut self, volleys: u32) {
+        self.weapons
+            .iter_mut()
+            .for_each(|weapon| weapon.ammo = weapon.ammo.map(|ammo| ammo + volleys));
+    }
+
+    pub fn overcharge(&mut self, duration: f32) {
+        self.overcharge = self.overcharge.max(duration);
+    }
+
+    pub fn is_overcharged(&self) -> bool {
+        self.overcharge > 0.0
+    }
+
+    // Holstered weapons keep cooling down
+    pub fn update(&mut self, delta: f32) {
+        self.overcharge = (self.overcharge - delta).max(0.0);
+        self.weapons
+            .iter_mut()
+            .for_each(|weapon| weapon.update(delta));
+    }
+}
+
+impl Default for Weapon {
+    fn default() -> Self {
+        Self::new(Projectile::plasma(), 5.0, vec![Vec3::new(0.0, 0.0, -2.5)])
+    }
+}
+
+impl ResourceLoader for Weapon {
+    type Resource = Weapon;
+
+    fn is_resource(path: &PathBuf) -> bool {
+        path.extension().map_or(false, |e| e == "weapon")
+    }
+
+    fn load_resource(contents: &[PathBuf]) -> Result<Self::Resource, GameError> {
+        let definition = Definition::load(try_get_file("weapon", contents)?)?;
+
+        let mut weapon = Weapon::new(
+            Projectile::load(&definition)?,
+            definition.value("fire_rate")?,
+            definition.vec3s("muzzle")?,
+        );
+        if weapon.muzzles.is_empty() {
+            return GameError::err(String::from("A weapon needs at least one muzzle"));
+        }
+
+        weapon.spread = definition.value_or("spread", 0.0)?;
+        weapon.ammo = match definition.has("ammo") {
+            true => Some(definition.value("ammo")?),
+            false => None,
+        };
+        weapon.heat_per_shot = definition.value_or("heat_per_shot", 0.0)?;
+        weapon.cooling_rate = definition.value_or("cooling_rate", 0.0)?;
+        weapon.energy = definition.value_or("energy", 0.0)?;
+        Ok(weapon)
+    }
+}
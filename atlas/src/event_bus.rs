@@ -30,6 +30,16 @@ impl EventReader {
             })
         });
     }
+
+    // Looks at the pending events without consuming them, whoever reads them later still gets them
+    pub fn peek<T: 'static>(&self, mut reader: impl FnMut(&T) -> ()) {
+        let events = self.events.borrow();
+        events.iter().find_map(|queue| {
+            queue
+                .downcast_ref::<Vec<T>>()
+                .map(|queue| queue.iter().for_each(|event| reader(event)))
+        });
+    }
 }
 
 impl EventSender {
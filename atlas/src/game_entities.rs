@@ -10,6 +10,14 @@ pub mod bullet;
 pub mod asteroid;
 pub mod hud;
 pub mod explosion;
+pub mod squad;
+pub mod decoy;
+pub mod session;
+pub mod mission;
+pub mod scripted;
+pub mod pickup;
+pub mod widget;
+pub mod ui_layout;
 
 pub struct GameEntity<T> {
     pub entity: T,
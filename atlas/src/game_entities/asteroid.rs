@@ -20,23 +20,38 @@ pub struct AsteroidEntity {
     pub body: PhysicalBody,
     pub collider: Collider,
     pub info: Unit,
+    pub radius: f32,
 }
 
 impl AsteroidEntity {
-    pub fn prefab(material: PhongMaterial, radius: f32) -> Self {
-        let (vertices, indices) = asteroid(radius, 15);
-        let primitive = Mesh::new(&vertices, &indices);
-        let mut rnd = rand::thread_rng();
+    pub fn prefab(material: PhongMaterial, radius: f32, rnd: &mut impl Rng) -> Self {
+        let mesh = asteroid_model(material, radius, rnd);
         let mut body = PhysicalBody::new(1000.0, 100.0, 1.0);
         body.momentum = Vec3::new(
             rnd.gen_range(-50.0..50.0) * 100.0,
             rnd.gen_range(-50.0..50.0) * 100.0,
             rnd.gen_range(-50.0..50.0) * 100.0,
         );
+        let health = 100.0 + rnd.gen_range(-10.0..10.0);
+        Self::new(mesh, body, radius, health)
+    }
+
+    // Each of the `count` fragments takes an equal share of the mass, health scales with the radius
+    pub fn fragment(&self, radius: f32, count: usize, rnd: &mut impl Rng) -> Self {
+        let material = self.mesh.meshes[0].0.clone();
+        let mesh = asteroid_model(material, radius, rnd);
+        let body = PhysicalBody::new(
+            self.body.mass / count as f32,
+            self.body.angular_inertia / count as f32,
+            self.body.dampening_factor,
+        );
+        let health = self.info.max_health * radius / self.radius;
+        Self::new(mesh, body, radius, health)
+    }
+
+    fn new(mesh: Model, body: PhysicalBody, radius: f32, health: f32) -> Self {
         Self {
-            mesh: Model {
-                meshes: vec![(material, primitive)],
-            },
+            mesh,
             body,
             collider: Collider {
                 radius: radius * 1.2,
@@ -44,17 +59,28 @@ impl AsteroidEntity {
                 toi: 0.0,
                 last_impact: Vec3::ZERO,
             },
-            info: Unit::new("Asteroid", "Neutral", 100.0 + rnd.gen_range(-10.0..10.0)),
+            info: Unit::new("Asteroid", "Neutral", health),
+            radius,
         }
     }
 }
 
-pub fn generate_asteroid((width, height): (usize, usize)) -> Result<Texture, GameError> {
+fn asteroid_model(material: PhongMaterial, radius: f32, rnd: &mut impl Rng) -> Model {
+    let (vertices, indices) = asteroid(radius, 15, rnd);
+    Model {
+        meshes: vec![(material, Mesh::new(&vertices, &indices))],
+    }
+}
+
+pub fn generate_asteroid(
+    (width, height): (usize, usize),
+    rnd: &mut impl Rng,
+) -> Result<Texture, GameError> {
     let mut buffer = vec![96; width * height * 3];
-    add_perlin_noise(&mut buffer, (width, height), 4, 128.0);
-    add_perlin_noise(&mut buffer, (width, height), 8, 32.0);
-    add_perlin_noise(&mut buffer, (width, height), 16, 32.0);
-    add_perlin_noise(&mut buffer, (width, height), 32, 32.0);
+    add_perlin_noise(&mut buffer, (width, height), 4, 128.0, rnd);
+    add_perlin_noise(&mut buffer, (width, height), 8, 32.0, rnd);
+    add_perlin_noise(&mut buffer, (width, height), 16, 32.0, rnd);
+    add_perlin_noise(&mut buffer, (width, height), 32, 32.0, rnd);
     Texture::from_buff(&buffer, ChannelLayout::Rgb8, (width as u32, height as u32))
 }
 
@@ -63,9 +89,8 @@ pub fn add_perlin_noise(
     (width, height): (usize, usize),
     details: usize,
     strength: f32,
+    rnd: &mut impl Rng,
 ) {
-    let mut rnd = rand::thread_rng();
-
     let mut perlin_grid = vec![];
     for _ in 0..details {
         let mut row = vec![];
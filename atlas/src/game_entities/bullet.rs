@@ -1,8 +1,26 @@
+use glam::Vec3;
+
 use crate::components::collider::Collider;
+use crate::components::particle_emitter::ParticleEmitter;
 use crate::components::physical_body::PhysicalBody;
+use crate::components::unit::DamageType;
+use crate::components::weapon::Guidance;
+
+pub struct Missile {
+    pub target: Option<usize>,
+    pub guidance: Guidance,
+    pub fuel: f32,
+    pub damage: f32,
+    pub damage_type: DamageType,
+    pub exhaust: ParticleEmitter,
+}
 
 pub struct BulletEntity {
+    pub source: usize,
     pub collider: Collider,
     pub body: PhysicalBody,
     pub lifetime: f32,
+    pub colour: Vec3,
+    pub explosion: String,
+    pub missile: Option<Missile>,
 }
@@ -0,0 +1,8 @@
+use crate::components::{particle_emitter::ParticleEmitter, physical_body::PhysicalBody};
+
+pub struct Decoy {
+    pub body: PhysicalBody,
+    pub flare: ParticleEmitter,
+    pub lifetime: f32,
+    pub signature: f32,
+}
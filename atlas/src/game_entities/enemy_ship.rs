@@ -1,7 +1,8 @@
 use crate::{
     components::{
-        collider::Collider, particle_emitter::ParticleEmitter, physical_body::PhysicalBody,
-        unit::Unit,
+        behaviour::Behaviour, collider::Collider, countermeasures::Countermeasures,
+        particle_emitter::ParticleEmitter, physical_body::PhysicalBody, swarm_agent::SwarmAgent,
+        unit::Unit, weapon::Weapon,
     },
     graphics::model::Model,
 };
@@ -12,4 +13,8 @@ pub struct EnemyShip {
     pub collider: Collider,
     pub mesh: Model,
     pub info: Unit,
+    pub agent: SwarmAgent,
+    pub behaviour: Behaviour,
+    pub weapon: Weapon,
+    pub countermeasures: Countermeasures,
 }
@@ -6,6 +6,7 @@ use crate::{
     components::{
         collider::{solve_quadratic, Collider, QuadraticSolution},
         health_renderer::HealthRenderer,
+        lives::PilotState,
         physical_body::PhysicalBody,
         sprite_renderer::SpriteRenderer,
         text_renderer::TextRenderer,
@@ -18,7 +19,10 @@ use crate::{
     systems::trail_renderer::TrailEvent,
 };
 
-use super::{asteroid::AsteroidEntity, player_ship::PlayerShip, GameEntity};
+use super::{
+    asteroid::AsteroidEntity, mission::MissionEntity, player_ship::PlayerShip, session::Session,
+    GameEntity,
+};
 
 pub struct HudEntity {
     pub crosshair: SpriteRenderer,
@@ -27,7 +31,24 @@ pub struct HudEntity {
     pub unit: TextRenderer,
     pub velocity: TextRenderer,
     pub mass: TextRenderer,
+    pub status: TextRenderer,
+    pub systems: TextRenderer,
+    pub score: TextRenderer,
+    pub mission: TextRenderer,
     pub player_id: usize,
+    pub nodes: HudNodes,
+    // Hidden under menus drawn over the game
+    pub visible: bool,
+}
+
+// Layout nodes of the HUD, the crosshair node marks the centre the target readouts hang off
+#[derive(Clone, Copy)]
+pub struct HudNodes {
+    pub crosshair: usize,
+    pub status: usize,
+    pub systems: usize,
+    pub score: usize,
+    pub mission: usize,
 }
 
 impl<'a>
@@ -64,6 +85,18 @@ impl<'a>
                 &ship.entity.physical_body,
             )
         });
+        let players = self
+            .iter::<PlayerShip>()
+            .filter(|player| player.entity.lives.is_alive())
+            .map(|player| {
+                (
+                    &player.transform,
+                    player.id,
+                    &player.entity.info,
+                    &player.entity.collider,
+                    &player.entity.physical_body,
+                )
+            });
         let asteroids = self.iter::<AsteroidEntity>().map(|ship| {
             (
                 &ship.transform,
@@ -74,7 +107,7 @@ impl<'a>
             )
         });
 
-        Box::new(enemies.chain(asteroids))
+        Box::new(enemies.chain(players).chain(asteroids))
     }
 }
 
@@ -109,10 +142,12 @@ pub fn update_hud(entity_manager: &mut EntityManager, event_sender: &EventSender
                     Some((distance, id, u, physical))
                 },
             )
+            .filter(|(_, id, _, _)| *id != player.id)
             .filter(|(d, _, _, _)| *d < 0.1)
             .min_by(|(a, _, _, _), (b, _, _, _)| a.total_cmp(b));
 
         intersection.map(|(_, id, _, _)| event_sender.write(TrailEvent::Focus(id)));
+        let (player_id, lock) = (player.id, intersection.map(|(_, id, _, _)| id));
 
         let name = intersection
             .map(|i| i.2.name.clone())
@@ -129,7 +164,23 @@ pub fn update_hud(entity_manager: &mut EntityManager, event_sender: &EventSender
             .map(|mass| format!("Mass: {:.2} Mg", mass))
             .unwrap_or(String::new());
 
-        let helth = intersection.map(|i| i.2.health / i.2.max_health);
+        let score = entity_manager
+            .iter::<Session>()
+            .next()
+            .map_or(String::new(), |session| score(&session.entity));
+
+        let briefing = entity_manager
+            .iter::<MissionEntity>()
+            .next()
+            .map_or(String::new(), |mission| mission.entity.briefing());
+
+        let helth = intersection.map(|i| (i.2.health / i.2.max_health, i.2.shield.fraction()));
+
+        // Missile launchers lock onto whatever the HUD is focused on
+        entity_manager
+            .iter_mut::<PlayerShip>()
+            .filter(|player| player.id == player_id)
+            .for_each(|player| player.entity.arsenal.lock = lock);
 
         entity_manager
             .iter()
@@ -142,9 +193,14 @@ pub fn update_hud(entity_manager: &mut EntityManager, event_sender: &EventSender
                     hud.entity.unit.set_text(name.clone());
                     hud.entity.velocity.set_text(unit_velocity.clone());
                     hud.entity.mass.set_text(unit_mass.clone());
+                    hud.entity.status.set_text(status(&player.entity));
+                    hud.entity.systems.set_text(systems(&player.entity));
+                    hud.entity.score.set_text(score.clone());
+                    hud.entity.mission.set_text(briefing.clone());
 
-                    if let Some(helth) = helth {
+                    if let Some((helth, shield)) = helth {
                         hud.entity.health.health = helth;
+                        hud.entity.health.shield = shield;
                         hud.entity.health.enabled = true;
                     } else {
                         hud.entity.health.enabled = false;
@@ -154,6 +210,56 @@ pub fn update_hud(entity_manager: &mut EntityManager, event_sender: &EventSender
     }
 }
 
+fn score(session: &Session) -> String {
+    let score = &session.score;
+    format!(
+        "Score: {} x{} Wave: {} Accuracy: {:.0}% Ore: {}",
+        score.points,
+        score.multiplier(),
+        session.waves.spawned,
+        score.accuracy() * 100.0,
+        session.ore
+    )
+}
+
+fn status(player: &PlayerShip) -> String {
+    match player.lives.state() {
+        PilotState::Alive => format!(
+            "Hull: {:.0} Shield: {:.0} Lives: {}",
+            player.info.health.max(0.0),
+            player.info.shield.strength,
+            player.lives.remaining
+        ),
+        PilotState::Respawning(timer) => {
+            format!("Ship destroyed, respawning in {:.1}s", timer.max(0.0))
+        }
+        PilotState::GameOver(_) => String::from("Game over"),
+    }
+}
+
+// Ten segment gauges for the capacitor and the heat sink, FA marks flight assist
+fn systems(player: &PlayerShip) -> String {
+    let systems = &player.systems;
+    let state = match (systems.is_overheated(), systems.is_boosting()) {
+        (true, _) => "OVERHEAT",
+        (false, true) => "BOOST",
+        (false, false) => "",
+    };
+    let assist = if systems.flight_assist { "FA" } else { "" };
+    format!(
+        "Energy [{}] Heat [{}] {} {}",
+        gauge(systems.energy_fraction()),
+        gauge(systems.heat_fraction()),
+        assist,
+        state
+    )
+}
+
+fn gauge(fraction: f32) -> String {
+    let filled = (fraction.clamp(0.0, 1.0) * 10.0).round() as usize;
+    format!("{}{}", "|".repeat(filled), ".".repeat(10 - filled))
+}
+
 fn cast_ray(pos: Vec3, dir: Vec3, sphere: Vec3, radius: f32) -> Option<f32> {
     let pos = pos - sphere;
 
@@ -0,0 +1,30 @@
+use crate::game_mode::mission::{Mission, ObjectiveState};
+
+// The scripted mission of the scene and the HUD message its triggers last showed
+pub struct MissionEntity {
+    pub mission: Mission,
+    pub message: Option<(String, f32)>,
+}
+
+impl MissionEntity {
+    pub fn new(mission: Mission) -> Self {
+        Self {
+            mission,
+            message: None,
+        }
+    }
+
+    // Messages take over the objective list until they time out
+    pub fn briefing(&self) -> String {
+        if let Some((message, _)) = &self.message {
+            return message.clone();
+        }
+        self.mission
+            .objectives
+            .iter()
+            .filter(|objective| objective.state == ObjectiveState::Active)
+            .map(|objective| objective.describe())
+            .collect::<Vec<_>>()
+            .join(" | ")
+    }
+}
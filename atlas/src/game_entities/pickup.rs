@@ -0,0 +1,14 @@
+use crate::{
+    components::{collider::Collider, particle_emitter::ParticleEmitter, physical_body::PhysicalBody},
+    game_mode::loot::Loot,
+};
+
+// The collider is only a trigger, pickups never bounce off anything
+pub struct Pickup {
+    pub loot: Loot,
+    pub body: PhysicalBody,
+    pub collider: Collider,
+    pub glow: ParticleEmitter,
+    pub magnet: f32,
+    pub lifetime: f32,
+}
@@ -1,7 +1,10 @@
+use glam::Vec3;
+
 use crate::{
     components::{
-        camera::Camera, collider::Collider, particle_emitter::ParticleEmitter,
-        physical_body::PhysicalBody,
+        camera::Camera, collider::Collider, countermeasures::Countermeasures, lives::Lives,
+        particle_emitter::ParticleEmitter, physical_body::PhysicalBody,
+        ship_systems::ShipSystems, transform::Transform, unit::Unit, weapon::Arsenal,
     },
     graphics::model::Model,
 };
@@ -12,4 +15,18 @@ pub struct PlayerShip {
     pub thruster: ParticleEmitter,
     pub collider: Collider,
     pub mesh: Model,
+    pub arsenal: Arsenal,
+    pub countermeasures: Countermeasures,
+    pub systems: ShipSystems,
+    pub info: Unit,
+    pub lives: Lives,
+    // Set while the pilot waits for a new ship, the camera stays where the old one blew up
+    pub detached_camera: Option<DetachedCamera>,
+}
+
+#[derive(Clone, Copy)]
+pub struct DetachedCamera {
+    pub transform: Transform,
+    // Chase offset the camera had before the ship blew up, it goes back to it on respawn
+    pub offset: Vec3,
 }
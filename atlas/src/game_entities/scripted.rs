@@ -0,0 +1,25 @@
+use rhai::{Dynamic, Map};
+
+use crate::scripting::script::Script;
+
+// Runs a script every tick, attached to an entity or to the whole scene when there is no target.
+// The state is what the script sees as `this`
+pub struct Scripted {
+    pub script: Script,
+    pub target: Option<usize>,
+    pub state: Dynamic,
+    pub initialised: bool,
+    pub failed: bool,
+}
+
+impl Scripted {
+    pub fn new(script: Script, target: Option<usize>) -> Self {
+        Self {
+            script,
+            target,
+            state: Map::new().into(),
+            initialised: false,
+            failed: false,
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use crate::game_mode::{
+    loot::DropTable,
+    score::Score,
+    waves::{WaveDirector, WaveTable},
+};
+
+// Scoring, wave progression and loot of the current run
+pub struct Session {
+    pub score: Score,
+    pub waves: WaveDirector,
+    pub drops: DropTable,
+    pub ore: u32,
+}
+
+impl Session {
+    pub fn new(table: WaveTable, drops: DropTable) -> Self {
+        Self {
+            score: Score::new(table.combo_window),
+            waves: WaveDirector::new(table),
+            drops,
+            ore: 0,
+        }
+    }
+}
@@ -2,4 +2,5 @@ use crate::components::sprite_renderer::SpriteRenderer;
 
 pub struct Sprite {
     pub renderer: SpriteRenderer,
+    pub node: Option<usize>,
 }
@@ -0,0 +1,5 @@
+use crate::ai::steering::SquadParameters;
+
+pub struct Squad {
+    pub parameters: SquadParameters,
+}
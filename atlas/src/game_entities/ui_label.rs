@@ -2,4 +2,5 @@ use crate::components::text_renderer::TextRenderer;
 
 pub struct UiLabel {
     pub renderer: TextRenderer,
+    pub node: Option<usize>,
 }
@@ -0,0 +1,123 @@
+use glam::Vec2;
+
+use crate::components::{
+    button_trigger::Rect,
+    layout::{Direction, Layout},
+};
+
+struct LayoutNode {
+    layout: Layout,
+    parent: Option<usize>,
+    rect: Rect,
+}
+
+// Tree of layout nodes, parents are always added before their children
+pub struct UiLayout {
+    nodes: Vec<LayoutNode>,
+    screen: (f32, f32),
+    scale: f32,
+}
+
+impl UiLayout {
+    pub fn new() -> Self {
+        UiLayout {
+            nodes: vec![],
+            screen: (0.0, 0.0),
+            scale: 1.0,
+        }
+    }
+
+    pub fn add(&mut self, parent: Option<usize>, layout: Layout) -> usize {
+        let parent = parent.filter(|parent| *parent < self.nodes.len());
+        self.nodes.push(LayoutNode {
+            layout,
+            parent,
+            rect: Rect::new((0.0, 0.0), (0.0, 0.0)),
+        });
+        self.relayout();
+        self.nodes.len() - 1
+    }
+
+    pub fn layout_mut(&mut self, node: usize) -> Option<&mut Layout> {
+        self.nodes.get_mut(node).map(|node| &mut node.layout)
+    }
+
+    pub fn rect(&self, node: usize) -> Rect {
+        self.nodes
+            .get(node)
+            .map_or(Rect::new((0.0, 0.0), (0.0, 0.0)), |node| node.rect)
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    // Drops the nodes added after the first `count`, for UI that comes and goes like overlays
+    pub fn truncate(&mut self, count: usize) {
+        self.nodes.truncate(count);
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    pub fn dimensions(&self) -> (f32, f32) {
+        self.screen
+    }
+
+    pub fn resize(&mut self, (width, height): (i32, i32), scale: f32) {
+        self.screen = (width as f32, height as f32);
+        self.scale = scale;
+        self.relayout();
+    }
+
+    // One pass in insertion order, every parent rect is final before its children read it
+    pub fn relayout(&mut self) {
+        let screen = Rect::new((0.0, 0.0), self.screen);
+        let mut cursors = vec![0.0; self.nodes.len()];
+
+        (0..self.nodes.len()).for_each(|index| {
+            let LayoutNode { layout, parent, .. } = self.nodes[index];
+            let (inner, stack) = match parent {
+                Some(parent) => {
+                    let parent = &self.nodes[parent];
+                    let padding = parent.layout.padding * self.scale;
+                    (Self::inner(parent.rect, padding), parent.layout.stack)
+                }
+                None => (screen, None),
+            };
+
+            let size = Vec2::new(
+                layout.size.0.resolve(inner.size().x, self.scale),
+                layout.size.1.resolve(inner.size().y, self.scale),
+            );
+            let offset = Vec2::new(
+                layout.offset.0.resolve(inner.size().x, self.scale),
+                layout.offset.1.resolve(inner.size().y, self.scale),
+            );
+            let mut pos = inner.pos() + inner.size() * layout.anchor - size * layout.pivot + offset;
+
+            if let (Some(parent), Some((direction, spacing))) = (parent, stack) {
+                let cursor = &mut cursors[parent];
+                match direction {
+                    Direction::Horizontal => {
+                        pos.x = inner.pos().x + *cursor + offset.x;
+                        *cursor += size.x + spacing * self.scale;
+                    }
+                    Direction::Vertical => {
+                        pos.y = inner.pos().y + *cursor + offset.y;
+                        *cursor += size.y + spacing * self.scale;
+                    }
+                }
+            }
+
+            self.nodes[index].rect = Rect::new((pos.x, pos.y), (size.x, size.y));
+        });
+    }
+
+    fn inner(rect: Rect, padding: f32) -> Rect {
+        let pos = rect.pos() + Vec2::splat(padding);
+        let size = (rect.size() - Vec2::splat(padding * 2.0)).max(Vec2::ZERO);
+        Rect::new((pos.x, pos.y), (size.x, size.y))
+    }
+}
@@ -0,0 +1,442 @@
+use std::mem;
+
+use glam::{Vec2, Vec3, Vec4};
+
+use crate::{
+    components::{
+        button_handler::ButtonHandler,
+        button_trigger::{ButtonState, ButtonTrigger, Rect, UiEvent},
+        sprite_renderer::SpriteRenderer,
+        text_renderer::TextRenderer,
+        transform::Transform,
+    },
+    event_bus::EventSender,
+    graphics::material::sprite_material::SpriteMaterial,
+    resource_manager::font::Font,
+};
+
+// Height of a dropdown option or a list row, in unscaled UI units
+pub const ROW_HEIGHT: f32 = 40.0;
+// Gap between the left edge and the text, and from a row's centre down to the baseline
+const PADDING: f32 = 12.0;
+const BASELINE: f32 = 11.0;
+// An open dropdown is lifted above everything else so its options take the clicks
+const OPEN_LEVEL: usize = 100;
+// Arrow presses from one end of a slider to the other
+const SLIDER_STEPS: f32 = 20.0;
+// Warm highlight on the widget with keyboard or gamepad focus
+const FOCUS_TINT: Vec4 = Vec4::new(1.0, 0.8, 0.35, 1.0);
+
+pub enum Control {
+    Button(Box<dyn ButtonHandler>),
+    Slider {
+        value: f32,
+        min: f32,
+        max: f32,
+    },
+    Checkbox(bool),
+    Dropdown {
+        options: Vec<String>,
+        selected: usize,
+        open: bool,
+    },
+    List {
+        items: Vec<String>,
+        selected: usize,
+        scroll: usize,
+        rows: usize,
+    },
+}
+
+#[derive(Clone)]
+pub struct WidgetStyle {
+    pub font: Font,
+    pub material: SpriteMaterial,
+}
+
+// A retained UI control, the trigger rect is where it sits on screen
+pub struct Widget {
+    pub name: String,
+    pub caption: String,
+    pub control: Control,
+    pub trigger: ButtonTrigger,
+    pub background: SpriteRenderer,
+    pub rows: Vec<TextRenderer>,
+    pub node: Option<usize>,
+    // Tab goes through widgets by this, ties are broken by creation order
+    pub tab_order: usize,
+    focused: bool,
+    header: Rect,
+    level: usize,
+    scale: f32,
+}
+
+impl Widget {
+    pub fn new(
+        name: &str,
+        caption: &str,
+        control: Control,
+        trigger: ButtonTrigger,
+        style: &WidgetStyle,
+    ) -> Self {
+        let count = match &control {
+            Control::Dropdown { options, .. } => options.len() + 1,
+            Control::List { rows, .. } => *rows,
+            _ => 1,
+        };
+        let rows = (0..count)
+            .map(|_| TextRenderer::new(Transform::new(), "", style.font.clone()))
+            .collect();
+        let mut widget = Widget {
+            name: String::from(name),
+            caption: String::from(caption),
+            control,
+            header: trigger.rect(),
+            level: trigger.level(),
+            trigger,
+            background: SpriteRenderer::quad((1.0, 1.0), style.material.clone()),
+            rows,
+            node: None,
+            tab_order: 0,
+            focused: false,
+            scale: 1.0,
+        };
+        widget.refresh();
+        widget
+    }
+
+    pub fn button(
+        name: &str,
+        caption: &str,
+        handler: Box<dyn ButtonHandler>,
+        trigger: ButtonTrigger,
+        style: &WidgetStyle,
+    ) -> Self {
+        Self::new(name, caption, Control::Button(handler), trigger, style)
+    }
+
+    pub fn slider(
+        name: &str,
+        caption: &str,
+        (value, min, max): (f32, f32, f32),
+        trigger: ButtonTrigger,
+        style: &WidgetStyle,
+    ) -> Self {
+        let control = Control::Slider {
+            value: value.clamp(min, max),
+            min,
+            max,
+        };
+        Self::new(name, caption, control, trigger, style)
+    }
+
+    pub fn checkbox(
+        name: &str,
+        caption: &str,
+        checked: bool,
+        trigger: ButtonTrigger,
+        style: &WidgetStyle,
+    ) -> Self {
+        Self::new(name, caption, Control::Checkbox(checked), trigger, style)
+    }
+
+    pub fn dropdown(
+        name: &str,
+        caption: &str,
+        (options, selected): (&[&str], usize),
+        trigger: ButtonTrigger,
+        style: &WidgetStyle,
+    ) -> Self {
+        let control = Control::Dropdown {
+            options: options.iter().map(|option| String::from(*option)).collect(),
+            selected: selected.min(options.len().saturating_sub(1)),
+            open: false,
+        };
+        Self::new(name, caption, control, trigger, style)
+    }
+
+    // Only the position and width of a list's trigger count, its height follows the row count
+    pub fn list(
+        name: &str,
+        (items, rows): (&[&str], usize),
+        trigger: ButtonTrigger,
+        style: &WidgetStyle,
+    ) -> Self {
+        let control = Control::List {
+            items: items.iter().map(|item| String::from(*item)).collect(),
+            selected: 0,
+            scroll: 0,
+            rows: rows.max(1),
+        };
+        Self::new(name, "", control, trigger, style)
+    }
+
+    // Moves the widget, the rect is its closed size and an open dropdown grows from it
+    pub fn place(&mut self, rect: Rect, scale: f32) {
+        self.header = rect;
+        self.scale = scale;
+        self.refresh();
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.trigger.enabled = enabled;
+        if !enabled {
+            self.dismiss();
+        }
+        self.set_state(ButtonState::Normal);
+    }
+
+    pub fn set_state(&mut self, state: ButtonState) {
+        let state = if self.trigger.enabled { state } else { ButtonState::Disabled };
+        self.trigger.state = state;
+        self.background.tint = match (state, self.focused) {
+            (ButtonState::Disabled, _) => Vec4::new(0.4, 0.4, 0.4, 0.6),
+            (ButtonState::Pressed, _) => Vec4::new(0.6, 0.6, 0.6, 1.0),
+            (_, true) => FOCUS_TINT,
+            (ButtonState::Hovered, false) => Vec4::ONE,
+            (ButtonState::Normal, false) => Vec4::new(0.8, 0.8, 0.8, 1.0),
+        };
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+        self.set_state(self.trigger.state);
+    }
+
+    pub fn value(&self) -> f32 {
+        match &self.control {
+            Control::Slider { value, .. } => *value,
+            Control::Checkbox(checked) => *checked as i32 as f32,
+            Control::Dropdown { selected, .. } | Control::List { selected, .. } => *selected as f32,
+            Control::Button(_) => 0.0,
+        }
+    }
+
+    // Releasing the mouse over the widget the press started on
+    pub fn activate(&mut self, (x, y): (f32, f32), event_sender: &mut EventSender) {
+        match (&self.control, self.row_at(y)) {
+            (Control::Slider { .. }, _) => self.drag((x, y), event_sender),
+            (Control::Dropdown { open: true, .. }, Some(row)) if row > 0 => {
+                self.select(row - 1, event_sender);
+                self.dismiss();
+            }
+            (Control::List { scroll, .. }, Some(row)) => {
+                let item = row + *scroll;
+                self.select(item, event_sender);
+                self.press(event_sender);
+            }
+            (Control::List { .. }, None) => {}
+            _ => self.press(event_sender),
+        }
+    }
+
+    // Enter or the A button on the focused widget
+    pub fn press(&mut self, event_sender: &mut EventSender) {
+        let name = self.name.clone();
+        match &mut self.control {
+            Control::Button(handler) => {
+                handler.on_click(event_sender);
+                event_sender.write(UiEvent::Pressed(name));
+            }
+            Control::Checkbox(checked) => {
+                *checked = !*checked;
+                event_sender.write(UiEvent::Toggled(name, *checked));
+            }
+            Control::Dropdown { open, .. } => *open = !*open,
+            Control::List { selected, items, .. } if *selected < items.len() => {
+                event_sender.write(UiEvent::Activated(name, *selected))
+            }
+            Control::List { .. } => {}
+            Control::Slider { .. } => {}
+        }
+        self.refresh();
+    }
+
+    pub fn select(&mut self, index: usize, event_sender: &mut EventSender) {
+        let name = self.name.clone();
+        match &mut self.control {
+            Control::Dropdown {
+                options, selected, ..
+            } if index < options.len() => *selected = index,
+            Control::List {
+                items,
+                selected,
+                scroll,
+                rows,
+            } if index < items.len() => {
+                *selected = index;
+                // Keeps the selection on screen
+                *scroll = (*scroll).clamp((index + 1).saturating_sub(*rows), index);
+            }
+            _ => return,
+        }
+        event_sender.write(UiEvent::Selected(name, index));
+        self.refresh();
+    }
+
+    // Arrow keys adjust sliders, lists and open dropdowns, anything else lets focus move on
+    pub fn step(&mut self, (x, y): (i32, i32), event_sender: &mut EventSender) -> bool {
+        match &mut self.control {
+            Control::Slider { value, min, max } if x != 0 => {
+                *value = (*value + x as f32 * (*max - *min) / SLIDER_STEPS).clamp(*min, *max);
+                event_sender.write(UiEvent::Changed(self.name.clone(), *value));
+                self.refresh();
+                true
+            }
+            Control::List { selected, .. } | Control::Dropdown { selected, open: true, .. }
+                if y != 0 =>
+            {
+                let index = (*selected as i32 + y).max(0) as usize;
+                self.select(index, event_sender);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // Sliders follow the cursor while the mouse button is held
+    pub fn drag(&mut self, (x, _): (f32, f32), event_sender: &mut EventSender) {
+        let rect = self.header;
+        let Control::Slider { value, min, max } = &mut self.control else {return};
+        let fraction = ((x - rect.pos().x) / rect.size().x).clamp(0.0, 1.0);
+        let new_value = *min + (*max - *min) * fraction;
+        if new_value != *value {
+            *value = new_value;
+            event_sender.write(UiEvent::Changed(self.name.clone(), new_value));
+            self.refresh();
+        }
+    }
+
+    pub fn scroll(&mut self, lines: i32) {
+        let Control::List { items, scroll, rows, .. } = &mut self.control else {return};
+        let last = items.len().saturating_sub(*rows) as i32;
+        *scroll = (*scroll as i32 + lines).clamp(0, last) as usize;
+        self.refresh();
+    }
+
+    // Closes an open dropdown, used when the click lands somewhere else or on going back
+    pub fn dismiss(&mut self) -> bool {
+        let Control::Dropdown { open, .. } = &mut self.control else {return false};
+        let closed = mem::take(open);
+        if closed {
+            self.refresh();
+        }
+        closed
+    }
+
+    pub fn background_transform(&self) -> Transform {
+        let rect = self.trigger.rect();
+        let center = rect.pos() + rect.size() * 0.5;
+        // The quad's texture coordinates are laid out for y up, flipping both axes turns it upright
+        Transform {
+            scale: Vec3::new(-rect.size().x, -rect.size().y, 1.0),
+            ..Transform::pos(Vec3::new(center.x, center.y, 0.0))
+        }
+    }
+
+    pub fn row_transform(&self, row: usize) -> Transform {
+        let rect = self.header;
+        let offset = Vec2::new(
+            PADDING * self.scale,
+            self.row_top(row) + self.row_height(row) * 0.5 + BASELINE * self.scale,
+        );
+        let pos = rect.pos() + offset;
+        Transform {
+            scale: Vec3::new(self.scale, self.scale, 1.0),
+            ..Transform::pos(Vec3::new(pos.x, pos.y, 0.0))
+        }
+    }
+
+    fn row_top(&self, row: usize) -> f32 {
+        match row {
+            0 => 0.0,
+            _ => self.row_height(0) + (row - 1) as f32 * ROW_HEIGHT * self.scale,
+        }
+    }
+
+    fn row_height(&self, row: usize) -> f32 {
+        match (&self.control, row) {
+            (Control::List { .. }, _) => ROW_HEIGHT * self.scale,
+            (_, 0) => self.header.size().y,
+            _ => ROW_HEIGHT * self.scale,
+        }
+    }
+
+    fn row_at(&self, y: f32) -> Option<usize> {
+        let y = y - self.header.pos().y;
+        (0..self.rows.len()).find(|row| {
+            let top = self.row_top(*row);
+            y >= top && y < top + self.row_height(*row)
+        })
+    }
+
+    // Rebuilds the row text and the clickable area after the control changed
+    pub fn refresh(&mut self) {
+        let header = self.header;
+        let mut lines = match &self.control {
+            Control::Button(_) => vec![self.caption.clone()],
+            Control::Slider { value, min, max } => {
+                let fraction = (value - min) / (max - min).max(f32::EPSILON);
+                vec![format!("{} [{}] {:.2}", self.caption, gauge(fraction), value)]
+            }
+            Control::Checkbox(checked) => {
+                let mark = if *checked { "x" } else { " " };
+                vec![format!("[{}] {}", mark, self.caption)]
+            }
+            Control::Dropdown {
+                options,
+                selected,
+                open,
+            } => {
+                let current = options.get(*selected).map_or("", |option| option.as_str());
+                let arrow = if *open { "^" } else { "v" };
+                let title = vec![format!("{}: {} {}", self.caption, current, arrow)];
+                let shown = options.iter().filter(|_| *open).map(|option| format!("  {}", option));
+                title.into_iter().chain(shown).collect()
+            }
+            Control::List {
+                items,
+                selected,
+                scroll,
+                rows,
+            } => items
+                .iter()
+                .enumerate()
+                .skip(*scroll)
+                .take(*rows)
+                .map(|(index, item)| {
+                    let mark = if index == *selected { ">" } else { " " };
+                    format!("{} {}", mark, item)
+                })
+                .collect(),
+        };
+        lines.resize(self.rows.len(), String::new());
+        self.rows
+            .iter_mut()
+            .zip(lines)
+            .filter(|(row, line)| row.get_text() != *line)
+            .for_each(|(row, line)| row.set_text(line));
+
+        let (height, level) = match &self.control {
+            Control::Dropdown { options, open: true, .. } => (
+                header.size().y + options.len() as f32 * ROW_HEIGHT * self.scale,
+                self.level + OPEN_LEVEL,
+            ),
+            Control::List { rows, .. } => (*rows as f32 * ROW_HEIGHT * self.scale, self.level),
+            _ => (header.size().y, self.level),
+        };
+        let pos = header.pos();
+        self.trigger.set_rect(Rect::new((pos.x, pos.y), (header.size().x, height)));
+        self.trigger.set_level(level);
+        self.set_state(self.trigger.state);
+    }
+}
+
+fn gauge(fraction: f32) -> String {
+    let filled = (fraction.clamp(0.0, 1.0) * 10.0).round() as usize;
+    format!("{}{}", "|".repeat(filled), ".".repeat(10 - filled))
+}
@@ -0,0 +1,5 @@
+pub mod high_scores;
+pub mod loot;
+pub mod mission;
+pub mod score;
+pub mod waves;
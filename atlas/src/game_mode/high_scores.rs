@@ -0,0 +1,71 @@
+use std::{env, fs, path::PathBuf};
+
+use crate::{game_root::GameError, scene::Summary};
+
+const TABLE_SIZE: usize = 10;
+
+// Kept next to the executable, one `score wave kills accuracy survived` line per entry
+pub struct HighScores {
+    path: PathBuf,
+    entries: Vec<Summary>,
+}
+
+impl HighScores {
+    pub fn load(name: &str) -> Result<Self, GameError> {
+        let mut path = env::current_exe()?;
+        path.set_file_name(name);
+
+        let entries = match fs::read_to_string(&path) {
+            Ok(contents) => contents.lines().filter_map(parse_entry).collect(),
+            Err(_) => vec![],
+        };
+        Ok(Self { path, entries })
+    }
+
+    pub fn entries(&self) -> &[Summary] {
+        &self.entries
+    }
+
+    // Returns the place the run took on the table, if it made it there at all
+    pub fn record(&mut self, summary: Summary) -> Option<usize> {
+        let rank = self
+            .entries
+            .iter()
+            .position(|entry| entry.score < summary.score)
+            .unwrap_or(self.entries.len());
+        if rank >= TABLE_SIZE {
+            return None;
+        }
+
+        self.entries.insert(rank, summary);
+        self.entries.truncate(TABLE_SIZE);
+        Some(rank)
+    }
+
+    pub fn save(&self) -> Result<(), GameError> {
+        let contents: String = self
+            .entries
+            .iter()
+            .map(|entry| {
+                format!(
+                    "{} {} {} {} {}\n",
+                    entry.score, entry.wave, entry.kills, entry.accuracy, entry.survived
+                )
+            })
+            .collect();
+        Ok(fs::write(&self.path, contents)?)
+    }
+}
+
+fn parse_entry(line: &str) -> Option<Summary> {
+    let values: Vec<_> = line.split_whitespace().collect();
+    let [score, wave, kills, accuracy, survived] = values[..] else {return None};
+    Some(Summary {
+        score: score.parse().ok()?,
+        wave: wave.parse().ok()?,
+        kills: kills.parse().ok()?,
+        accuracy: accuracy.parse().ok()?,
+        survived: survived.parse().ok()?,
+        lives_lost: 0,
+    })
+}
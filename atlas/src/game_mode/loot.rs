@@ -0,0 +1,123 @@
+use std::path::PathBuf;
+
+use glam::Vec3;
+use rand::Rng;
+
+use crate::{
+    game_root::GameError,
+    resource_manager::{definition::Definition, try_get_file, ResourceLoader},
+};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PickupKind {
+    Repair,
+    Ammo,
+    Overcharge,
+    Ore,
+}
+
+// Amount is hull points, volleys, seconds of overcharge or units of ore depending on the kind
+#[derive(Clone, Copy)]
+pub struct Loot {
+    pub kind: PickupKind,
+    pub amount: f32,
+}
+
+#[derive(Clone)]
+struct Drop {
+    unit_type: String,
+    chance: f32,
+    loot: Loot,
+}
+
+#[derive(Clone)]
+pub struct DropTable {
+    pub magnet: f32,
+    pub lifetime: f32,
+    drops: Vec<Drop>,
+}
+
+impl PickupKind {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "repair" => Some(Self::Repair),
+            "ammo" => Some(Self::Ammo),
+            "overcharge" => Some(Self::Overcharge),
+            "ore" => Some(Self::Ore),
+            _ => None,
+        }
+    }
+
+    pub fn colour(&self) -> Vec3 {
+        match self {
+            Self::Repair => Vec3::new(0.2, 1.0, 0.3),
+            Self::Ammo => Vec3::new(1.0, 0.8, 0.2),
+            Self::Overcharge => Vec3::new(0.6, 0.3, 1.0),
+            Self::Ore => Vec3::new(0.4, 0.8, 1.0),
+        }
+    }
+}
+
+impl DropTable {
+    // Every line of the table is rolled on its own, a unit can drop several pickups at once
+    pub fn roll(&self, unit_type: &str, rng: &mut impl Rng) -> Vec<Loot> {
+        self.drops
+            .iter()
+            .filter(|drop| drop.unit_type == unit_type)
+            .filter(|drop| rng.gen::<f32>() < drop.chance)
+            .map(|drop| drop.loot)
+            .collect()
+    }
+}
+
+impl Default for DropTable {
+    fn default() -> Self {
+        Self {
+            magnet: 30.0,
+            lifetime: 20.0,
+            drops: vec![],
+        }
+    }
+}
+
+impl ResourceLoader for DropTable {
+    type Resource = DropTable;
+
+    fn is_resource(path: &PathBuf) -> bool {
+        path.extension().map_or(false, |e| e == "drops")
+    }
+
+    fn load_resource(contents: &[PathBuf]) -> Result<Self::Resource, GameError> {
+        let definition = Definition::load(try_get_file("drops", contents)?)?;
+        let default = Self::default();
+
+        let drops = definition
+            .all("drop")
+            .into_iter()
+            .map(|values| match values {
+                [unit_type, chance, kind, amount] => Ok(Drop {
+                    unit_type: unit_type.clone(),
+                    chance: chance
+                        .parse()
+                        .map_err(|_| GameError::new(&format!("Invalid chance '{}'", chance)))?,
+                    loot: Loot {
+                        kind: PickupKind::parse(kind)
+                            .ok_or(GameError::new(&format!("Unknown pickup '{}'", kind)))?,
+                        amount: amount
+                            .parse()
+                            .map_err(|_| GameError::new(&format!("Invalid amount '{}'", amount)))?,
+                    },
+                }),
+                _ => GameError::err(String::from(
+                    "'drop' expects a unit type, a chance, a pickup and an amount",
+                )),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            magnet: definition.value_or("magnet", default.magnet)?,
+            lifetime: definition.value_or("lifetime", default.lifetime)?,
+            drops,
+        })
+    }
+}
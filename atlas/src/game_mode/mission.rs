@@ -0,0 +1,233 @@
+use std::path::PathBuf;
+
+use glam::Vec3;
+
+use crate::{
+    game_root::GameError,
+    resource_manager::{definition::Definition, try_get_file, ResourceLoader},
+};
+
+#[derive(Clone)]
+pub enum Condition {
+    Destroy { unit_type: String, count: u32 },
+    Reach { position: Vec3, radius: f32 },
+    Escort { unit: String, position: Vec3, radius: f32 },
+    Survive(f32),
+}
+
+#[derive(Clone)]
+pub enum Action {
+    Message(String),
+    Spawn {
+        prefab: String,
+        position: Vec3,
+        count: u32,
+    },
+    Scene(String),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Outcome {
+    Complete,
+    Fail,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ObjectiveState {
+    Pending,
+    Active,
+    Completed,
+    Failed,
+}
+
+#[derive(Clone)]
+pub struct Objective {
+    pub id: String,
+    pub condition: Condition,
+    pub after: Option<String>,
+    pub deadline: Option<f32>,
+    pub state: ObjectiveState,
+    pub elapsed: f32,
+    pub progress: u32,
+}
+
+#[derive(Clone)]
+pub struct Trigger {
+    pub objective: String,
+    pub outcome: Outcome,
+    pub action: Action,
+}
+
+// Objectives are checked by the mission system, triggers fire once their objective completes or fails
+#[derive(Clone, Default)]
+pub struct Mission {
+    pub name: String,
+    pub objectives: Vec<Objective>,
+    pub triggers: Vec<Trigger>,
+}
+
+impl Objective {
+    pub fn describe(&self) -> String {
+        match &self.condition {
+            Condition::Destroy { unit_type, count } => {
+                format!("Destroy {}: {}/{}", unit_type, self.progress, count)
+            }
+            Condition::Reach { .. } => String::from("Reach the waypoint"),
+            Condition::Escort { unit, .. } => format!("Escort {}", unit),
+            Condition::Survive(duration) => {
+                format!("Survive: {:.0}s", (duration - self.elapsed).max(0.0))
+            }
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self.state,
+            ObjectiveState::Completed | ObjectiveState::Failed
+        )
+    }
+}
+
+impl Mission {
+    pub fn parse(definition: &Definition) -> Result<Self, GameError> {
+        let mut objectives = definition
+            .all("objective")
+            .into_iter()
+            .map(parse_objective)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for values in definition.all("after") {
+            let [id, prerequisite] = values else {
+                return GameError::err(String::from("'after' expects an objective and its prerequisite"));
+            };
+            let objective = find(&mut objectives, id)?;
+            objective.after = Some(prerequisite.clone());
+            objective.state = ObjectiveState::Pending;
+        }
+        for values in definition.all("deadline") {
+            let [id, seconds] = values else {
+                return GameError::err(String::from("'deadline' expects an objective and a time"));
+            };
+            find(&mut objectives, id)?.deadline = Some(number(seconds)?);
+        }
+
+        let triggers = definition
+            .all("on")
+            .into_iter()
+            .map(parse_trigger)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            name: definition
+                .all("name")
+                .first()
+                .map_or(String::new(), |words| words.join(" ")),
+            objectives,
+            triggers,
+        })
+    }
+
+    pub fn objective(&self, id: &str) -> Option<&Objective> {
+        self.objectives.iter().find(|objective| objective.id == id)
+    }
+
+    pub fn actions(&self, objective: &str, outcome: Outcome) -> Vec<Action> {
+        self.triggers
+            .iter()
+            .filter(|trigger| trigger.objective == objective && trigger.outcome == outcome)
+            .map(|trigger| trigger.action.clone())
+            .collect()
+    }
+}
+
+impl ResourceLoader for Mission {
+    type Resource = Mission;
+
+    fn is_resource(path: &PathBuf) -> bool {
+        path.extension().map_or(false, |e| e == "mission")
+    }
+
+    fn load_resource(contents: &[PathBuf]) -> Result<Self::Resource, GameError> {
+        Mission::parse(&Definition::load(try_get_file("mission", contents)?)?)
+    }
+}
+
+fn parse_objective(values: &[String]) -> Result<Objective, GameError> {
+    let [id, kind, args @ ..] = values else {
+        return GameError::err(String::from("'objective' expects an id and a kind"));
+    };
+
+    let condition = match (kind.as_str(), args) {
+        ("destroy", [unit_type, count]) => Condition::Destroy {
+            unit_type: unit_type.clone(),
+            count: number(count)?,
+        },
+        ("reach", [x, y, z, radius]) => Condition::Reach {
+            position: vector(x, y, z)?,
+            radius: number(radius)?,
+        },
+        // Unit names may have spaces, everything before the position is the name
+        ("escort", [unit @ .., x, y, z, radius]) if !unit.is_empty() => Condition::Escort {
+            unit: unit.join(" "),
+            position: vector(x, y, z)?,
+            radius: number(radius)?,
+        },
+        ("survive", [seconds]) => Condition::Survive(number(seconds)?),
+        _ => return GameError::err(format!("Invalid objective '{}'", values.join(" "))),
+    };
+
+    Ok(Objective {
+        id: id.clone(),
+        condition,
+        after: None,
+        deadline: None,
+        state: ObjectiveState::Active,
+        elapsed: 0.0,
+        progress: 0,
+    })
+}
+
+fn parse_trigger(values: &[String]) -> Result<Trigger, GameError> {
+    let [objective, outcome, action, args @ ..] = values else {
+        return GameError::err(String::from("'on' expects an objective, an outcome and an action"));
+    };
+
+    let outcome = match outcome.as_str() {
+        "complete" => Outcome::Complete,
+        "fail" => Outcome::Fail,
+        outcome => return GameError::err(format!("Unknown outcome '{}'", outcome)),
+    };
+    let action = match (action.as_str(), args) {
+        ("message", words) => Action::Message(words.join(" ")),
+        ("spawn", [prefab, x, y, z, count]) => Action::Spawn {
+            prefab: prefab.clone(),
+            position: vector(x, y, z)?,
+            count: number(count)?,
+        },
+        ("scene", [scene]) => Action::Scene(scene.clone()),
+        _ => return GameError::err(format!("Invalid action '{}'", values.join(" "))),
+    };
+
+    Ok(Trigger {
+        objective: objective.clone(),
+        outcome,
+        action,
+    })
+}
+
+fn find<'a>(objectives: &'a mut [Objective], id: &str) -> Result<&'a mut Objective, GameError> {
+    objectives
+        .iter_mut()
+        .find(|objective| objective.id == id)
+        .ok_or(GameError::new(&format!("Unknown objective '{}'", id)))
+}
+
+fn number<T: std::str::FromStr>(value: &str) -> Result<T, GameError> {
+    value
+        .parse()
+        .map_err(|_| GameError::new(&format!("Invalid number '{}'", value)))
+}
+
+fn vector(x: &str, y: &str, z: &str) -> Result<Vec3, GameError> {
+    Ok(Vec3::new(number(x)?, number(y)?, number(z)?))
+}
@@ -0,0 +1,58 @@
+// Every few kills inside the combo window raise the multiplier by one
+const KILLS_PER_MULTIPLIER: u32 = 3;
+const MAX_MULTIPLIER: u64 = 5;
+
+pub struct Score {
+    pub points: u64,
+    pub kills: u32,
+    pub combo: u32,
+    pub best_combo: u32,
+    pub shots: u32,
+    pub hits: u32,
+    pub combo_window: f32,
+    combo_timer: f32,
+}
+
+impl Score {
+    pub fn new(combo_window: f32) -> Self {
+        Self {
+            points: 0,
+            kills: 0,
+            combo: 0,
+            best_combo: 0,
+            shots: 0,
+            hits: 0,
+            combo_window,
+            combo_timer: 0.0,
+        }
+    }
+
+    pub fn multiplier(&self) -> u64 {
+        (1 + (self.combo / KILLS_PER_MULTIPLIER) as u64).min(MAX_MULTIPLIER)
+    }
+
+    // Returns the points actually awarded after the combo multiplier
+    pub fn kill(&mut self, points: u64) -> u64 {
+        let awarded = points * self.multiplier();
+        self.points += awarded;
+        self.kills += 1;
+        self.combo += 1;
+        self.best_combo = self.best_combo.max(self.combo);
+        self.combo_timer = self.combo_window;
+        awarded
+    }
+
+    pub fn accuracy(&self) -> f32 {
+        match self.shots {
+            0 => 0.0,
+            shots => (self.hits as f32 / shots as f32).min(1.0),
+        }
+    }
+
+    pub fn update(&mut self, delta: f32) {
+        self.combo_timer = (self.combo_timer - delta).max(0.0);
+        if self.combo_timer == 0.0 {
+            self.combo = 0;
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use std::path::PathBuf;
+
+use crate::{
+    game_root::GameError,
+    resource_manager::{definition::Definition, try_get_file, ResourceLoader},
+};
+
+#[derive(Clone, Copy)]
+pub struct Wave {
+    pub asteroids: u32,
+    pub enemies: u32,
+}
+
+#[derive(Clone)]
+pub struct WaveTable {
+    pub interval: f32,
+    pub escalation: u32,
+    pub combo_window: f32,
+    waves: Vec<Wave>,
+    points: Vec<(String, u64)>,
+}
+
+pub struct WaveDirector {
+    pub table: WaveTable,
+    pub spawned: usize,
+    countdown: f32,
+}
+
+impl WaveTable {
+    // Past the end of the table the last wave repeats with `escalation` more enemies every time
+    pub fn wave(&self, index: usize) -> Wave {
+        let last = self.waves.len() - 1;
+        let wave = self.waves[index.min(last)];
+        Wave {
+            enemies: wave.enemies + index.saturating_sub(last) as u32 * self.escalation,
+            ..wave
+        }
+    }
+
+    pub fn points(&self, unit_type: &str) -> u64 {
+        self.points
+            .iter()
+            .find(|(kind, _)| kind == unit_type)
+            .map_or(0, |(_, points)| *points)
+    }
+}
+
+impl Default for WaveTable {
+    fn default() -> Self {
+        Self {
+            interval: 5.0,
+            escalation: 1,
+            combo_window: 3.0,
+            waves: vec![Wave {
+                asteroids: 4,
+                enemies: 5,
+            }],
+            points: vec![(String::from("Enemy"), 250), (String::from("Neutral"), 25)],
+        }
+    }
+}
+
+impl WaveDirector {
+    pub fn new(table: WaveTable) -> Self {
+        Self {
+            countdown: table.interval,
+            table,
+            spawned: 0,
+        }
+    }
+
+    // The next wave is only counted down once every enemy of the previous one is gone
+    pub fn update(&mut self, delta: f32, enemies_left: bool) -> Option<Wave> {
+        if enemies_left {
+            self.countdown = self.table.interval;
+            return None;
+        }
+
+        self.countdown -= delta;
+        if self.countdown > 0.0 {
+            return None;
+        }
+
+        self.countdown = self.table.interval;
+        self.spawned += 1;
+        Some(self.table.wave(self.spawned - 1))
+    }
+}
+
+impl ResourceLoader for WaveTable {
+    type Resource = WaveTable;
+
+    fn is_resource(path: &PathBuf) -> bool {
+        path.extension().map_or(false, |e| e == "waves")
+    }
+
+    fn load_resource(contents: &[PathBuf]) -> Result<Self::Resource, GameError> {
+        let definition = Definition::load(try_get_file("waves", contents)?)?;
+        let default = Self::default();
+
+        let waves = definition
+            .values::<u32>("wave")?
+            .into_iter()
+            .map(|values| match values[..] {
+                [asteroids, enemies] => Ok(Wave { asteroids, enemies }),
+                _ => GameError::err(String::from("'wave' expects asteroid and enemy counts")),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if waves.is_empty() {
+            return GameError::err(String::from("A wave table needs at least one wave"));
+        }
+
+        let points = definition
+            .all("points")
+            .into_iter()
+            .map(|values| match values {
+                [unit_type, points] => points
+                    .parse()
+                    .map(|points| (unit_type.clone(), points))
+                    .map_err(|_| GameError::new(&format!("Invalid points '{}'", points))),
+                _ => GameError::err(String::from("'points' expects a unit type and a value")),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            interval: definition.value_or("interval", default.interval)?,
+            escalation: definition.value_or("escalation", default.escalation)?,
+            combo_window: definition.value_or("combo_window", default.combo_window)?,
+            waves,
+            points,
+        })
+    }
+}
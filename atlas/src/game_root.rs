@@ -3,8 +3,10 @@ use std::{fmt, rc::Rc};
 use crate::{
     graphics::graphics_context::GraphicsContext,
     logger::{console_logger::ConsoleLogger, Logger},
+    random::{Random, SimulationMode},
     resource_manager::scene_manager::SceneManager,
     scene::{Scene, SceneEvent},
+    settings::Settings,
 };
 
 #[derive(Debug, Clone)]
@@ -41,28 +43,59 @@ pub struct GameRoot {
     scene_manager: SceneManager,
     logger: Rc<dyn Logger>,
     graphics_context: GraphicsContext,
+    random: Random,
+    settings: Settings,
 }
 
 impl GameRoot {
-    pub fn new(title: &str) -> Result<Self, GameError> {
+    pub fn new(title: &str, mode: SimulationMode) -> Result<Self, GameError> {
         let logger = Rc::new(ConsoleLogger::new());
-        let graphics_context = GraphicsContext::new(title)?;
+        // The window is created from the settings, so they are read first
+        let settings = Settings::load(&title.to_lowercase(), logger.as_ref());
+        let graphics_context = GraphicsContext::new(title, &settings)?;
         let scene_manager = SceneManager::new()?;
+        let random = Random::new(mode);
+
+        logger.log_info(&format!("Simulation seed: {}", random.seed()));
 
         Ok(GameRoot {
             logger,
             scene_manager,
             graphics_context,
+            random,
+            settings,
+        })
+    }
+
+    pub fn headless(title: &str, mode: SimulationMode) -> Result<Self, GameError> {
+        let logger = Rc::new(ConsoleLogger::new());
+        let graphics_context = GraphicsContext::headless(title);
+        let scene_manager = SceneManager::new()?;
+        let random = Random::new(mode);
+
+        logger.log_info(&format!("Headless simulation seed: {}", random.seed()));
+
+        // Player settings stay out of headless runs so their output only depends on the seed
+        Ok(GameRoot {
+            logger,
+            scene_manager,
+            graphics_context,
+            random,
+            settings: Settings::default(),
         })
     }
 
     pub fn run(&mut self) {
         let mut next_scene = String::from("first_scene");
+        let mut summary = None;
         loop {
-            let mut scene: Box<dyn Scene> = match self
-                .scene_manager
-                .get_scene(&next_scene, &mut self.graphics_context)
-            {
+            let mut scene: Box<dyn Scene> = match self.scene_manager.get_scene(
+                &next_scene,
+                &mut self.graphics_context,
+                &self.random,
+                &self.settings,
+                summary.take(),
+            ) {
                 Ok(scene) => scene,
                 Err(e) => {
                     self.logger.log_error(&format!(
@@ -74,7 +107,14 @@ impl GameRoot {
                 }
             };
 
-            match scene.run(&mut self.graphics_context) {
+            let event = scene.run(&mut self.graphics_context);
+            // The options scene saves what the player changed, the next scene gets it from here
+            if let Err(e) = self.settings.reload() {
+                self.logger
+                    .log_error(&format!("Failed to reload settings: {}", e));
+            }
+
+            match event {
                 SceneEvent::Exit => {
                     self.logger.log_info("Exiting the game");
                     break;
@@ -82,13 +122,38 @@ impl GameRoot {
                 SceneEvent::NewScene(scene) => {
                     self.logger
                         .log_info(&format!("Transitioning to: {}", scene));
-                    next_scene = String::from(scene);
+                    next_scene = scene;
+                }
+                SceneEvent::GameOver(game_summary) => {
+                    self.logger
+                        .log_info(&format!("Game over: {}", game_summary));
+                    next_scene = String::from("main");
+                    summary = Some(game_summary);
+                }
+                SceneEvent::RestartScene => {
+                    self.logger.log_info(&format!("Restarting: {}", next_scene));
                 }
-                _ => {}
             }
         }
     }
 
+    pub fn run_headless(&mut self, scene_id: &str, ticks: u64) -> Result<String, GameError> {
+        let mut scene =
+            self.scene_manager.get_scene(
+                scene_id,
+                &mut self.graphics_context,
+                &self.random,
+                &self.settings,
+                None,
+            )?;
+
+        self.logger
+            .log_info(&format!("Simulating '{}' for {} ticks", scene_id, ticks));
+        (0..ticks).for_each(|_| scene.fixed_update());
+
+        Ok(scene.world_state())
+    }
+
     pub fn scene_manager(&mut self) -> &mut SceneManager {
         &mut self.scene_manager
     }
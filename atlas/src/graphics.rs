@@ -1,3 +1,5 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
 pub mod graphics_context;
 pub mod instanced_mesh;
 pub mod lights;
@@ -8,3 +10,16 @@ pub mod shaders;
 pub mod texture;
 pub mod vertices;
 pub mod context;
+
+// Process-wide, it follows the last `GraphicsContext` created, so one process can't mix headless and
+// windowed contexts. Code that has a context at hand asks `GraphicsContext::is_headless` instead
+static HEADLESS: AtomicBool = AtomicBool::new(false);
+
+// Without a GL context every upload becomes a no-op, so scenes can still build their meshes
+pub fn is_headless() -> bool {
+    HEADLESS.load(Ordering::Relaxed)
+}
+
+fn set_headless(headless: bool) {
+    HEADLESS.store(headless, Ordering::Relaxed);
+}
@@ -3,6 +3,7 @@ use std::mem;
 use glad_gl::gl;
 
 use super::{
+    is_headless,
     material::Material,
     shaders::{Shader, ShaderDefinition},
 };
@@ -30,6 +31,9 @@ impl Context {
         shader_def: &'a T,
         shader_fn: impl FnOnce(&mut ShaderContext<T::Shader>),
     ) {
+        if is_headless() {
+            return;
+        }
         unsafe {
             let shader = shader_def.create_shader();
             gl::UseProgram(shader.shader_id());
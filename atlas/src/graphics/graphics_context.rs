@@ -1,19 +1,47 @@
 use std::sync::mpsc::Receiver;
 
 use glad_gl::gl;
-use glfw::{Action, Context, Glfw, InitError, WindowEvent};
+use glam::Vec2;
+use glfw::{Action, Context, Glfw, InitError, JoystickId, WindowEvent};
 
-use crate::graphics::context;
-use crate::{event_bus::EventSender, game_root::GameError, scene::SceneEvent};
+use crate::graphics::{context, is_headless, set_headless};
+use crate::input::key::{Axis, GamepadButton, Key, GAMEPAD_AXES, GAMEPAD_BUTTONS};
+use crate::{
+    event_bus::EventSender, game_root::GameError, scene::SceneEvent, settings::Settings,
+};
 
-pub struct GraphicsContext {
-    title: String,
+struct Window {
     glfw: Glfw,
     window: glfw::Window,
     event_channel: Receiver<(f64, WindowEvent)>,
+}
+
+pub struct GraphicsContext {
+    title: String,
+    window: Option<Window>,
+    dimensions: (i32, i32),
     tracked_mouse_pos: (f32, f32),
+    // Set when the cursor jumps without the player moving the mouse, e.g. after a lock
+    motion_reset: bool,
+    controllers: Vec<Controller>,
+}
+
+// Last reported state of a connected joystick, events are only sent for what changed
+struct Controller {
+    id: JoystickId,
+    name: String,
+    buttons: Vec<(GamepadButton, bool)>,
+    axes: Vec<(Axis, f32)>,
 }
 
+// Joysticks beyond these are ignored
+const JOYSTICKS: [JoystickId; 4] = [
+    JoystickId::Joystick1,
+    JoystickId::Joystick2,
+    JoystickId::Joystick3,
+    JoystickId::Joystick4,
+];
+
 impl From<InitError> for GameError {
     fn from(value: InitError) -> Self {
         GameError::new(&value.to_string())
@@ -29,8 +57,16 @@ pub enum IoEvent {
     LeftMouseRelease((f32, f32)),
     RightMousePress((f32, f32)),
     RightMouseRelease((f32, f32)),
-    KeyPressed(char),
-    KeyReleased(char),
+    MiddleMousePress((f32, f32)),
+    MiddleMouseRelease((f32, f32)),
+    MouseScroll((f32, f32)),
+    KeyPressed(Key),
+    KeyReleased(Key),
+    GamepadPressed(GamepadButton),
+    GamepadReleased(GamepadButton),
+    AxisMoved(Axis, f32),
+    GamepadConnected(String),
+    GamepadDisconnected(String),
     Other,
 }
 
@@ -41,25 +77,33 @@ pub enum ContextEvent {
 }
 
 impl GraphicsContext {
-    pub fn new(title: &str) -> Result<Self, GameError> {
+    pub fn new(title: &str, settings: &Settings) -> Result<Self, GameError> {
         let mut glfw = glfw::init(glfw::FAIL_ON_ERRORS)?;
 
+        let samples = settings.quality.samples();
+        glfw.window_hint(glfw::WindowHint::Samples(Some(samples).filter(|s| *s > 0)));
+        let (width, height) = settings.resolution;
         let (mut window, event_channel) = glfw
             .with_primary_monitor(|glfw, monitor| {
-                glfw.create_window(1920, 1080, title, glfw::WindowMode::FullScreen(monitor?))
+                let mode = match (settings.fullscreen, monitor) {
+                    (true, Some(monitor)) => glfw::WindowMode::FullScreen(monitor),
+                    _ => glfw::WindowMode::Windowed,
+                };
+                glfw.create_window(width, height, title, mode)
             })
             .ok_or(GameError::new("Failed to initialize window"))?;
 
         window.make_current();
+        glfw.set_swap_interval(swap_interval(settings.vsync));
 
         window.set_close_polling(true);
         window.set_cursor_pos_polling(true);
         window.set_size_polling(true);
+        window.set_content_scale_polling(true);
         window.set_mouse_button_polling(true);
+        window.set_scroll_polling(true);
         window.set_key_polling(true);
 
-        //glfw.set_swap_interval(glfw::SwapInterval::None);
-
         gl::load(|e| glfw.get_proc_address_raw(e) as *const std::os::raw::c_void);
 
         unsafe {
@@ -72,63 +116,202 @@ impl GraphicsContext {
             //gl::CullFace(gl::FRONT);
             gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
             gl::LineWidth(10.0);
+            if samples > 0 {
+                gl::Enable(gl::MULTISAMPLE);
+            }
         }
 
+        set_headless(false);
+
         Ok(GraphicsContext {
             title: String::from(title),
-            window,
-            event_channel,
-            glfw,
+            dimensions: window.get_size(),
+            window: Some(Window {
+                window,
+                event_channel,
+                glfw,
+            }),
             tracked_mouse_pos: (0.0, 0.0),
+            motion_reset: true,
+            controllers: vec![],
         })
     }
 
+    pub fn headless(title: &str) -> Self {
+        set_headless(true);
+
+        GraphicsContext {
+            title: String::from(title),
+            window: None,
+            dimensions: (1920, 1080),
+            tracked_mouse_pos: (0.0, 0.0),
+            motion_reset: true,
+            controllers: vec![],
+        }
+    }
+
     pub fn new_context(&self) -> context::Context {
         context::Context {}
     }
 
     pub fn poll_events(&mut self, event_sender: &mut EventSender) {
-        self.glfw.poll_events();
-        glfw::flush_messages(&self.event_channel).for_each(|(_time, event)| match event {
+        let Some(window) = self.window.as_mut() else {return};
+
+        window.glfw.poll_events();
+        glfw::flush_messages(&window.event_channel).for_each(|(_time, event)| match event {
             WindowEvent::CursorPos(x, y) => {
                 let (prev_x, prev_y) = self.tracked_mouse_pos;
-                event_sender.write(IoEvent::MouseMotion((x as f32 - prev_x, y as f32 - prev_y)));
+                if !self.motion_reset {
+                    event_sender
+                        .write(IoEvent::MouseMotion((x as f32 - prev_x, y as f32 - prev_y)));
+                }
+                self.motion_reset = false;
                 self.tracked_mouse_pos = (x as f32, y as f32);
                 event_sender.write(IoEvent::MousePositionChange((x as f32, y as f32)));
             }
-            WindowEvent::MouseButton(glfw::MouseButton::Button1, glfw::Action::Press, _) => {
-                event_sender.write(IoEvent::LeftMousePress(self.tracked_mouse_pos));
+            WindowEvent::MouseButton(button, action, _) => {
+                use glfw::MouseButton::{Button1, Button2, Button3};
+                let pos = self.tracked_mouse_pos;
+                let event = match (button, action) {
+                    (Button1, Action::Press) => IoEvent::LeftMousePress(pos),
+                    (Button1, Action::Release) => IoEvent::LeftMouseRelease(pos),
+                    (Button2, Action::Press) => IoEvent::RightMousePress(pos),
+                    (Button2, Action::Release) => IoEvent::RightMouseRelease(pos),
+                    (Button3, Action::Press) => IoEvent::MiddleMousePress(pos),
+                    (Button3, Action::Release) => IoEvent::MiddleMouseRelease(pos),
+                    _ => return,
+                };
+                event_sender.write(event);
             }
-            WindowEvent::MouseButton(glfw::MouseButton::Button2, glfw::Action::Press, _) => {
-                event_sender.write(IoEvent::RightMousePress(self.tracked_mouse_pos));
+            WindowEvent::Scroll(x, y) => {
+                event_sender.write(IoEvent::MouseScroll((x as f32, y as f32)));
             }
             WindowEvent::Close => {
                 event_sender.write(SceneEvent::Exit);
             }
             WindowEvent::Size(width, height) => {
+                self.dimensions = (width, height);
                 event_sender.write(ContextEvent::Resized(width, height));
             }
-            WindowEvent::Key(glfw::Key::Escape, _, Action::Release, _) => {
-                event_sender.write(ContextEvent::Close);
+            // Moving to a monitor with another DPI setting, the UI has to be laid out again
+            WindowEvent::ContentScale(_, _) => {
+                let (width, height) = self.dimensions;
+                event_sender.write(ContextEvent::Resized(width, height));
             }
             WindowEvent::Key(key, _, Action::Press, _) => {
-                event_sender.write(IoEvent::KeyPressed(key as u8 as char));
+                event_sender.write(IoEvent::KeyPressed(to_key(key)));
             }
             WindowEvent::Key(key, _, Action::Release, _) => {
-                event_sender.write(IoEvent::KeyReleased(key as u8 as char));
+                event_sender.write(IoEvent::KeyReleased(to_key(key)));
             }
             _ => {}
         });
+        self.poll_joysticks(event_sender);
+    }
+
+    // Joysticks are polled, presence and state are compared against the previous poll
+    fn poll_joysticks(&mut self, event_sender: &mut EventSender) {
+        let Some(window) = self.window.as_ref() else {return};
+
+        JOYSTICKS.iter().for_each(|&id| {
+            let joystick = window.glfw.get_joystick(id);
+            let known = self.controllers.iter().position(|controller| controller.id == id);
+
+            match (joystick.is_present(), known) {
+                (true, None) => {
+                    let name = joystick
+                        .get_gamepad_name()
+                        .or_else(|| joystick.get_name())
+                        .unwrap_or_default();
+                    println!("Controller connected: {}", name);
+                    event_sender.write(IoEvent::GamepadConnected(name.clone()));
+                    self.controllers.push(Controller {
+                        id,
+                        name,
+                        buttons: vec![],
+                        axes: vec![],
+                    });
+                }
+                (false, Some(index)) => {
+                    let controller = self.controllers.swap_remove(index);
+                    println!("Controller disconnected: {}", controller.name);
+                    // Nothing stays held or deflected after the cable is pulled
+                    controller.buttons.iter().filter(|(_, down)| *down).for_each(|(button, _)| {
+                        event_sender.write(IoEvent::GamepadReleased(*button))
+                    });
+                    controller.axes.iter().for_each(|(axis, _)| {
+                        event_sender.write(IoEvent::AxisMoved(*axis, 0.0))
+                    });
+                    event_sender.write(IoEvent::GamepadDisconnected(controller.name));
+                    return;
+                }
+                (false, None) => return,
+                _ => {}
+            }
+
+            let (buttons, axes) = read_joystick(&joystick);
+            let controller = self.controllers.iter_mut().find(|controller| controller.id == id);
+            let Some(controller) = controller else {return};
+            controller.update(buttons, axes, event_sender);
+        });
+    }
+
+    // Resolution, window mode and vsync change on the fly, multisampling waits for a restart
+    pub fn apply_settings(&mut self, settings: &Settings) {
+        let Some(Window { window, glfw, .. }) = self.window.as_mut() else {return};
+        let (width, height) = settings.resolution;
+        glfw.with_primary_monitor(|_, monitor| {
+            let video_mode = monitor.as_ref().and_then(|monitor| monitor.get_video_mode());
+            match (settings.fullscreen, monitor) {
+                (true, Some(monitor)) => window.set_monitor(
+                    glfw::WindowMode::FullScreen(monitor),
+                    0,
+                    0,
+                    width,
+                    height,
+                    None,
+                ),
+                // Windowed mode opens in the middle of the screen
+                _ => {
+                    let (x, y) = video_mode.map_or((0, 0), |mode| {
+                        (
+                            (mode.width as i32 - width as i32) / 2,
+                            (mode.height as i32 - height as i32) / 2,
+                        )
+                    });
+                    let mode = glfw::WindowMode::Windowed;
+                    window.set_monitor(mode, x.max(0), y.max(0), width, height, None)
+                }
+            }
+        });
+        glfw.set_swap_interval(swap_interval(settings.vsync));
+    }
+
+    // Resolutions of the primary monitor, largest first
+    pub fn resolutions(&mut self) -> Vec<(u32, u32)> {
+        let Some(Window { glfw, .. }) = self.window.as_mut() else {return vec![]};
+        let modes = glfw.with_primary_monitor(|_, monitor| {
+            monitor.map_or(vec![], |monitor| monitor.get_video_modes())
+        });
+        let mut resolutions: Vec<_> = modes.iter().map(|mode| (mode.width, mode.height)).collect();
+        resolutions.sort_by(|a, b| b.cmp(a));
+        resolutions.dedup();
+        resolutions
     }
 
     pub fn cursor_lock(&mut self, lock: bool) {
+        let Some(Window { window, .. }) = self.window.as_mut() else {return};
+        self.motion_reset = true;
         match lock {
-            true => self.window.set_cursor_mode(glfw::CursorMode::Disabled),
-            false => self.window.set_cursor_mode(glfw::CursorMode::Normal),
+            true => window.set_cursor_mode(glfw::CursorMode::Disabled),
+            false => window.set_cursor_mode(glfw::CursorMode::Normal),
         }
     }
 
     pub fn depth_write(&self, enabled: bool) {
+        if is_headless() {
+            return;
+        }
         unsafe {
             match enabled {
                 true => gl::DepthMask(gl::TRUE),
@@ -137,20 +320,219 @@ impl GraphicsContext {
         }
     }
 
+    // Takes the window size, on HiDPI screens the framebuffer behind it has more pixels
     pub fn set_viewport(&self, width: i32, height: i32) {
+        if is_headless() {
+            return;
+        }
+        let (scale_x, scale_y) = self.content_scale();
         unsafe {
-            gl::Viewport(0, 0, width, height);
+            gl::Viewport(
+                0,
+                0,
+                (width as f32 * scale_x) as i32,
+                (height as f32 * scale_y) as i32,
+            );
         }
     }
 
+    // Framebuffer pixels per window unit, cursor positions and `dimensions` are in window units
+    pub fn content_scale(&self) -> (f32, f32) {
+        let Some(Window { window, .. }) = self.window.as_ref() else {return (1.0, 1.0)};
+        let (framebuffer_width, framebuffer_height) = window.get_framebuffer_size();
+        let (width, height) = window.get_size();
+        (
+            framebuffer_width as f32 / width.max(1) as f32,
+            framebuffer_height as f32 / height.max(1) as f32,
+        )
+    }
+
+    // How much the UI grows on top of window units to follow the monitor's DPI setting,
+    // systems that already count window units in points (macOS) report 1 here
+    pub fn ui_scale(&self) -> f32 {
+        let Some(Window { window, .. }) = self.window.as_ref() else {return 1.0};
+        let (content_scale, _) = window.get_content_scale();
+        let (framebuffer_scale, _) = self.content_scale();
+        content_scale / framebuffer_scale.max(f32::EPSILON)
+    }
+
+    // Into the space of an orthogonal UI camera of the given size, top left origin and y down
+    pub fn to_ui(&self, (x, y): (f32, f32), (ui_width, ui_height): (f32, f32)) -> Vec2 {
+        let (width, height) = self.dimensions;
+        Vec2::new(
+            x / width.max(1) as f32 * ui_width,
+            y / height.max(1) as f32 * ui_height,
+        )
+    }
+
     pub fn display(&mut self) {
-        self.window.swap_buffers();
+        let Some(Window { window, .. }) = self.window.as_mut() else {return};
+        window.swap_buffers();
         unsafe {
             gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
         }
     }
 
     pub fn dimensions(&self) -> (i32, i32) {
-        self.window.get_size()
+        self.dimensions
+    }
+
+    pub fn is_headless(&self) -> bool {
+        self.window.is_none()
+    }
+}
+
+impl Controller {
+    fn update(
+        &mut self,
+        buttons: Vec<(GamepadButton, bool)>,
+        axes: Vec<(Axis, f32)>,
+        event_sender: &mut EventSender,
+    ) {
+        buttons.iter().for_each(|&(button, down)| {
+            let was_down = self
+                .buttons
+                .iter()
+                .any(|&(previous, previous_down)| previous == button && previous_down);
+            match (down, was_down) {
+                (true, false) => event_sender.write(IoEvent::GamepadPressed(button)),
+                (false, true) => event_sender.write(IoEvent::GamepadReleased(button)),
+                _ => {}
+            }
+        });
+        axes.iter().for_each(|&(axis, position)| {
+            let previous = self
+                .axes
+                .iter()
+                .find(|(previous, _)| *previous == axis)
+                .map(|(_, position)| *position);
+            if previous != Some(position) {
+                event_sender.write(IoEvent::AxisMoved(axis, position));
+            }
+        });
+        self.buttons = buttons;
+        self.axes = axes;
+    }
+}
+
+// Devices GLFW knows a gamepad mapping for report the standard layout, anything else raw indices
+fn read_joystick(joystick: &glfw::Joystick) -> (Vec<(GamepadButton, bool)>, Vec<(Axis, f32)>) {
+    if let Some(state) = joystick.get_gamepad_state().filter(|_| joystick.is_gamepad()) {
+        let buttons = GAMEPAD_BUTTONS
+            .iter()
+            .enumerate()
+            .map(|(index, &button)| {
+                let glfw_button = glfw::GamepadButton::from_i32(index as i32);
+                let down =
+                    glfw_button.map_or(false, |b| state.get_button_state(b) == Action::Press);
+                (button, down)
+            })
+            .collect();
+        let axes = GAMEPAD_AXES
+            .iter()
+            .enumerate()
+            .map(|(index, &axis)| {
+                let glfw_axis = glfw::GamepadAxis::from_i32(index as i32);
+                let position = glfw_axis.map_or(0.0, |a| state.get_axis(a));
+                // Triggers rest at -1, they read better as 0 to 1
+                match axis {
+                    Axis::LeftTrigger | Axis::RightTrigger => (axis, (position + 1.0) * 0.5),
+                    _ => (axis, position),
+                }
+            })
+            .collect();
+        return (buttons, axes);
+    }
+
+    let buttons = joystick
+        .get_buttons()
+        .into_iter()
+        .enumerate()
+        .map(|(index, state)| {
+            (GamepadButton::Joystick(index as u8), state == glfw::ffi::PRESS)
+        })
+        .collect();
+    let axes = joystick
+        .get_axes()
+        .into_iter()
+        .enumerate()
+        .map(|(index, position)| (Axis::Joystick(index as u8), position))
+        .collect();
+    (buttons, axes)
+}
+
+fn to_key(key: glfw::Key) -> Key {
+    match key {
+        glfw::Key::A => Key::A,
+        glfw::Key::B => Key::B,
+        glfw::Key::C => Key::C,
+        glfw::Key::D => Key::D,
+        glfw::Key::E => Key::E,
+        glfw::Key::F => Key::F,
+        glfw::Key::G => Key::G,
+        glfw::Key::H => Key::H,
+        glfw::Key::I => Key::I,
+        glfw::Key::J => Key::J,
+        glfw::Key::K => Key::K,
+        glfw::Key::L => Key::L,
+        glfw::Key::M => Key::M,
+        glfw::Key::N => Key::N,
+        glfw::Key::O => Key::O,
+        glfw::Key::P => Key::P,
+        glfw::Key::Q => Key::Q,
+        glfw::Key::R => Key::R,
+        glfw::Key::S => Key::S,
+        glfw::Key::T => Key::T,
+        glfw::Key::U => Key::U,
+        glfw::Key::V => Key::V,
+        glfw::Key::W => Key::W,
+        glfw::Key::X => Key::X,
+        glfw::Key::Y => Key::Y,
+        glfw::Key::Z => Key::Z,
+        glfw::Key::Num0 => Key::Num0,
+        glfw::Key::Num1 => Key::Num1,
+        glfw::Key::Num2 => Key::Num2,
+        glfw::Key::Num3 => Key::Num3,
+        glfw::Key::Num4 => Key::Num4,
+        glfw::Key::Num5 => Key::Num5,
+        glfw::Key::Num6 => Key::Num6,
+        glfw::Key::Num7 => Key::Num7,
+        glfw::Key::Num8 => Key::Num8,
+        glfw::Key::Num9 => Key::Num9,
+        glfw::Key::Space => Key::Space,
+        glfw::Key::Enter | glfw::Key::KpEnter => Key::Enter,
+        glfw::Key::Escape => Key::Escape,
+        glfw::Key::Tab => Key::Tab,
+        glfw::Key::Backspace => Key::Backspace,
+        glfw::Key::Up => Key::Up,
+        glfw::Key::Down => Key::Down,
+        glfw::Key::Left => Key::Left,
+        glfw::Key::Right => Key::Right,
+        glfw::Key::LeftShift => Key::LeftShift,
+        glfw::Key::RightShift => Key::RightShift,
+        glfw::Key::LeftControl => Key::LeftControl,
+        glfw::Key::RightControl => Key::RightControl,
+        glfw::Key::LeftAlt => Key::LeftAlt,
+        glfw::Key::RightAlt => Key::RightAlt,
+        glfw::Key::F1 => Key::F1,
+        glfw::Key::F2 => Key::F2,
+        glfw::Key::F3 => Key::F3,
+        glfw::Key::F4 => Key::F4,
+        glfw::Key::F5 => Key::F5,
+        glfw::Key::F6 => Key::F6,
+        glfw::Key::F7 => Key::F7,
+        glfw::Key::F8 => Key::F8,
+        glfw::Key::F9 => Key::F9,
+        glfw::Key::F10 => Key::F10,
+        glfw::Key::F11 => Key::F11,
+        glfw::Key::F12 => Key::F12,
+        _ => Key::Unknown,
+    }
+}
+
+fn swap_interval(vsync: bool) -> glfw::SwapInterval {
+    match vsync {
+        true => glfw::SwapInterval::Sync(1),
+        false => glfw::SwapInterval::None,
     }
 }
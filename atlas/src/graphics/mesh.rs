@@ -2,10 +2,7 @@ use std::{mem, ptr};
 
 use glad_gl::gl;
 
-
-
-
-
+use super::is_headless;
 use super::vertices::{
     buffer::{Buffer, BufferTarget},
     indices::{IndexGeometry},
@@ -80,12 +77,18 @@ impl<Vertex: BufferElement, Index: IndexGeometry> Mesh<Vertex, Index> {
     }
 
     pub fn bind(&self) {
+        if is_headless() {
+            return;
+        }
         unsafe {
             gl::BindVertexArray(self.vao);
         }
     }
 
     pub fn use_vao(&self, action: impl FnOnce()) {
+        if is_headless() {
+            return;
+        }
         unsafe {
             gl::BindVertexArray(self.vao);
             action();
@@ -98,6 +101,9 @@ impl<Vertex: BufferElement, Index: IndexGeometry> Mesh<Vertex, Index> {
     }
 
     fn create_vao() -> u32 {
+        if is_headless() {
+            return 0;
+        }
         unsafe {
             let mut vao = 0;
             gl::GenVertexArrays(1, &mut vao);
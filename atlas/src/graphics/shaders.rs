@@ -5,7 +5,7 @@ use std::{
 };
 
 use glad_gl::gl;
-use glam::{Mat4, Vec3};
+use glam::{Mat4, Vec3, Vec4};
 
 use crate::{
     game_root::GameError,
@@ -108,6 +108,14 @@ impl<Q: Shader> UniformLoader<Vec3> for Q {
     }
 }
 
+impl<Q: Shader> UniformLoader<Vec4> for Q {
+    fn load(&self, uniform: i32, value: Vec4) {
+        unsafe {
+            gl::Uniform4f(uniform, value.x, value.y, value.z, value.w);
+        }
+    }
+}
+
 pub fn build_shader(
     vertex: Option<CString>,
     geometry: Option<CString>,
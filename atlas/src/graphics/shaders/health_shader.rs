@@ -8,6 +8,7 @@ use super::{locate_uniform, Shader, ShaderDefinition, UniformLoader};
 struct Uniform {
     projection_view_model: i32,
     health: i32,
+    shield: i32,
 }
 
 #[derive(Clone, Default)]
@@ -35,6 +36,9 @@ impl HealthShader {
     pub fn health(&self, health: f32) {
         self.load(self.uniform.health, health);
     }
+    pub fn shield(&self, shield: f32) {
+        self.load(self.uniform.shield, shield);
+    }
 }
 
 impl ShaderDefinition for HealthShaderDefinition {
@@ -55,10 +59,13 @@ impl ShaderDefinition for HealthShaderDefinition {
         )?;
         let health = locate_uniform(shader_id, "health")
             .ok_or(GameError::new("Failed to find uniform 'health'"))?;
+        let shield = locate_uniform(shader_id, "shield")
+            .ok_or(GameError::new("Failed to find uniform 'shield'"))?;
 
         let uniform = Uniform {
             projection_view_model,
             health,
+            shield,
         };
 
         Ok(Self { shader_id, uniform })
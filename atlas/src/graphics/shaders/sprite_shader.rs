@@ -1,4 +1,4 @@
-use glam::Mat4;
+use glam::{Mat4, Vec4};
 
 use crate::game_root::GameError;
 
@@ -8,6 +8,7 @@ use super::{locate_uniform, Shader, ShaderDefinition, UniformLoader};
 struct Uniform {
     projection_view_model: i32,
     sprite_texture: i32,
+    tint: Option<i32>,
 }
 
 #[derive(Clone, Default)]
@@ -30,6 +31,13 @@ impl SpriteShader {
     pub fn sprite(&self, sprite: i32) {
         self.load(self.uniform.sprite_texture, sprite);
     }
+
+    // Shaders without a `tint` uniform draw the texture as it is
+    pub fn tint(&self, tint: Vec4) {
+        if let Some(uniform) = self.uniform.tint {
+            self.load(uniform, tint);
+        }
+    }
 }
 
 impl Shader for SpriteShader {
@@ -60,6 +68,7 @@ impl ShaderDefinition for SpriteShaderDefinition {
         let uniform = Uniform {
             projection_view_model,
             sprite_texture,
+            tint: locate_uniform(shader_id, "tint"),
         };
 
         Ok(Self { shader_id, uniform })
@@ -6,6 +6,8 @@ use image::GenericImageView;
 
 use crate::game_root::GameError;
 
+use super::is_headless;
+
 #[derive(Clone, Copy)]
 pub enum ChannelLayout {
     R8,
@@ -125,6 +127,13 @@ impl Texture {
         })
     }
 
+    // Replaces the contents, the texture id stays the same so every copy sees the new data
+    pub fn update(&mut self, buffer: &[u8], dimensions: (u32, u32)) {
+        self.bind();
+        Self::load_texture(buffer, self.channel_layout, dimensions);
+        self.dimensions = dimensions;
+    }
+
     pub fn texture(&self) -> u32 {
         self.texture_id
     }
@@ -135,6 +144,9 @@ impl Texture {
 
     fn create_texture() -> u32 {
         let mut texture_id = 0;
+        if is_headless() {
+            return texture_id;
+        }
         unsafe {
             gl::GenTextures(1, &mut texture_id);
             gl::BindTexture(gl::TEXTURE_2D, texture_id);
@@ -148,12 +160,18 @@ impl Texture {
     }
 
     pub fn bind(&self) {
+        if is_headless() {
+            return;
+        }
         unsafe {
             gl::BindTexture(gl::TEXTURE_2D, self.texture_id);
         }
     }
 
     fn load_texture(data: &[u8], channel_layout: ChannelLayout, dimensions: (u32, u32)) {
+        if is_headless() {
+            return;
+        }
         unsafe {
             gl::TexImage2D(
                 gl::TEXTURE_2D,
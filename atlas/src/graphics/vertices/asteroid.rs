@@ -5,8 +5,12 @@ use rand::Rng;
 
 use super::{indices::TriangleGeometry, layouts::PTNVertex};
 
-pub fn asteroid(radius: f32, details: usize) -> (Vec<PTNVertex>, Vec<TriangleGeometry>) {
-    let pos = pos_asteroid(radius, details);
+pub fn asteroid(
+    radius: f32,
+    details: usize,
+    rng: &mut impl Rng,
+) -> (Vec<PTNVertex>, Vec<TriangleGeometry>) {
+    let pos = pos_asteroid(radius, details, rng);
     let tex = tex_asteroid(details);
     let norm = norm_asteroid(details);
     let indices = index_asteroid(details);
@@ -21,8 +25,7 @@ pub fn asteroid(radius: f32, details: usize) -> (Vec<PTNVertex>, Vec<TriangleGeo
     (vertices, indices)
 }
 
-fn pos_asteroid(radius: f32, details: usize) -> Vec<[f32; 3]> {
-    let mut rng = rand::thread_rng();
+fn pos_asteroid(radius: f32, details: usize, rng: &mut impl Rng) -> Vec<[f32; 3]> {
     let radius = radius;
 
     let deformations: Vec<_> = (0..(rng.gen_range(10..20)))
@@ -2,6 +2,8 @@ use std::{marker::PhantomData, mem};
 
 use glad_gl::gl;
 
+use crate::graphics::is_headless;
+
 #[derive(Clone)]
 pub struct Buffer<T> {
     count: u32,
@@ -27,6 +29,14 @@ impl BufferTarget {
 
 impl<T> Buffer<T> {
     pub fn build(data: &[T], buffer_target: BufferTarget) -> Self {
+        if is_headless() {
+            return Self {
+                count: data.len() as u32,
+                buffer_handle: 0,
+                buffer_target,
+                data_type: PhantomData::default(),
+            };
+        }
         unsafe {
             let mut buffer_handle: u32 = 0;
             let size = (data.len() * mem::size_of::<T>()) as u32;
@@ -51,6 +61,10 @@ impl<T> Buffer<T> {
     }
 
     pub fn reload(&mut self, data: &[T]) {
+        if is_headless() {
+            self.count = data.len() as u32;
+            return;
+        }
         unsafe {
             let buffer_size = self.get_buffer_size();
 
@@ -71,6 +85,9 @@ impl<T> Buffer<T> {
     }
 
     pub fn bind(&self) {
+        if is_headless() {
+            return;
+        }
         unsafe {
             gl::BindBuffer(self.buffer_target.to_gl(), self.buffer_handle);
         }
@@ -1,23 +1,31 @@
 use super::{indices::TriangleGeometry, layouts::P2TVertex};
 
+// Hull arc with the thinner shield arc around it, the second texture coordinate tells them apart
 pub fn health_bar() -> (Vec<P2TVertex>, Vec<TriangleGeometry>) {
-    let (inner, outer) = (100.0, 125.0);
+    let (mut vertices, mut indices) = arc(100.0, 125.0, 0.0, 0);
+    let (shield_vertices, shield_indices) = arc(130.0, 140.0, 1.0, vertices.len() as u32);
+    vertices.extend(shield_vertices);
+    indices.extend(shield_indices);
 
+    (vertices, indices)
+}
+
+fn arc(inner: f32, outer: f32, layer: f32, offset: u32) -> (Vec<P2TVertex>, Vec<TriangleGeometry>) {
     let quarter = 3.1415926535 / 4.0;
     let hemi = 3.1416926535 / 2.0;
-    let vertices = (0..21)
+    let vertices: Vec<_> = (0..21)
         .map(|a| (hemi + quarter + hemi * a as f32 / 20.0, a as f32))
         .map(|(a, x)| {
             [
-                P2TVertex([inner * a.cos(), inner * a.sin()], [x / 20.0, 0.0]),
-                P2TVertex([outer * a.cos(), outer * a.sin()], [x / 20.0, 0.0]),
+                P2TVertex([inner * a.cos(), inner * a.sin()], [x / 20.0, layer]),
+                P2TVertex([outer * a.cos(), outer * a.sin()], [x / 20.0, layer]),
             ]
         })
         .flatten()
         .collect();
 
-    let indices = (0..200)
-        .map(|i| TriangleGeometry([i, i + 1, i + 2]))
+    let indices = (0..vertices.len() as u32 - 2)
+        .map(|i| TriangleGeometry([offset + i, offset + i + 1, offset + i + 2]))
         .collect();
 
     (vertices, indices)
@@ -0,0 +1,2 @@
+pub mod input_map;
+pub mod key;
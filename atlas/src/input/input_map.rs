@@ -0,0 +1,514 @@
+use std::{
+    collections::{HashMap, HashSet},
+    env, fs,
+    path::PathBuf,
+};
+
+use crate::{
+    event_bus::EventReader, game_root::GameError, graphics::graphics_context::IoEvent,
+    resource_manager::definition::Definition,
+};
+
+use super::key::{Axis, GamepadButton, Key, MouseButton};
+
+// Written out when the player has no binding file yet
+const DEFAULT_BINDINGS: &str = "
+bind thrust_forward key W
+bind thrust_back key S
+bind strafe_left key A
+bind strafe_right key D
+bind strafe_up key R
+bind strafe_down key V
+bind roll_left key Q
+bind roll_right key E
+bind yaw axis mouse_x
+bind pitch axis mouse_y
+bind boost key B
+bind boost key LeftShift
+bind flight_assist key X
+bind fire_primary key Space
+bind countermeasures key F
+bind weapon_1 key 1
+bind weapon_2 key 2
+bind weapon_3 key 3
+bind weapon_4 key 4
+bind weapon_5 key 5
+bind weapon_6 key 6
+bind weapon_7 key 7
+bind weapon_8 key 8
+bind weapon_9 key 9
+bind thrust_forward axis left_y -1
+bind strafe_right axis left_x
+bind yaw_rate axis right_x
+bind pitch_rate axis right_y
+bind roll_left pad left_bumper
+bind roll_right pad right_bumper
+bind strafe_up pad dpad_up
+bind strafe_down pad dpad_down
+bind weapon_next pad dpad_right
+bind weapon_previous pad dpad_left
+bind boost axis left_trigger
+bind fire_primary axis right_trigger
+bind countermeasures pad b
+bind flight_assist pad y
+bind fire_primary mouse left
+bind countermeasures mouse right
+bind weapon_next axis scroll_y
+bind weapon_previous axis scroll_y -1
+bind zoom_out axis scroll_y -1 with key LeftControl
+curve right_x 2
+curve right_y 2
+";
+
+// Analog axes closer to the centre than this read as zero unless the binding file says otherwise
+const DEFAULT_DEAD_ZONE: f32 = 0.15;
+// An axis bound to a button-like action holds it past this deflection, a wheel notch is 1
+const AXIS_PRESS: f32 = 0.5;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Input {
+    Key(Key),
+    Mouse(MouseButton),
+    Gamepad(GamepadButton),
+    Axis(Axis),
+}
+
+// A binding with a modifier only counts while the modifier is down, and while it does
+// it takes its input away from the bindings without one
+#[derive(Clone, Debug)]
+pub struct Binding {
+    pub action: String,
+    pub input: Input,
+    pub scale: f32,
+    pub modifier: Option<Input>,
+}
+
+// The dead zone is cut out and the rest rescaled to the full range before the curve is applied
+#[derive(Clone, Copy, Debug)]
+pub struct Response {
+    pub dead_zone: f32,
+    pub curve: f32,
+}
+
+#[derive(Clone, Copy, Default)]
+struct ActionState {
+    held: bool,
+    pressed: bool,
+    released: bool,
+    value: f32,
+}
+
+// Binds physical inputs to named actions, `update` turns the events of a tick into action states
+pub struct InputMap {
+    path: Option<PathBuf>,
+    bindings: Vec<Binding>,
+    responses: HashMap<Axis, Response>,
+    down: HashSet<Input>,
+    motion: HashMap<Axis, f32>,
+    analog: HashMap<Axis, f32>,
+    actions: HashMap<String, ActionState>,
+}
+
+impl InputMap {
+    pub fn parse(source: &str) -> Result<Self, GameError> {
+        let definition = Definition::parse(source);
+        let bindings = definition
+            .all("bind")
+            .into_iter()
+            .map(parse_binding)
+            .collect::<Result<_, _>>()?;
+
+        let mut responses: HashMap<Axis, Response> = HashMap::new();
+        for (key, values) in ["deadzone", "curve"]
+            .into_iter()
+            .flat_map(|key| definition.all(key).into_iter().map(move |values| (key, values)))
+        {
+            let error = || GameError::new(&format!("Invalid {} '{}'", key, values.join(" ")));
+            let [axis, value] = values else {return Err(error())};
+            let axis = Axis::from_name(axis).ok_or_else(error)?;
+            let value = value.parse().map_err(|_| error())?;
+
+            let response = responses.entry(axis).or_default();
+            match key {
+                "deadzone" => response.dead_zone = value,
+                _ => response.curve = value,
+            }
+        }
+
+        Ok(Self {
+            path: None,
+            bindings,
+            responses,
+            down: HashSet::new(),
+            motion: HashMap::new(),
+            analog: HashMap::new(),
+            actions: HashMap::new(),
+        })
+    }
+
+    // Kept next to the executable like the high scores, so the player can edit the bindings
+    pub fn load(name: &str) -> Result<Self, GameError> {
+        let mut path = env::current_exe()?;
+        path.set_file_name(name);
+
+        let Ok(contents) = fs::read_to_string(&path) else {
+            let map = Self {
+                path: Some(path),
+                ..Self::default()
+            };
+            map.save()?;
+            return Ok(map);
+        };
+        let mut map = Self::parse(&contents)?;
+        map.path = Some(path);
+        Ok(map)
+    }
+
+    pub fn save(&self) -> Result<(), GameError> {
+        let Some(path) = &self.path else {
+            return Err(GameError::new("Input map was not loaded from a file"));
+        };
+        let bindings = self.bindings.iter().map(|binding| {
+            let mut line = format!("bind {} {}", binding.action, binding.input.name());
+            if binding.scale != 1.0 {
+                line += &format!(" {}", binding.scale);
+            }
+            if let Some(modifier) = binding.modifier {
+                line += &format!(" with {}", modifier.name());
+            }
+            line + "\n"
+        });
+        // Sorted so saving an unchanged map writes the same file
+        let mut responses: Vec<_> = self.responses.iter().collect();
+        responses.sort_by_key(|(axis, _)| axis.name());
+        let responses = responses.into_iter().map(|(axis, response)| {
+            let axis = axis.name();
+            format!(
+                "deadzone {} {}\ncurve {} {}\n",
+                axis, response.dead_zone, axis, response.curve
+            )
+        });
+        let contents: String = bindings.chain(responses).collect();
+        Ok(fs::write(path, contents)?)
+    }
+
+    pub fn bindings(&self) -> &[Binding] {
+        &self.bindings
+    }
+
+    pub fn bind(&mut self, action: &str, input: Input, scale: f32, modifier: Option<Input>) {
+        self.bindings.push(Binding {
+            action: String::from(action),
+            input,
+            scale,
+            modifier,
+        });
+    }
+
+    pub fn unbind(&mut self, action: &str) {
+        self.bindings.retain(|binding| binding.action != action);
+    }
+
+    // Swaps the input of one binding, the other bindings of its action stay as they are
+    pub fn rebind(&mut self, index: usize, input: Input) {
+        if let Some(binding) = self.bindings.get_mut(index) {
+            binding.input = input;
+        }
+    }
+
+    pub fn response(&self, axis: Axis) -> Response {
+        self.responses.get(&axis).copied().unwrap_or_default()
+    }
+
+    pub fn set_response(&mut self, axis: Axis, response: Response) {
+        self.responses.insert(axis, response);
+    }
+
+    pub fn update(&mut self, event_reader: &EventReader) {
+        self.motion.clear();
+        event_reader.read(|event: IoEvent| self.handle(&event));
+        self.refresh();
+    }
+
+    // Also the way to drive the map with synthetic input, followed by `refresh`
+    pub fn handle(&mut self, event: &IoEvent) {
+        match *event {
+            IoEvent::KeyPressed(key) => self.press(Input::Key(key), true),
+            IoEvent::KeyReleased(key) => self.press(Input::Key(key), false),
+            IoEvent::LeftMousePress(_) => self.press(Input::Mouse(MouseButton::Left), true),
+            IoEvent::LeftMouseRelease(_) => self.press(Input::Mouse(MouseButton::Left), false),
+            IoEvent::RightMousePress(_) => self.press(Input::Mouse(MouseButton::Right), true),
+            IoEvent::RightMouseRelease(_) => self.press(Input::Mouse(MouseButton::Right), false),
+            IoEvent::MiddleMousePress(_) => self.press(Input::Mouse(MouseButton::Middle), true),
+            IoEvent::MiddleMouseRelease(_) => {
+                self.press(Input::Mouse(MouseButton::Middle), false)
+            }
+            IoEvent::GamepadPressed(button) => self.press(Input::Gamepad(button), true),
+            IoEvent::GamepadReleased(button) => self.press(Input::Gamepad(button), false),
+            IoEvent::MouseMotion((x, y)) => {
+                *self.motion.entry(Axis::MouseX).or_default() += x;
+                *self.motion.entry(Axis::MouseY).or_default() += y;
+            }
+            IoEvent::MouseScroll((x, y)) => {
+                *self.motion.entry(Axis::ScrollX).or_default() += x;
+                *self.motion.entry(Axis::ScrollY).or_default() += y;
+            }
+            IoEvent::AxisMoved(axis, position) => {
+                self.analog.insert(axis, position);
+            }
+            _ => {}
+        }
+    }
+
+    fn press(&mut self, input: Input, down: bool) {
+        match down {
+            true => self.down.insert(input),
+            false => self.down.remove(&input),
+        };
+    }
+
+    // Recomputes every action from the inputs that are down and the axis positions gathered so far
+    pub fn refresh(&mut self) {
+        let chorded: HashSet<Input> = self
+            .bindings
+            .iter()
+            .filter(|binding| binding.modifier.map_or(false, |m| self.down.contains(&m)))
+            .map(|binding| binding.input)
+            .collect();
+
+        let mut actions: HashMap<String, ActionState> = HashMap::new();
+        self.bindings.iter().for_each(|binding| {
+            let state = actions.entry(binding.action.clone()).or_default();
+            let active = match binding.modifier {
+                Some(modifier) => self.down.contains(&modifier),
+                None => !chorded.contains(&binding.input),
+            };
+            if !active {
+                return;
+            }
+
+            match binding.input {
+                Input::Axis(axis) => {
+                    let value = self.axis(axis) * binding.scale;
+                    state.value += value;
+                    state.held |= value > AXIS_PRESS;
+                }
+                input if self.down.contains(&input) => {
+                    state.held = true;
+                    state.value += binding.scale;
+                }
+                _ => {}
+            }
+        });
+
+        actions.iter_mut().for_each(|(action, state)| {
+            let was_held = self.actions.get(action).map_or(false, |state| state.held);
+            state.pressed = state.held && !was_held;
+            state.released = !state.held && was_held;
+        });
+        self.actions = actions;
+    }
+
+    pub fn held(&self, action: &str) -> bool {
+        self.state(action).held
+    }
+
+    // True only on the tick the action went down
+    pub fn pressed(&self, action: &str) -> bool {
+        self.state(action).pressed
+    }
+
+    pub fn released(&self, action: &str) -> bool {
+        self.state(action).released
+    }
+
+    // Sum of the held buttons and the axes bound to the action, each times its scale
+    pub fn value(&self, action: &str) -> f32 {
+        self.state(action).value
+    }
+
+    fn axis(&self, axis: Axis) -> f32 {
+        match axis.is_relative() {
+            true => self.motion.get(&axis).copied().unwrap_or_default(),
+            false => {
+                let position = self.analog.get(&axis).copied().unwrap_or_default();
+                self.response(axis).apply(position)
+            }
+        }
+    }
+
+    fn state(&self, action: &str) -> ActionState {
+        self.actions.get(action).copied().unwrap_or_default()
+    }
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        Self::parse(DEFAULT_BINDINGS).expect("Default bindings are valid")
+    }
+}
+
+impl Response {
+    pub fn apply(&self, position: f32) -> f32 {
+        let range = (1.0 - self.dead_zone).max(f32::EPSILON);
+        let magnitude = ((position.abs() - self.dead_zone) / range).clamp(0.0, 1.0);
+        magnitude.powf(self.curve) * position.signum()
+    }
+}
+
+impl Default for Response {
+    fn default() -> Self {
+        Self {
+            dead_zone: DEFAULT_DEAD_ZONE,
+            curve: 1.0,
+        }
+    }
+}
+
+// `bind action kind name [scale] [with kind name]`
+fn parse_binding(values: &[String]) -> Result<Binding, GameError> {
+    let error = || GameError::new(&format!("Invalid binding '{}'", values.join(" ")));
+    let [action, kind, name, rest @ ..] = values else {return Err(error())};
+    let input = parse_input(kind, name).ok_or_else(error)?;
+
+    let (mut rest, mut scale) = (rest, 1.0);
+    if let [value, remaining @ ..] = rest {
+        if value != "with" {
+            scale = value.parse().map_err(|_| error())?;
+            rest = remaining;
+        }
+    }
+    let modifier = match rest {
+        [] => None,
+        [with, kind, name] if with == "with" => Some(parse_input(kind, name).ok_or_else(error)?),
+        _ => return Err(error()),
+    };
+
+    Ok(Binding {
+        action: action.clone(),
+        input,
+        scale,
+        modifier,
+    })
+}
+
+fn parse_input(kind: &str, name: &str) -> Option<Input> {
+    match kind {
+        "key" => Key::from_name(name).map(Input::Key),
+        "mouse" => MouseButton::from_name(name).map(Input::Mouse),
+        "pad" => GamepadButton::from_name(name).map(Input::Gamepad),
+        "axis" => Axis::from_name(name).map(Input::Axis),
+        _ => None,
+    }
+}
+
+impl Input {
+    pub fn name(&self) -> String {
+        match self {
+            Input::Key(key) => format!("key {}", key.name()),
+            Input::Mouse(button) => format!("mouse {}", button.name()),
+            Input::Gamepad(button) => format!("pad {}", button.name()),
+            Input::Axis(axis) => format!("axis {}", axis.name()),
+        }
+    }
+
+    // The input an event pushes down, used to bind whatever the player presses next
+    pub fn pressed_by(event: &IoEvent) -> Option<Self> {
+        match *event {
+            IoEvent::KeyPressed(key) => Some(Input::Key(key)),
+            IoEvent::LeftMousePress(_) => Some(Input::Mouse(MouseButton::Left)),
+            IoEvent::RightMousePress(_) => Some(Input::Mouse(MouseButton::Right)),
+            IoEvent::MiddleMousePress(_) => Some(Input::Mouse(MouseButton::Middle)),
+            IoEvent::GamepadPressed(button) => Some(Input::Gamepad(button)),
+            IoEvent::AxisMoved(axis, position) if position.abs() > AXIS_PRESS => {
+                Some(Input::Axis(axis))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(bindings: &str) -> InputMap {
+        InputMap::parse(bindings).expect("Test bindings are valid")
+    }
+
+    fn send(map: &mut InputMap, event: IoEvent) {
+        map.handle(&event);
+        map.refresh();
+    }
+
+    fn assert_near(value: f32, expected: f32) {
+        assert!((value - expected).abs() < 1e-5, "{} != {}", value, expected);
+    }
+
+    #[test]
+    fn dead_zone_cuts_the_centre_and_rescales_the_rest() {
+        let mut input = map("bind strafe axis left_x");
+
+        send(&mut input, IoEvent::AxisMoved(Axis::LeftX, 0.1));
+        assert_near(input.value("strafe"), 0.0);
+
+        send(&mut input, IoEvent::AxisMoved(Axis::LeftX, 0.575));
+        assert_near(input.value("strafe"), 0.5);
+
+        send(&mut input, IoEvent::AxisMoved(Axis::LeftX, -1.0));
+        assert_near(input.value("strafe"), -1.0);
+    }
+
+    #[test]
+    fn curve_bends_the_response_and_keeps_the_sign() {
+        let mut input = map("bind yaw axis right_x\ndeadzone right_x 0\ncurve right_x 2");
+
+        send(&mut input, IoEvent::AxisMoved(Axis::RightX, 0.5));
+        assert_near(input.value("yaw"), 0.25);
+
+        send(&mut input, IoEvent::AxisMoved(Axis::RightX, -0.5));
+        assert_near(input.value("yaw"), -0.25);
+    }
+
+    #[test]
+    fn trigger_holds_its_action_past_the_press_threshold() {
+        let mut input = map("bind fire axis right_trigger\ndeadzone right_trigger 0");
+
+        send(&mut input, IoEvent::AxisMoved(Axis::RightTrigger, 0.4));
+        assert!(!input.held("fire"));
+        assert!(!input.pressed("fire"));
+
+        send(&mut input, IoEvent::AxisMoved(Axis::RightTrigger, 0.6));
+        assert!(input.held("fire"));
+        assert!(input.pressed("fire"));
+
+        input.refresh();
+        assert!(input.held("fire"));
+        assert!(!input.pressed("fire"));
+
+        send(&mut input, IoEvent::AxisMoved(Axis::RightTrigger, 0.2));
+        assert!(!input.held("fire"));
+        assert!(input.released("fire"));
+    }
+
+    #[test]
+    fn gamepad_button_goes_through_pressed_held_and_released() {
+        let mut input = map("bind assist pad y");
+
+        send(&mut input, IoEvent::GamepadPressed(GamepadButton::Y));
+        assert!(input.pressed("assist"));
+        assert!(input.held("assist"));
+        assert_near(input.value("assist"), 1.0);
+
+        input.refresh();
+        assert!(!input.pressed("assist"));
+        assert!(input.held("assist"));
+
+        send(&mut input, IoEvent::GamepadReleased(GamepadButton::Y));
+        assert!(input.released("assist"));
+        assert!(!input.held("assist"));
+
+        input.refresh();
+        assert!(!input.released("assist"));
+    }
+}
@@ -0,0 +1,315 @@
+// Physical keys, independent of the windowing library and of the keyboard layout
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Key {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+    I,
+    J,
+    K,
+    L,
+    M,
+    N,
+    O,
+    P,
+    Q,
+    R,
+    S,
+    T,
+    U,
+    V,
+    W,
+    X,
+    Y,
+    Z,
+    Num0,
+    Num1,
+    Num2,
+    Num3,
+    Num4,
+    Num5,
+    Num6,
+    Num7,
+    Num8,
+    Num9,
+    Space,
+    Enter,
+    Escape,
+    Tab,
+    Backspace,
+    Up,
+    Down,
+    Left,
+    Right,
+    LeftShift,
+    RightShift,
+    LeftControl,
+    RightControl,
+    LeftAlt,
+    RightAlt,
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+    Unknown,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+}
+
+// Buttons in the standard gamepad layout, devices without one (HOTAS, wheels) report raw indices
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum GamepadButton {
+    A,
+    B,
+    X,
+    Y,
+    LeftBumper,
+    RightBumper,
+    Back,
+    Start,
+    Guide,
+    LeftThumb,
+    RightThumb,
+    DpadUp,
+    DpadRight,
+    DpadDown,
+    DpadLeft,
+    Joystick(u8),
+}
+
+// Mouse and wheel axes are relative and only carry a value for the tick they arrived in,
+// the analog ones hold their position until the next change
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Axis {
+    MouseX,
+    MouseY,
+    ScrollX,
+    ScrollY,
+    LeftX,
+    LeftY,
+    RightX,
+    RightY,
+    LeftTrigger,
+    RightTrigger,
+    Joystick(u8),
+}
+
+// In the order of the GLFW gamepad layout
+pub const GAMEPAD_BUTTONS: [GamepadButton; 15] = [
+    GamepadButton::A,
+    GamepadButton::B,
+    GamepadButton::X,
+    GamepadButton::Y,
+    GamepadButton::LeftBumper,
+    GamepadButton::RightBumper,
+    GamepadButton::Back,
+    GamepadButton::Start,
+    GamepadButton::Guide,
+    GamepadButton::LeftThumb,
+    GamepadButton::RightThumb,
+    GamepadButton::DpadUp,
+    GamepadButton::DpadRight,
+    GamepadButton::DpadDown,
+    GamepadButton::DpadLeft,
+];
+
+pub const GAMEPAD_AXES: [Axis; 6] = [
+    Axis::LeftX,
+    Axis::LeftY,
+    Axis::RightX,
+    Axis::RightY,
+    Axis::LeftTrigger,
+    Axis::RightTrigger,
+];
+
+// Names used by the binding config, keys are spelled like on the keycaps
+const KEY_NAMES: [(Key, &str); 64] = [
+    (Key::A, "A"),
+    (Key::B, "B"),
+    (Key::C, "C"),
+    (Key::D, "D"),
+    (Key::E, "E"),
+    (Key::F, "F"),
+    (Key::G, "G"),
+    (Key::H, "H"),
+    (Key::I, "I"),
+    (Key::J, "J"),
+    (Key::K, "K"),
+    (Key::L, "L"),
+    (Key::M, "M"),
+    (Key::N, "N"),
+    (Key::O, "O"),
+    (Key::P, "P"),
+    (Key::Q, "Q"),
+    (Key::R, "R"),
+    (Key::S, "S"),
+    (Key::T, "T"),
+    (Key::U, "U"),
+    (Key::V, "V"),
+    (Key::W, "W"),
+    (Key::X, "X"),
+    (Key::Y, "Y"),
+    (Key::Z, "Z"),
+    (Key::Num0, "0"),
+    (Key::Num1, "1"),
+    (Key::Num2, "2"),
+    (Key::Num3, "3"),
+    (Key::Num4, "4"),
+    (Key::Num5, "5"),
+    (Key::Num6, "6"),
+    (Key::Num7, "7"),
+    (Key::Num8, "8"),
+    (Key::Num9, "9"),
+    (Key::Space, "Space"),
+    (Key::Enter, "Enter"),
+    (Key::Escape, "Escape"),
+    (Key::Tab, "Tab"),
+    (Key::Backspace, "Backspace"),
+    (Key::Up, "Up"),
+    (Key::Down, "Down"),
+    (Key::Left, "Left"),
+    (Key::Right, "Right"),
+    (Key::LeftShift, "LeftShift"),
+    (Key::RightShift, "RightShift"),
+    (Key::LeftControl, "LeftControl"),
+    (Key::RightControl, "RightControl"),
+    (Key::LeftAlt, "LeftAlt"),
+    (Key::RightAlt, "RightAlt"),
+    (Key::F1, "F1"),
+    (Key::F2, "F2"),
+    (Key::F3, "F3"),
+    (Key::F4, "F4"),
+    (Key::F5, "F5"),
+    (Key::F6, "F6"),
+    (Key::F7, "F7"),
+    (Key::F8, "F8"),
+    (Key::F9, "F9"),
+    (Key::F10, "F10"),
+    (Key::F11, "F11"),
+    (Key::F12, "F12"),
+    (Key::Unknown, "Unknown"),
+];
+
+impl Key {
+    pub fn name(&self) -> &'static str {
+        KEY_NAMES
+            .iter()
+            .find(|(key, _)| key == self)
+            .map_or("Unknown", |(_, name)| name)
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        KEY_NAMES
+            .iter()
+            .find(|(_, key)| *key == name)
+            .map(|(key, _)| *key)
+    }
+}
+
+impl MouseButton {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MouseButton::Left => "left",
+            MouseButton::Right => "right",
+            MouseButton::Middle => "middle",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "left" => Some(MouseButton::Left),
+            "right" => Some(MouseButton::Right),
+            "middle" => Some(MouseButton::Middle),
+            _ => None,
+        }
+    }
+}
+
+impl GamepadButton {
+    pub fn name(&self) -> String {
+        let name = match self {
+            GamepadButton::A => "a",
+            GamepadButton::B => "b",
+            GamepadButton::X => "x",
+            GamepadButton::Y => "y",
+            GamepadButton::LeftBumper => "left_bumper",
+            GamepadButton::RightBumper => "right_bumper",
+            GamepadButton::Back => "back",
+            GamepadButton::Start => "start",
+            GamepadButton::Guide => "guide",
+            GamepadButton::LeftThumb => "left_thumb",
+            GamepadButton::RightThumb => "right_thumb",
+            GamepadButton::DpadUp => "dpad_up",
+            GamepadButton::DpadRight => "dpad_right",
+            GamepadButton::DpadDown => "dpad_down",
+            GamepadButton::DpadLeft => "dpad_left",
+            GamepadButton::Joystick(index) => return format!("joy_{}", index),
+        };
+        String::from(name)
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        if let Some(index) = name.strip_prefix("joy_") {
+            return index.parse().ok().map(GamepadButton::Joystick);
+        }
+        GAMEPAD_BUTTONS
+            .iter()
+            .find(|button| button.name() == name)
+            .copied()
+    }
+}
+
+impl Axis {
+    pub fn name(&self) -> String {
+        let name = match self {
+            Axis::MouseX => "mouse_x",
+            Axis::MouseY => "mouse_y",
+            Axis::ScrollX => "scroll_x",
+            Axis::ScrollY => "scroll_y",
+            Axis::LeftX => "left_x",
+            Axis::LeftY => "left_y",
+            Axis::RightX => "right_x",
+            Axis::RightY => "right_y",
+            Axis::LeftTrigger => "left_trigger",
+            Axis::RightTrigger => "right_trigger",
+            Axis::Joystick(index) => return format!("joy_{}", index),
+        };
+        String::from(name)
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        if let Some(index) = name.strip_prefix("joy_") {
+            return index.parse().ok().map(Axis::Joystick);
+        }
+        [Axis::MouseX, Axis::MouseY, Axis::ScrollX, Axis::ScrollY]
+            .iter()
+            .chain(GAMEPAD_AXES.iter())
+            .find(|axis| axis.name() == name)
+            .copied()
+    }
+
+    pub fn is_relative(&self) -> bool {
+        matches!(
+            self,
+            Axis::MouseX | Axis::MouseY | Axis::ScrollX | Axis::ScrollY
+        )
+    }
+}
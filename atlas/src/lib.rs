@@ -1,12 +1,18 @@
 extern crate freetype;
 
+pub mod ai;
 pub mod components;
 pub mod entity_manager;
 pub mod event_bus;
 pub mod game_entities;
+pub mod game_mode;
 pub mod game_root;
 pub mod graphics;
+pub mod input;
 pub mod logger;
+pub mod random;
 pub mod resource_manager;
 pub mod scene;
+pub mod scripting;
+pub mod settings;
 pub mod systems;
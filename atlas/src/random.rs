@@ -0,0 +1,70 @@
+use std::{cell::RefCell, rc::Rc};
+
+use rand::{rngs::StdRng, Error, RngCore, SeedableRng};
+
+#[derive(Clone, Copy, Debug)]
+pub enum SimulationMode {
+    Realtime,
+    Deterministic(u64),
+}
+
+pub struct Random {
+    seed: u64,
+    mode: SimulationMode,
+}
+
+#[derive(Clone)]
+pub struct RandomStream {
+    rng: Rc<RefCell<StdRng>>,
+}
+
+impl Random {
+    pub fn new(mode: SimulationMode) -> Self {
+        let seed = match mode {
+            SimulationMode::Realtime => rand::thread_rng().next_u64(),
+            SimulationMode::Deterministic(seed) => seed,
+        };
+        Self { seed, mode }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn is_deterministic(&self) -> bool {
+        matches!(self.mode, SimulationMode::Deterministic(_))
+    }
+
+    // Seeded by name only, so adding a new stream doesn't shift the existing ones
+    pub fn stream(&self, name: &str) -> RandomStream {
+        RandomStream {
+            rng: Rc::new(RefCell::new(StdRng::seed_from_u64(
+                self.seed ^ fnv_hash(name),
+            ))),
+        }
+    }
+}
+
+impl RngCore for RandomStream {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.borrow_mut().next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.borrow_mut().next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.borrow_mut().fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.rng.borrow_mut().try_fill_bytes(dest)
+    }
+}
+
+fn fnv_hash(name: &str) -> u64 {
+    name.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
@@ -1,3 +1,4 @@
+pub mod definition;
 pub mod font;
 pub mod indexer;
 pub mod model;
@@ -0,0 +1,94 @@
+use std::{fs, path::PathBuf, str::FromStr};
+
+use glam::Vec3;
+
+use crate::game_root::GameError;
+
+// Plain text asset format: one `key value...` entry per line, `#` starts a comment
+pub struct Definition {
+    entries: Vec<(String, Vec<String>)>,
+}
+
+impl Definition {
+    pub fn parse(source: &str) -> Self {
+        let entries = source
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or_default())
+            .filter_map(|line| {
+                let mut tokens = line.split_whitespace().map(String::from);
+                Some((tokens.next()?, tokens.collect()))
+            })
+            .collect();
+
+        Self { entries }
+    }
+
+    pub fn load(path: &PathBuf) -> Result<Self, GameError> {
+        Ok(Self::parse(&fs::read_to_string(path)?))
+    }
+
+    pub fn all(&self, key: &str) -> Vec<&[String]> {
+        self.entries
+            .iter()
+            .filter(|(entry, _)| entry == key)
+            .map(|(_, values)| values.as_slice())
+            .collect()
+    }
+
+    pub fn has(&self, key: &str) -> bool {
+        self.entries.iter().any(|(entry, _)| entry == key)
+    }
+
+    pub fn string(&self, key: &str) -> Result<&str, GameError> {
+        self.all(key)
+            .first()
+            .and_then(|values| values.first())
+            .map(|value| value.as_str())
+            .ok_or(GameError::new(&format!("Missing '{}' entry", key)))
+    }
+
+    pub fn value<T: FromStr>(&self, key: &str) -> Result<T, GameError> {
+        parse(key, self.string(key)?)
+    }
+
+    pub fn value_or<T: FromStr>(&self, key: &str, default: T) -> Result<T, GameError> {
+        match self.has(key) {
+            true => self.value(key),
+            false => Ok(default),
+        }
+    }
+
+    pub fn values<T: FromStr>(&self, key: &str) -> Result<Vec<Vec<T>>, GameError> {
+        self.all(key)
+            .into_iter()
+            .map(|values| values.iter().map(|value| parse(key, value)).collect())
+            .collect()
+    }
+
+    pub fn vec3(&self, key: &str) -> Result<Vec3, GameError> {
+        self.all(key)
+            .first()
+            .ok_or(GameError::new(&format!("Missing '{}' entry", key)))
+            .and_then(|values| to_vec3(key, values))
+    }
+
+    pub fn vec3s(&self, key: &str) -> Result<Vec<Vec3>, GameError> {
+        self.all(key)
+            .into_iter()
+            .map(|values| to_vec3(key, values))
+            .collect()
+    }
+}
+
+fn parse<T: FromStr>(key: &str, value: &str) -> Result<T, GameError> {
+    value
+        .parse()
+        .map_err(|_| GameError::new(&format!("Invalid value '{}' for '{}'", value, key)))
+}
+
+fn to_vec3(key: &str, values: &[String]) -> Result<Vec3, GameError> {
+    match values {
+        [x, y, z] => Ok(Vec3::new(parse(key, x)?, parse(key, y)?, parse(key, z)?)),
+        _ => GameError::err(format!("'{}' expects three components", key)),
+    }
+}
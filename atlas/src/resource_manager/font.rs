@@ -1,6 +1,9 @@
-use std::{path::PathBuf};
+use std::{cell::RefCell, collections::HashMap, path::PathBuf, rc::Rc};
 
-use freetype::{face::LoadFlag, Library};
+use freetype::{
+    face::{KerningMode, LoadFlag},
+    Face, Library,
+};
 use glad_gl::gl;
 
 use crate::{
@@ -13,67 +16,139 @@ use crate::{
     },
 };
 
-use super::{ResourceLoader, ResourceManager};
+use super::ResourceLoader;
+
+// Last fallback of every font and the whole of the default one
+const DEFAULT_FONT: &[u8] = include_bytes!("../../fonts/DejaVuSans.ttf");
+const PIXEL_SIZE: u32 = 32;
+// The atlas keeps its width and doubles its height when a glyph no longer fits
+const ATLAS_WIDTH: usize = 512;
+const ATLAS_HEIGHT: usize = 256;
+// Empty texels around each glyph so filtering does not pick up its neighbours
+const GLYPH_PADDING: usize = 1;
 
 #[derive(Clone, Copy)]
-pub struct Character {
+pub struct Glyph {
+    face: usize,
+    index: u32,
     pub size: (usize, usize),
     pub bearing: (i32, i32),
-    pub advance: usize,
+    pub advance: f32,
+    // Top left corner in the atlas, in texels
+    pub position: (usize, usize),
+}
+
+// Glyphs are rasterized the first time some text uses them, from the first face that has them
+struct GlyphAtlas {
+    _library: Library,
+    faces: Vec<Face>,
+    glyphs: HashMap<char, Option<Glyph>>,
+    texture: Texture,
+    pixels: Vec<u8>,
+    height: usize,
+    // Shelf packing, glyphs go left to right in rows as tall as the tallest glyph in them
+    shelf: (usize, usize, usize),
+    dirty: bool,
 }
 
+// Copies share the atlas, so a glyph added for one text is there for all of them
 #[derive(Clone)]
 pub struct Font {
-    pub characters: [Character; 128],
-    pub texture: Texture,
-    texture_size: (usize, usize),
+    atlas: Rc<RefCell<GlyphAtlas>>,
 }
 
 impl Default for Font {
     fn default() -> Self {
-        todo!()
+        Self::new(&[]).expect("The built-in font loads")
     }
 }
 
 impl Font {
+    // Faces earlier in the list win, the built-in font is tried last
+    pub fn new(paths: &[PathBuf]) -> Result<Self, GameError> {
+        let library =
+            Library::init().map_err(|e| GameError::new(&format!("Failed to load font: {}", e)))?;
+
+        let mut faces = paths
+            .iter()
+            .map(|path| {
+                library
+                    .new_face(path, 0)
+                    .map_err(|_| GameError::new(&format!("Couldn't create font face '{:?}'", path)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let default = library
+            .new_memory_face(DEFAULT_FONT.to_vec(), 0)
+            .map_err(|_| GameError::new("Couldn't create the built-in font face"))?;
+        faces.push(default);
+
+        if faces
+            .iter()
+            .any(|face| face.set_pixel_sizes(0, PIXEL_SIZE).is_err())
+        {
+            return GameError::err(format!("Failed to set font size: '{:?}'", paths));
+        }
+
+        let pixels = vec![0; ATLAS_WIDTH * ATLAS_HEIGHT];
+        let texture = Texture::from_buff(
+            &pixels,
+            ChannelLayout::R8,
+            (ATLAS_WIDTH as u32, ATLAS_HEIGHT as u32),
+        )?;
+
+        Ok(Font {
+            atlas: Rc::new(RefCell::new(GlyphAtlas {
+                _library: library,
+                faces,
+                glyphs: HashMap::new(),
+                texture,
+                pixels,
+                height: ATLAS_HEIGHT,
+                shelf: (GLYPH_PADDING, GLYPH_PADDING, 0),
+                dirty: false,
+            })),
+        })
+    }
+
+    // Texture coordinates are in texels, so text built before the atlas grew stays valid
     pub fn render(&self, text: &str, target: &mut Mesh<P2TVertex, TriangleGeometry>) {
+        let mut atlas = self.atlas.borrow_mut();
         let (mut x, y) = (0.0, 0.0);
-        let char_quads: Vec<_> = text
-            .bytes()
-            .filter_map(|char| {
-                let glyph = self.characters.get(char as usize)?;
-                let (width, height) = self.texture_size;
-                let (rows, columns) = (8, 16);
-                let (column, row) = (char % columns, char / columns);
-
-                let (x1, y1) = (x + glyph.bearing.0 as f32, y - glyph.bearing.1 as f32);
-                let (x2, y2) = (x1 + glyph.size.0 as f32, y1 + glyph.size.1 as f32);
-                let (u1, v1) = (column as f32 / columns as f32, row as f32 / rows as f32);
-                let (u2, v2) = (
-                    u1 + (glyph.size.0 as f32 / width as f32),
-                    v1 + (glyph.size.1 as f32 / height as f32),
-                );
-
-                x += (glyph.advance >> 6) as f32;
-
-                Some([
+        let mut previous: Option<Glyph> = None;
+
+        let mut char_quads = vec![];
+        text.chars().for_each(|character| {
+            let Some(glyph) = atlas.glyph(character) else {return};
+            if let Some(previous) = previous {
+                x += atlas.kerning(previous, glyph);
+            }
+            previous = Some(glyph);
+
+            let (x1, y1) = (x + glyph.bearing.0 as f32, y - glyph.bearing.1 as f32);
+            let (x2, y2) = (x1 + glyph.size.0 as f32, y1 + glyph.size.1 as f32);
+            let (u1, v1) = (glyph.position.0 as f32, glyph.position.1 as f32);
+            let (u2, v2) = (u1 + glyph.size.0 as f32, v1 + glyph.size.1 as f32);
+
+            x += glyph.advance;
+
+            // Spaces only move the pen
+            if glyph.size.0 > 0 && glyph.size.1 > 0 {
+                char_quads.extend([
                     P2TVertex([x1, y1], [u1, v1]),
                     P2TVertex([x2, y1], [u2, v1]),
                     P2TVertex([x2, y2], [u2, v2]),
                     P2TVertex([x1, y2], [u1, v2]),
-                ])
-            })
-            .flatten()
-            .collect();
+                ]);
+            }
+        });
+        atlas.upload();
 
-        let char_quads_indices: Vec<_> = text
-            .bytes()
-            .enumerate()
-            .map(|(i, _)| {
-                let offset = i as u32 * 4;
+        let char_quads_indices: Vec<_> = (0..char_quads.len() as u32 / 4)
+            .map(|i| {
+                let offset = i * 4;
                 [
-                    TriangleGeometry([offset + 1, offset + 0, offset + 2]),
-                    TriangleGeometry([offset + 3, offset + 2, offset + 0]),
+                    TriangleGeometry([offset + 1, offset, offset + 2]),
+                    TriangleGeometry([offset + 3, offset + 2, offset]),
                 ]
             })
             .flatten()
@@ -84,11 +159,99 @@ impl Font {
     }
 }
 
+impl GlyphAtlas {
+    fn glyph(&mut self, character: char) -> Option<Glyph> {
+        if let Some(glyph) = self.glyphs.get(&character) {
+            return *glyph;
+        }
+        let glyph = self.rasterize(character);
+        self.glyphs.insert(character, glyph);
+        glyph
+    }
+
+    // Characters no face has are remembered as missing and skipped
+    fn rasterize(&mut self, character: char) -> Option<Glyph> {
+        let (face, index) = self.faces.iter().enumerate().find_map(|(face, font_face)| {
+            let index = font_face.get_char_index(character as usize);
+            (index != 0).then_some((face, index))
+        })?;
+        let font_face = &self.faces[face];
+        font_face.load_glyph(index, LoadFlag::RENDER).ok()?;
+
+        let slot = font_face.glyph();
+        let bitmap = slot.bitmap();
+        let size = (bitmap.width() as usize, bitmap.rows() as usize);
+        let bearing = (slot.bitmap_left(), slot.bitmap_top());
+        let advance = (slot.advance().x >> 6) as f32;
+        // Blank glyphs like the space have no buffer at all
+        let data = match size.0 > 0 && size.1 > 0 {
+            true => Vec::from(bitmap.buffer()),
+            false => vec![],
+        };
+        let pitch = bitmap.pitch().unsigned_abs() as usize;
+
+        let position = self.allocate(size);
+        write(
+            &mut self.pixels,
+            position,
+            ATLAS_WIDTH,
+            &data,
+            (size.0, size.1, pitch),
+        );
+        self.dirty = true;
+
+        Some(Glyph {
+            face,
+            index,
+            size,
+            bearing,
+            advance,
+            position,
+        })
+    }
+
+    fn allocate(&mut self, (width, height): (usize, usize)) -> (usize, usize) {
+        let (x, y, row_height) = &mut self.shelf;
+        if *x + width + GLYPH_PADDING > ATLAS_WIDTH {
+            *x = GLYPH_PADDING;
+            *y += *row_height + GLYPH_PADDING;
+            *row_height = 0;
+        }
+        let position = (*x, *y);
+        *x += width + GLYPH_PADDING;
+        *row_height = (*row_height).max(height);
+
+        while position.1 + height + GLYPH_PADDING > self.height {
+            self.height *= 2;
+            self.pixels.resize(ATLAS_WIDTH * self.height, 0);
+        }
+        position
+    }
+
+    // Kerning only applies between glyphs of the same face
+    fn kerning(&self, left: Glyph, right: Glyph) -> f32 {
+        let face = &self.faces[left.face];
+        if left.face != right.face || !face.has_kerning() {
+            return 0.0;
+        }
+        face.get_kerning(left.index, right.index, KerningMode::KerningDefault)
+            .map_or(0.0, |kerning| (kerning.x >> 6) as f32)
+    }
+
+    fn upload(&mut self) {
+        if self.dirty {
+            let dimensions = (ATLAS_WIDTH as u32, self.height as u32);
+            self.texture.update(&self.pixels, dimensions);
+            self.dirty = false;
+        }
+    }
+}
+
 impl Material for Font {
     fn bind(&self) {
         unsafe {
             gl::ActiveTexture(gl::TEXTURE0);
-            self.texture.bind()
+            self.atlas.borrow().texture.bind()
         }
     }
 }
@@ -100,101 +263,20 @@ impl ResourceLoader for Font {
         path.extension().map_or(false, |e| e == "font")
     }
 
+    // Every otf and ttf file in the directory is used, ordered by name, the first one is the main face
     fn load_resource(contents: &[PathBuf]) -> Result<Self::Resource, GameError> {
-        let mut freetype_lib =
-            Library::init().map_err(|e| GameError::new(&format!("Failed to load font: {}", e)))?;
-
-        let filename = contents
+        let mut files: Vec<_> = contents
             .iter()
-            .find(|f| f.extension().map_or(false, |f| f == "otf" || f == "ttf"))
-            .ok_or(GameError::new("No otf or ttf file found"))?;
-
-        load_font_file(filename, &mut freetype_lib)
-    }
-}
+            .filter(|f| f.extension().map_or(false, |f| f == "otf" || f == "ttf"))
+            .cloned()
+            .collect();
+        if files.is_empty() {
+            return Err(GameError::new("No otf or ttf file found"));
+        }
+        files.sort();
 
-fn load_font_file(path: &PathBuf, freetype: &mut Library) -> Result<Font, GameError> {
-    let Ok(face) = freetype.new_face(path, 0) else {return Err(GameError::new("Couldn't create new font face"))};
-    if face.set_pixel_sizes(0, 32).is_err() {
-        return GameError::err(format!("Failed to read font file: '{:?}'", path));
+        Font::new(&files)
     }
-
-    let characters: Vec<_> = (0..128)
-        .filter_map(|i| {
-            face.load_char(i, LoadFlag::RENDER)
-                .map(|_| {
-                    let bitmap = Vec::from(face.glyph().bitmap().buffer());
-
-                    let advance = face.glyph().advance().x as usize;
-                    let size = (
-                        face.glyph().bitmap().width() as usize,
-                        face.glyph().bitmap().rows() as usize,
-                    );
-                    let bearing = (
-                        face.glyph().bitmap_left() as i32,
-                        face.glyph().bitmap_top() as i32,
-                    );
-
-                    (
-                        Character {
-                            advance,
-                            size,
-                            bearing,
-                        },
-                        bitmap,
-                    )
-                })
-                .ok()
-        })
-        .collect();
-
-    let max_width = characters
-        .iter()
-        .map(|(char, _)| char.size.0 as usize)
-        .max()
-        .ok_or(GameError::new("Failed to read max glyph width"))?;
-
-    let max_height = characters
-        .iter()
-        .map(|(char, _)| char.size.1 as usize)
-        .max()
-        .ok_or(GameError::new("Failed to read max glyph height"))?;
-
-    let (columns, rows) = (16, 8);
-    let (width, height) = (columns * max_width, rows * max_height);
-    let mut texture_buffer: Vec<u8> = vec![0; width * height];
-
-    characters
-        .iter()
-        .enumerate()
-        .for_each(|(i, (char, bitmap))| {
-            let row = (i / columns) * max_height;
-            let column = (i % columns) * max_width;
-
-            write(
-                &mut texture_buffer,
-                (column, row),
-                width,
-                bitmap,
-                (char.size.0 as usize, char.size.1 as usize),
-            );
-        });
-
-    let texture = Texture::from_buff(
-        texture_buffer.as_slice(),
-        ChannelLayout::R8,
-        (width as u32, height as u32),
-    )?;
-
-    let characters: Vec<_> = characters.iter().map(|(char, _)| *char).collect();
-
-    Ok(Font {
-        characters: characters
-            .try_into()
-            .map_err(|_e| GameError::new(&format!("Failed to read characters")))?,
-        texture,
-        texture_size: (width, height),
-    })
 }
 
 fn write(
@@ -202,10 +284,10 @@ fn write(
     (x, y): (usize, usize),
     buffer_width: usize,
     data: &[u8],
-    (data_width, data_height): (usize, usize),
+    (data_width, data_height, data_pitch): (usize, usize, usize),
 ) {
     (0..data_width).for_each(|x1| {
         (0..data_height)
-            .for_each(|y1| buffer[(y + y1) * buffer_width + (x + x1)] = data[y1 * data_width + x1])
+            .for_each(|y1| buffer[(y + y1) * buffer_width + (x + x1)] = data[y1 * data_pitch + x1])
     })
 }
@@ -1,4 +1,7 @@
-use crate::{game_root::GameError, graphics::graphics_context::GraphicsContext, scene::Scene};
+use crate::{
+    game_root::GameError, graphics::graphics_context::GraphicsContext, random::Random,
+    scene::{Scene, Summary}, settings::Settings,
+};
 use std::collections::HashMap;
 
 impl From<std::io::Error> for GameError {
@@ -7,7 +10,13 @@ impl From<std::io::Error> for GameError {
     }
 }
 
-pub type SceneInitializer = fn(&mut GraphicsContext) -> Result<Box<dyn Scene>, GameError>;
+// The summary is that of the game that just ended, if the scene comes after a game over
+pub type SceneInitializer = fn(
+    &mut GraphicsContext,
+    &Random,
+    &Settings,
+    Option<Summary>,
+) -> Result<Box<dyn Scene>, GameError>;
 
 pub struct SceneManager {
     scene_initializers: HashMap<String, SceneInitializer>,
@@ -42,9 +51,12 @@ impl SceneManager {
         &mut self,
         res_id: &str,
         graphics_context: &mut GraphicsContext,
+        random: &Random,
+        settings: &Settings,
+        summary: Option<Summary>,
     ) -> Result<Box<dyn Scene>, GameError> {
         if let Some(scene_initializer) = self.scene_initializers.get(res_id) {
-            match scene_initializer(graphics_context) {
+            match scene_initializer(graphics_context, random, settings, summary) {
                 Ok(scene) => Ok(scene),
                 Err(e) => Err(GameError::new(&format!(
                     "Failed to initialize scene: '{}': {}",
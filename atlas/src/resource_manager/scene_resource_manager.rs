@@ -6,8 +6,13 @@ use std::{
 };
 
 use crate::{
+    ai::behaviour_tree::BehaviourTree,
+    components::{ship_systems::ShipSystems, weapon::Weapon},
+    game_mode::{loot::DropTable, mission::Mission, waves::WaveTable},
     game_root::GameError,
+    scripting::script::Script,
     graphics::{
+        is_headless,
         material::{
             bullet_material::BulletMaterial, particle_material::ParticleMaterial,
             phong_material::PhongMaterial, skybox_material::SkyboxMaterial,
@@ -79,14 +84,16 @@ impl SceneResourceManager {
         res_man.build_resource::<Font>(&resource_index);
 
         //Shaders
-        res_man.build_resource::<SpriteShaderDefinition>(&resource_index);
-        res_man.build_resource::<SkyboxShaderDefinition>(&resource_index);
-        res_man.build_resource::<MeshShaderDefinition>(&resource_index);
-        res_man.build_resource::<FlatShaderDefinition>(&resource_index);
-        res_man.build_resource::<TextShaderDefinition>(&resource_index);
-        res_man.build_resource::<BulletShaderDefinition>(&resource_index);
-        res_man.build_resource::<ParticleShaderDefinition>(&resource_index);
-        res_man.build_resource::<HealthShaderDefinition>(&resource_index);
+        if !is_headless() {
+            res_man.build_resource::<SpriteShaderDefinition>(&resource_index);
+            res_man.build_resource::<SkyboxShaderDefinition>(&resource_index);
+            res_man.build_resource::<MeshShaderDefinition>(&resource_index);
+            res_man.build_resource::<FlatShaderDefinition>(&resource_index);
+            res_man.build_resource::<TextShaderDefinition>(&resource_index);
+            res_man.build_resource::<BulletShaderDefinition>(&resource_index);
+            res_man.build_resource::<ParticleShaderDefinition>(&resource_index);
+            res_man.build_resource::<HealthShaderDefinition>(&resource_index);
+        }
 
         //Materials
         res_man.build_resource::<SpriteMaterial>(&resource_index);
@@ -95,6 +102,19 @@ impl SceneResourceManager {
         res_man.build_resource::<ParticleMaterial>(&resource_index);
         res_man.build_resource::<BulletMaterial>(&resource_index);
 
+        //AI
+        res_man.build_resource::<BehaviourTree>(&resource_index);
+
+        //Gameplay
+        res_man.build_resource::<Weapon>(&resource_index);
+        res_man.build_resource::<ShipSystems>(&resource_index);
+        res_man.build_resource::<WaveTable>(&resource_index);
+        res_man.build_resource::<DropTable>(&resource_index);
+        res_man.build_resource::<Mission>(&resource_index);
+
+        //Scripting
+        res_man.build_resource::<Script>(&resource_index);
+
         Ok(res_man)
     }
 
@@ -120,18 +140,15 @@ impl SceneResourceManager {
             })
     }
 
+    // Test executables sit a directory deeper than the game, so the assets are looked up from
+    // the executable's directory upwards
     fn root_path(root: &str) -> Result<PathBuf, GameError> {
-        if let Ok(mut game_dir) = env::current_exe() {
-            game_dir.pop();
-            game_dir.pop();
-            game_dir.pop();
-            game_dir.push("flux");
-            game_dir.push("assets");
-            game_dir.push(root);
-            Ok(game_dir)
-        } else {
-            Err(GameError::new("Failed to read asset path"))
-        }
+        let exe = env::current_exe()?;
+        exe.ancestors()
+            .map(|dir| dir.join("flux").join("assets"))
+            .find(|assets| assets.is_dir())
+            .map(|assets| assets.join(root))
+            .ok_or(GameError::new("Failed to read asset path"))
     }
 }
 
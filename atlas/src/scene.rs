@@ -1,12 +1,46 @@
+use std::fmt;
+
 use crate::graphics::graphics_context::GraphicsContext;
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub enum SceneEvent {
-    NewScene(&'static str),
+    NewScene(String),
+    GameOver(Summary),
     RestartScene,
     Exit,
 }
 
+#[derive(Clone, Copy, Default)]
+pub struct Summary {
+    pub score: u64,
+    pub wave: usize,
+    pub kills: u32,
+    pub accuracy: f32,
+    pub survived: f32,
+    pub lives_lost: u32,
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} points, wave {}, {} kills, {:.0}% accuracy, survived {:.1}s, lost {} lives",
+            self.score,
+            self.wave,
+            self.kills,
+            self.accuracy * 100.0,
+            self.survived,
+            self.lives_lost
+        )
+    }
+}
+
 pub trait Scene {
     fn run(&mut self, graphics_context: &mut GraphicsContext) -> SceneEvent;
+
+    fn fixed_update(&mut self) {}
+
+    fn world_state(&self) -> String {
+        String::new()
+    }
 }
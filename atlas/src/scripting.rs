@@ -0,0 +1,3 @@
+pub mod sandbox;
+pub mod script;
+pub mod world;
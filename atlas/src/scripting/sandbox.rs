@@ -0,0 +1,30 @@
+use rhai::{module_resolvers::DummyModuleResolver, Engine};
+
+use super::world;
+
+// Budget for a single call into a script, a runaway loop fails the script instead of stalling the frame
+const MAX_OPERATIONS: u64 = 50_000;
+const MAX_CALL_LEVELS: usize = 32;
+const MAX_STRING_SIZE: usize = 4096;
+const MAX_COLLECTION_SIZE: usize = 1024;
+
+// Scripts only see the world API, there is no file access, no imports and no eval
+pub fn create_engine() -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_call_levels(MAX_CALL_LEVELS)
+        .set_max_expr_depths(64, 32)
+        .set_max_string_size(MAX_STRING_SIZE)
+        .set_max_array_size(MAX_COLLECTION_SIZE)
+        .set_max_map_size(MAX_COLLECTION_SIZE)
+        .set_module_resolver(DummyModuleResolver::new())
+        .disable_symbol("eval");
+    engine.on_print(|text| println!("[script] {}", text));
+    engine.on_debug(|text, source, position| {
+        println!("[script] {} {:?} @ {}", text, source, position)
+    });
+
+    world::register(&mut engine);
+    engine
+}
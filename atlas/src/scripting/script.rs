@@ -0,0 +1,29 @@
+use std::{fs, path::PathBuf};
+
+use crate::{
+    game_root::GameError,
+    resource_manager::{try_get_file, ResourceLoader},
+};
+
+// The path is kept so the script system can pick up edits while the game is running
+#[derive(Clone, Default)]
+pub struct Script {
+    pub path: PathBuf,
+    pub source: String,
+}
+
+impl ResourceLoader for Script {
+    type Resource = Script;
+
+    fn is_resource(path: &PathBuf) -> bool {
+        path.extension().map_or(false, |e| e == "script")
+    }
+
+    fn load_resource(contents: &[PathBuf]) -> Result<Self::Resource, GameError> {
+        let path = try_get_file("script", contents)?;
+        Ok(Script {
+            path: path.clone(),
+            source: fs::read_to_string(path)?,
+        })
+    }
+}
@@ -0,0 +1,237 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use glam::Vec3;
+use rhai::{Array, Dynamic, Engine, EvalAltResult, INT};
+
+use crate::{
+    components::{
+        physical_body::PhysicalBody,
+        transform::Transform,
+        unit::{Damage, DamageType, Unit},
+    },
+    event_bus::EventSender,
+    scene::SceneEvent,
+    systems::{
+        bullet_detonator::BulletEvent, mission_system::PrefabEvent, script_system::ScriptEvent,
+    },
+};
+
+// Component writes are queued and applied once every script had its turn
+pub enum Command {
+    Transform(usize, Transform),
+    Body(usize, Vec3, Vec3),
+    Health(usize, f32),
+}
+
+// Copy of the world taken before the scripts run, writes also land here so later reads see them
+pub struct Frame {
+    pub entity: Option<usize>,
+    pub time: f32,
+    pub transforms: HashMap<usize, Transform>,
+    pub bodies: HashMap<usize, PhysicalBody>,
+    pub units: HashMap<usize, Unit>,
+    pub commands: Vec<Command>,
+    event_sender: EventSender,
+}
+
+// Handle passed to every script call, clones share the same frame
+#[derive(Clone)]
+pub struct World {
+    pub frame: Rc<RefCell<Frame>>,
+}
+
+impl World {
+    pub fn new(event_sender: EventSender, time: f32) -> Self {
+        Self {
+            frame: Rc::new(RefCell::new(Frame {
+                entity: None,
+                time,
+                transforms: HashMap::new(),
+                bodies: HashMap::new(),
+                units: HashMap::new(),
+                commands: vec![],
+                event_sender,
+            })),
+        }
+    }
+
+    fn transform(&mut self, id: INT) -> Dynamic {
+        let frame = self.frame.borrow();
+        entity(id)
+            .and_then(|id| frame.transforms.get(&id))
+            .map_or(Dynamic::UNIT, |transform| Dynamic::from(*transform))
+    }
+
+    fn set_transform(&mut self, id: INT, transform: Transform) {
+        let Some(id) = entity(id) else {return};
+        let mut frame = self.frame.borrow_mut();
+        if let Some(current) = frame.transforms.get_mut(&id) {
+            *current = transform;
+            frame.commands.push(Command::Transform(id, transform));
+        }
+    }
+
+    fn body(&mut self, id: INT) -> Dynamic {
+        let frame = self.frame.borrow();
+        entity(id)
+            .and_then(|id| frame.bodies.get(&id))
+            .map_or(Dynamic::UNIT, |body| Dynamic::from(body.clone()))
+    }
+
+    // Only the momentum is writable, mass and inertia belong to the prefab
+    fn set_body(&mut self, id: INT, body: PhysicalBody) {
+        let Some(id) = entity(id) else {return};
+        let mut frame = self.frame.borrow_mut();
+        if let Some(current) = frame.bodies.get_mut(&id) {
+            current.momentum = body.momentum;
+            current.angular_momentum = body.angular_momentum;
+            frame
+                .commands
+                .push(Command::Body(id, body.momentum, body.angular_momentum));
+        }
+    }
+
+    fn unit(&mut self, id: INT) -> Dynamic {
+        let frame = self.frame.borrow();
+        entity(id)
+            .and_then(|id| frame.units.get(&id))
+            .map_or(Dynamic::UNIT, |unit| Dynamic::from(unit.clone()))
+    }
+
+    fn set_health(&mut self, id: INT, health: f32) {
+        let Some(id) = entity(id) else {return};
+        let mut frame = self.frame.borrow_mut();
+        if let Some(unit) = frame.units.get_mut(&id) {
+            unit.health = health.min(unit.max_health);
+            let health = unit.health;
+            frame.commands.push(Command::Health(id, health));
+        }
+    }
+
+    // Goes through the regular damage pipeline, shields and armour still apply
+    fn damage(&mut self, id: INT, amount: f32, kind: &str) -> Result<(), Box<EvalAltResult>> {
+        let kind = DamageType::parse(kind).ok_or(format!("Unknown damage type '{}'", kind))?;
+        let Some(id) = entity(id) else {return Ok(())};
+        let frame = self.frame.borrow();
+        let Some(transform) = frame.transforms.get(&id) else {return Ok(())};
+        let damage = Damage::new(amount, kind, transform.position, frame.entity);
+        frame.event_sender.write(BulletEvent::Damaged(id, damage));
+        Ok(())
+    }
+
+    fn units(&mut self) -> Array {
+        let mut units: Vec<_> = self.frame.borrow().units.keys().copied().collect();
+        units.sort();
+        units.into_iter().map(|id| Dynamic::from(id as INT)).collect()
+    }
+
+    fn find(&mut self, name: &str) -> INT {
+        let frame = self.frame.borrow();
+        frame
+            .units
+            .iter()
+            .filter(|(_, unit)| unit.name == name)
+            .map(|(id, _)| *id as INT)
+            .min()
+            .unwrap_or(-1)
+    }
+
+    fn spawn(&mut self, prefab: &str, position: Vec3, count: INT) {
+        let count = count.max(0) as u32;
+        self.frame
+            .borrow()
+            .event_sender
+            .write(PrefabEvent::Spawn(String::from(prefab), position, count));
+    }
+
+    fn signal(&mut self, name: &str) {
+        self.frame
+            .borrow()
+            .event_sender
+            .write(ScriptEvent::Signal(String::from(name)));
+    }
+
+    fn scene(&mut self, name: &str) {
+        self.frame
+            .borrow()
+            .event_sender
+            .write(SceneEvent::NewScene(String::from(name)));
+    }
+}
+
+pub fn register(engine: &mut Engine) {
+    engine
+        .register_type_with_name::<Vec3>("Vec3")
+        .register_fn("vec3", |x: f32, y: f32, z: f32| Vec3::new(x, y, z))
+        .register_get_set("x", |v: &mut Vec3| v.x, |v: &mut Vec3, x: f32| v.x = x)
+        .register_get_set("y", |v: &mut Vec3| v.y, |v: &mut Vec3, y: f32| v.y = y)
+        .register_get_set("z", |v: &mut Vec3| v.z, |v: &mut Vec3, z: f32| v.z = z)
+        .register_fn("+", |a: Vec3, b: Vec3| a + b)
+        .register_fn("-", |a: Vec3, b: Vec3| a - b)
+        .register_fn("*", |v: Vec3, scale: f32| v * scale)
+        .register_fn("length", |v: &mut Vec3| v.length())
+        .register_fn("normalize", |v: &mut Vec3| v.normalize_or_zero())
+        .register_fn("distance", |a: &mut Vec3, b: Vec3| a.distance(b))
+        .register_fn("to_string", |v: &mut Vec3| v.to_string());
+
+    engine
+        .register_type_with_name::<Transform>("Transform")
+        .register_get_set(
+            "position",
+            |t: &mut Transform| t.position,
+            |t: &mut Transform, position: Vec3| t.position = position,
+        )
+        .register_get_set(
+            "scale",
+            |t: &mut Transform| t.scale,
+            |t: &mut Transform, scale: Vec3| t.scale = scale,
+        )
+        .register_get("forward", |t: &mut Transform| t.rotation * Vec3::NEG_Z);
+
+    engine
+        .register_type_with_name::<PhysicalBody>("PhysicalBody")
+        .register_get_set(
+            "momentum",
+            |b: &mut PhysicalBody| b.momentum,
+            |b: &mut PhysicalBody, momentum: Vec3| b.momentum = momentum,
+        )
+        .register_get_set(
+            "angular_momentum",
+            |b: &mut PhysicalBody| b.angular_momentum,
+            |b: &mut PhysicalBody, momentum: Vec3| b.angular_momentum = momentum,
+        )
+        .register_get("mass", |b: &mut PhysicalBody| b.mass)
+        .register_get("velocity", |b: &mut PhysicalBody| b.velocity());
+
+    engine
+        .register_type_with_name::<Unit>("Unit")
+        .register_get("name", |u: &mut Unit| u.name.clone())
+        .register_get("unit_type", |u: &mut Unit| u.unit_type.clone())
+        .register_get("health", |u: &mut Unit| u.health)
+        .register_get("max_health", |u: &mut Unit| u.max_health)
+        .register_get("shield", |u: &mut Unit| u.shield.strength);
+
+    engine
+        .register_type_with_name::<World>("World")
+        .register_get("entity", |w: &mut World| {
+            w.frame.borrow().entity.map_or(-1, |id| id as INT)
+        })
+        .register_get("time", |w: &mut World| w.frame.borrow().time)
+        .register_fn("transform", World::transform)
+        .register_fn("set_transform", World::set_transform)
+        .register_fn("body", World::body)
+        .register_fn("set_body", World::set_body)
+        .register_fn("unit", World::unit)
+        .register_fn("set_health", World::set_health)
+        .register_fn("damage", World::damage)
+        .register_fn("units", World::units)
+        .register_fn("find", World::find)
+        .register_fn("spawn", World::spawn)
+        .register_fn("signal", World::signal)
+        .register_fn("scene", World::scene);
+}
+
+// Scripts use -1 for "no entity"
+fn entity(id: INT) -> Option<usize> {
+    usize::try_from(id).ok()
+}
@@ -0,0 +1,166 @@
+use std::{env, fs, path::PathBuf};
+
+use crate::{game_root::GameError, logger::Logger, resource_manager::definition::Definition};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Quality {
+    Low,
+    Medium,
+    High,
+}
+
+pub const QUALITIES: [Quality; 3] = [Quality::Low, Quality::Medium, Quality::High];
+
+impl Quality {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Quality::Low => "low",
+            Quality::Medium => "medium",
+            Quality::High => "high",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        QUALITIES.into_iter().find(|quality| quality.name() == name)
+    }
+
+    // Multisampling is picked when the window is created, so it changes after a restart
+    pub fn samples(&self) -> u32 {
+        match self {
+            Quality::Low => 0,
+            Quality::Medium => 2,
+            Quality::High => 4,
+        }
+    }
+}
+
+// Player settings, kept in the user's config directory as `key value` lines
+#[derive(Clone, Debug)]
+pub struct Settings {
+    path: Option<PathBuf>,
+    pub resolution: (u32, u32),
+    pub fullscreen: bool,
+    pub vsync: bool,
+    // Vertical field of view in degrees
+    pub fov: f32,
+    pub mouse_speed: f32,
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub effects_volume: f32,
+    pub quality: Quality,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            path: None,
+            resolution: (1920, 1080),
+            fullscreen: true,
+            vsync: true,
+            fov: 57.0,
+            mouse_speed: 0.01,
+            master_volume: 1.0,
+            music_volume: 0.8,
+            effects_volume: 1.0,
+            quality: Quality::High,
+        }
+    }
+}
+
+impl Settings {
+    // Missing entries keep their defaults, so an old file still loads after new settings appear
+    pub fn parse(source: &str) -> Result<Self, GameError> {
+        let definition = Definition::parse(source);
+        let default = Self::default();
+
+        let resolution = match definition.values::<u32>("resolution")?.first().map(Vec::as_slice) {
+            Some([width, height]) => (*width, *height),
+            Some(_) => return Err(GameError::new("Resolution needs a width and a height")),
+            None => default.resolution,
+        };
+        let quality = match definition.has("quality") {
+            true => {
+                let name = definition.string("quality")?;
+                Quality::from_name(name)
+                    .ok_or(GameError::new(&format!("Unknown quality '{}'", name)))?
+            }
+            false => default.quality,
+        };
+
+        Ok(Settings {
+            path: None,
+            resolution,
+            fullscreen: definition.value_or("fullscreen", default.fullscreen)?,
+            vsync: definition.value_or("vsync", default.vsync)?,
+            fov: definition.value_or("fov", default.fov)?,
+            mouse_speed: definition.value_or("mouse_speed", default.mouse_speed)?,
+            master_volume: definition.value_or("master_volume", default.master_volume)?,
+            music_volume: definition.value_or("music_volume", default.music_volume)?,
+            effects_volume: definition.value_or("effects_volume", default.effects_volume)?,
+            quality,
+        })
+    }
+
+    // A missing or broken file never stops the game, it starts on defaults and the file
+    // appears the first time the options are saved
+    pub fn load(game: &str, logger: &dyn Logger) -> Self {
+        let Some(path) = config_dir().map(|dir| dir.join(game).join("settings")) else {
+            logger.log_warning("No config directory found, settings will not be saved");
+            return Self::default();
+        };
+
+        let settings = match fs::read_to_string(&path) {
+            Ok(contents) => Self::parse(&contents).unwrap_or_else(|e| {
+                logger.log_error(&format!("Failed to parse {}: {}", path.display(), e));
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        };
+        Self {
+            path: Some(path),
+            ..settings
+        }
+    }
+
+    // Picks up what another scene saved in the meantime
+    pub fn reload(&mut self) -> Result<(), GameError> {
+        let Some(path) = self.path.clone() else {return Ok(())};
+        let Ok(contents) = fs::read_to_string(&path) else {return Ok(())};
+        *self = Self::parse(&contents)?;
+        self.path = Some(path);
+        Ok(())
+    }
+
+    pub fn save(&self) -> Result<(), GameError> {
+        let Some(path) = &self.path else {
+            return Err(GameError::new("Settings were not loaded from a file"));
+        };
+        let contents = format!(
+            "resolution {} {}\nfullscreen {}\nvsync {}\nfov {}\nmouse_speed {}\n\
+             master_volume {}\nmusic_volume {}\neffects_volume {}\nquality {}\n",
+            self.resolution.0,
+            self.resolution.1,
+            self.fullscreen,
+            self.vsync,
+            self.fov,
+            self.mouse_speed,
+            self.master_volume,
+            self.music_volume,
+            self.effects_volume,
+            self.quality.name()
+        );
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        Ok(fs::write(path, contents)?)
+    }
+}
+
+// `%APPDATA%` on Windows, `$XDG_CONFIG_HOME` or `~/.config` everywhere else
+fn config_dir() -> Option<PathBuf> {
+    let var = |name| env::var_os(name).filter(|value| !value.is_empty()).map(PathBuf::from);
+    match cfg!(windows) {
+        true => var("APPDATA"),
+        false => var("XDG_CONFIG_HOME").or_else(|| var("HOME").map(|home| home.join(".config"))),
+    }
+}
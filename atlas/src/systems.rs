@@ -13,3 +13,16 @@ pub mod bullet_detonator;
 pub mod health_renderer;
 pub mod asteroid_detonator;
 pub mod player_follower;
+pub mod state_dump;
+pub mod swarm_system;
+pub mod behaviour_system;
+pub mod weapon_system;
+pub mod missile_system;
+pub mod decoy_system;
+pub mod respawn_system;
+pub mod game_mode_system;
+pub mod mission_system;
+pub mod script_system;
+pub mod loot_system;
+pub mod power_system;
+pub mod layout_system;
@@ -1,33 +1,58 @@
-use std::cell::RefCell;
+use std::{cell::RefCell, f32::consts::{PI, TAU}};
 
 use glam::Vec3;
 use rand::Rng;
 
 use crate::{
-    components::particle_emitter::{Particle, ParticleEmitter, ParticleEmitterDefinition},
+    components::{
+        particle_emitter::{Particle, ParticleEmitter, ParticleEmitterDefinition},
+        transform::Transform,
+    },
     entity_manager::EntityManager,
     event_bus::EventReader,
-    game_entities::{asteroid::AsteroidEntity, explosion::Explosion},
+    game_entities::{asteroid::AsteroidEntity, enemy_ship::EnemyShip, explosion::Explosion},
     graphics::{instanced_mesh::InstancedMesh, vertices::generator},
+    random::RandomStream,
     resource_manager::{scene_resource_manager::SceneResourceManager, ResourceManager},
 };
 
-use super::bullet_detonator::UnitEvent;
+use super::bullet_detonator::{spawn_explosion, UnitEvent};
 
-pub fn detonate_asteroids(
+const FRAGMENTS: usize = 3;
+const FRAGMENT_SCALE: f32 = 0.55;
+const MIN_FRAGMENT_RADIUS: f32 = 4.0;
+const SEPARATION_SPEED: f32 = 12.0;
+
+pub fn detonate_units(
     entity_manager: &mut EntityManager,
     resource_manager: &mut SceneResourceManager,
     event_reader: &EventReader,
+    rng: &RandomStream,
 ) {
     event_reader.read(|event: UnitEvent| match event {
-        UnitEvent::Destroyed(id) => {
-            let position = entity_manager
-                .get::<AsteroidEntity>(id)
-                .map(|a| a.transform.position)
-                .unwrap();
+        UnitEvent::Destroyed(id, _) => {
+            // Destroyed ships leave nothing but a fireball behind
+            if let Some(ship) = entity_manager.get::<EnemyShip>(id) {
+                let position = ship.transform.position;
+                entity_manager.remove::<EnemyShip>(id);
+                spawn_explosion(entity_manager, resource_manager, "fireball", position, rng);
+                return;
+            }
+
+            let Some(asteroid) = entity_manager.get::<AsteroidEntity>(id) else {return};
+            let position = asteroid.transform.position;
+            let fragments = fragment(&asteroid.entity, position, &mut rng.clone());
 
             entity_manager.remove::<AsteroidEntity>(id);
 
+            if fragments.is_empty() {
+                spawn_explosion(entity_manager, resource_manager, "debris", position, rng);
+                return;
+            }
+            fragments.into_iter().for_each(|(fragment, transform)| {
+                entity_manager.add_at(fragment, transform);
+            });
+
             let (vertices, indices) = generator::quad(1.0, 1.0);
             let instanced_mesh = InstancedMesh::new(&vertices, &indices, &vec![]);
             let material = resource_manager.get("explosion").res;
@@ -38,7 +63,7 @@ pub fn detonate_asteroids(
                 },
                 material,
                 instanced_mesh,
-                Box::new(fire_ball(position)),
+                Box::new(fire_ball(position, rng.clone())),
             );
 
             entity_manager.add(Explosion {
@@ -49,8 +74,48 @@ pub fn detonate_asteroids(
     });
 }
 
-fn fire_ring(pos: Vec3) -> impl Fn(&mut Particle) {
-    let rng = RefCell::new(rand::thread_rng());
+// Fragments fly apart in the plane perpendicular to the last impact, the separation kicks cancel out
+// so together they carry exactly the momentum of the destroyed asteroid
+fn fragment(
+    asteroid: &AsteroidEntity,
+    position: Vec3,
+    rng: &mut RandomStream,
+) -> Vec<(AsteroidEntity, Transform)> {
+    let radius = asteroid.radius * FRAGMENT_SCALE;
+    if radius < MIN_FRAGMENT_RADIUS {
+        return vec![];
+    }
+
+    let axis = match asteroid.collider.last_impact.normalize_or_zero() {
+        Vec3::ZERO => Vec3::new(
+            rng.gen_range(-1.0..1.0),
+            rng.gen_range(-1.0..1.0),
+            rng.gen_range(-1.0..1.0),
+        )
+        .try_normalize()
+        .unwrap_or(Vec3::Y),
+        axis => axis,
+    };
+    let (tangent, bitangent) = axis.any_orthonormal_pair();
+    let phase = rng.gen_range(0.0..2.0 * PI);
+
+    (0..FRAGMENTS)
+        .map(|i| {
+            let angle = phase + 2.0 * PI * i as f32 / FRAGMENTS as f32;
+            let direction = tangent * angle.cos() + bitangent * angle.sin();
+
+            let mut fragment = asteroid.fragment(radius, FRAGMENTS, rng);
+            fragment.body.momentum = asteroid.body.momentum / FRAGMENTS as f32
+                + direction * SEPARATION_SPEED * fragment.body.mass;
+
+            let transform = Transform::pos(position + direction * asteroid.radius);
+            (fragment, transform)
+        })
+        .collect()
+}
+
+pub fn fire_ring(pos: Vec3, rng: RandomStream) -> impl Fn(&mut Particle) {
+    let rng = RefCell::new(rng);
     move |particle| {
         let mut rng = rng.try_borrow_mut().unwrap();
         particle.position = pos.to_array();
@@ -78,8 +143,8 @@ fn fire_ring(pos: Vec3) -> impl Fn(&mut Particle) {
     }
 }
 
-fn fire_ball(pos: Vec3) -> impl Fn(&mut Particle) {
-    let rng = RefCell::new(rand::thread_rng());
+pub fn fire_ball(pos: Vec3, rng: RandomStream) -> impl Fn(&mut Particle) {
+    let rng = RefCell::new(rng);
     move |particle| {
         let mut rng = rng.try_borrow_mut().unwrap();
         particle.position = pos.to_array();
@@ -105,3 +170,32 @@ fn fire_ball(pos: Vec3) -> impl Fn(&mut Particle) {
         particle.opacity_delta = 0.2;
     }
 }
+
+pub fn debris(pos: Vec3, rng: RandomStream) -> impl Fn(&mut Particle) {
+    let rng = RefCell::new(rng);
+    move |particle| {
+        let mut rng = rng.try_borrow_mut().unwrap();
+        particle.position = pos.to_array();
+        let (ang_x, ang_y) = (
+            rng.gen_range(0.0..TAU),
+            rng.gen_range(0.0..TAU),
+        );
+        let velocity = rng.gen_range(10.0..30.0);
+        particle.velocity = [
+            velocity * ang_x.cos() * ang_y.cos(),
+            velocity * ang_y.sin(),
+            velocity * ang_x.sin() * ang_y.cos(),
+        ];
+
+        let shade = rng.gen_range(0.3..0.5);
+        particle.color = [shade, shade, shade * 0.9, 0.9];
+
+        particle.tex = rng.gen_range(0..4) as f32 / 4.0;
+
+        particle.size = rng.gen_range(0.3..1.0);
+        particle.lifetime = 2.0;
+        particle.dampening = 0.5;
+
+        particle.opacity_delta = 0.5;
+    }
+}
@@ -0,0 +1,154 @@
+use std::f32::consts::FRAC_PI_2;
+
+use glam::Vec3;
+
+use crate::{
+    ai::behaviour_tree::{BehaviourAgent, Blackboard, BlackboardValue, Status},
+    components::{behaviour::Behaviour, swarm_agent::SteeringGoal},
+    entity_manager::EntityManager,
+    game_entities::{bullet::BulletEntity, enemy_ship::EnemyShip, player_ship::PlayerShip},
+};
+
+const WAYPOINT_REACHED: f32 = 10.0;
+const DEFAULT_PATROL_RADIUS: f32 = 50.0;
+
+struct EnemyAgent {
+    position: Vec3,
+    health: f32,
+    squad_centre: Vec3,
+    target: Option<(usize, Vec3)>,
+    incoming: Option<f32>,
+    goal: SteeringGoal,
+    release_decoys: bool,
+}
+
+pub fn update_behaviours(entity_manager: &mut EntityManager) {
+    let players: Vec<_> = entity_manager
+        .iter::<PlayerShip>()
+        .filter(|player| player.entity.lives.is_alive())
+        .map(|player| (player.id, player.transform.position))
+        .collect();
+
+    let missiles: Vec<_> = entity_manager
+        .iter::<BulletEntity>()
+        .filter_map(|bullet| {
+            let target = bullet.entity.missile.as_ref()?.target?;
+            Some((target, bullet.transform.position))
+        })
+        .collect();
+
+    let ships: Vec<_> = entity_manager
+        .iter::<EnemyShip>()
+        .map(|ship| (ship.entity.agent.squad, ship.transform.position))
+        .collect();
+
+    entity_manager.iter_mut::<EnemyShip>().for_each(|ship| {
+        let position = ship.transform.position;
+        let squad = ship.entity.agent.squad;
+        let squad_members: Vec<_> = ships
+            .iter()
+            .filter(|(other_squad, _)| *other_squad == squad)
+            .map(|(_, position)| *position)
+            .collect();
+
+        let mut agent = EnemyAgent {
+            position,
+            health: ship.entity.info.health / ship.entity.info.max_health,
+            squad_centre: squad_members.iter().sum::<Vec3>() / squad_members.len() as f32,
+            target: players.iter().copied().min_by(|(_, a), (_, b)| {
+                (*a - position)
+                    .length()
+                    .total_cmp(&(*b - position).length())
+            }),
+            incoming: missiles
+                .iter()
+                .filter(|(target, _)| *target == ship.id)
+                .map(|(_, missile)| (*missile - position).length())
+                .reduce(f32::min),
+            goal: ship.entity.agent.goal,
+            release_decoys: false,
+        };
+
+        let Behaviour { tree, blackboard } = &mut ship.entity.behaviour;
+        match agent.target {
+            Some((target, _)) => blackboard.set("target", BlackboardValue::Entity(target)),
+            None => blackboard.remove("target"),
+        }
+        blackboard.set("engaged", BlackboardValue::Bool(false));
+
+        tree.tick(&mut agent, blackboard);
+        ship.entity.agent.goal = agent.goal;
+        ship.entity.countermeasures.trigger = agent.release_decoys;
+    });
+}
+
+impl EnemyAgent {
+    fn target_distance(&self) -> Option<f32> {
+        self.target
+            .map(|(_, target)| (target - self.position).length())
+    }
+}
+
+impl BehaviourAgent for EnemyAgent {
+    fn condition(&self, name: &str, args: &[f32], _: &Blackboard) -> bool {
+        let arg = args.first().copied().unwrap_or_default();
+        match name {
+            "has_target" => self.target.is_some(),
+            "health_below" => self.health < arg,
+            "target_in_range" => self.target_distance().map_or(false, |d| d < arg),
+            "squad_scattered" => (self.squad_centre - self.position).length() > arg,
+            "missile_incoming" => self.incoming.map_or(false, |d| d < arg),
+            _ => false,
+        }
+    }
+
+    fn action(&mut self, name: &str, args: &[f32], blackboard: &mut Blackboard) -> Status {
+        match name {
+            "idle" => {
+                self.goal = SteeringGoal::Idle;
+                Status::Success
+            }
+            "patrol" => {
+                let radius = args.first().copied().unwrap_or(DEFAULT_PATROL_RADIUS);
+                let origin = blackboard.vector("patrol_origin").unwrap_or_else(|| {
+                    blackboard.set("patrol_origin", BlackboardValue::Vector(self.position));
+                    self.position
+                });
+                let mut waypoint = blackboard.float("waypoint").unwrap_or_default();
+                let angle = waypoint * FRAC_PI_2;
+                let target = origin + Vec3::new(angle.cos(), 0.0, angle.sin()) * radius;
+
+                if (target - self.position).length() < WAYPOINT_REACHED {
+                    waypoint = (waypoint + 1.0) % 4.0;
+                    blackboard.set("waypoint", BlackboardValue::Float(waypoint));
+                }
+                self.goal = SteeringGoal::Arrive(target);
+                Status::Running
+            }
+            "engage" => match self.target {
+                Some((target, _)) => {
+                    blackboard.set("engaged", BlackboardValue::Bool(true));
+                    self.goal = SteeringGoal::Pursue(target);
+                    Status::Running
+                }
+                None => Status::Failure,
+            },
+            "retreat" => match self.target {
+                Some((target, _)) => {
+                    self.goal = SteeringGoal::Evade(target);
+                    Status::Running
+                }
+                None => Status::Failure,
+            },
+            "release_decoys" => {
+                self.release_decoys = true;
+                Status::Success
+            }
+            "regroup" => {
+                self.goal = SteeringGoal::Seek(self.squad_centre);
+                Status::Running
+            }
+            _ => Status::Failure,
+        }
+    }
+}
@@ -1,38 +1,46 @@
-use std::{
-    cell::RefCell,
-    collections::{HashMap, HashSet},
-    mem,
-};
+use std::cell::RefCell;
 
 use glam::Vec3;
 use rand::Rng;
 
 use crate::{
     components::{
+        collider::Collider,
         particle_emitter::{Particle, ParticleEmitter, ParticleEmitterDefinition},
-        unit::Unit,
+        physical_body::PhysicalBody,
+        transform::Transform,
+        unit::{Damage, Unit},
     },
     entity_manager::{ComponentIteratorGenerator, EntityManager, EntityManagerTrait},
     event_bus::{EventReader, EventSender},
     game_entities::{
         asteroid::AsteroidEntity, bullet::BulletEntity, enemy_ship::EnemyShip, explosion::Explosion,
+        player_ship::PlayerShip,
     },
     graphics::{instanced_mesh::InstancedMesh, vertices::generator},
+    random::RandomStream,
     resource_manager::{
         self, scene_resource_manager::SceneResourceManager, ResourceLoader, ResourceManager,
     },
 };
 
-use super::player_controller::GameEvent;
+use super::{
+    asteroid_detonator::{debris, fire_ball, fire_ring},
+    game_mode_system::count_blast_hit,
+};
 
 pub enum BulletEvent {
     Exploded(usize, Vec3),
-    Damaged(usize, f32),
+    Detonated(usize, Vec3),
+    Impact(Vec3, String),
+    Beam(Vec3, Vec3),
+    Damaged(usize, Damage),
     Extinguished(usize),
 }
 
 pub enum UnitEvent {
-    Destroyed(usize),
+    // Destroyed unit and the entity credited with the kill
+    Destroyed(usize, Option<usize>),
 }
 
 impl<'a> ComponentIteratorGenerator<'a, (usize, &'a Unit)> for EntityManager {
@@ -41,11 +49,15 @@ impl<'a> ComponentIteratorGenerator<'a, (usize, &'a Unit)> for EntityManager {
             .iter::<EnemyShip>()
             .map(|ship| (ship.id, &ship.entity.info));
 
+        let players = self
+            .iter::<PlayerShip>()
+            .map(|player| (player.id, &player.entity.info));
+
         let asteroids = self
             .iter::<AsteroidEntity>()
             .map(|asteroid| (asteroid.id, &asteroid.entity.info));
 
-        Box::new(enemies.chain(asteroids))
+        Box::new(enemies.chain(players).chain(asteroids))
     }
 }
 
@@ -54,40 +66,59 @@ pub fn process_bullet_events(
     event_writer: &EventSender,
     resource_manager: &mut SceneResourceManager,
     entity_manager: &mut EntityManager,
+    rng: &RandomStream,
     delta: f32,
 ) {
     let mut deaths = vec![];
     event_bus.read::<BulletEvent>(|event| match event {
         BulletEvent::Exploded(bullet, position) => {
+            let preset = entity_manager
+                .get::<BulletEntity>(bullet)
+                .map_or(String::from("plasma"), |bullet| {
+                    bullet.entity.explosion.clone()
+                });
             entity_manager.remove::<BulletEntity>(bullet);
-            let (vertices, indices) = generator::quad(1.0, 1.0);
-            let instanced_mesh = InstancedMesh::new(&vertices, &indices, &vec![]);
-            let material = resource_manager.get("explosion").res;
-            let explosion = ParticleEmitter::new(
-                ParticleEmitterDefinition {
-                    count: 256,
-                    rate: 0.00005,
-                },
-                material,
-                instanced_mesh,
-                Box::new(create_spawner(position)),
-            );
-
-            entity_manager.add(Explosion {
-                lifetime: 0.5,
-                explosion,
-            });
+            spawn_explosion(entity_manager, resource_manager, &preset, position, rng);
         }
-        BulletEvent::Damaged(entity, damage) => {
-            if let Some((id, unit)) = entity_manager
+        BulletEvent::Detonated(missile, position) => {
+            let Some(bullet) = entity_manager.get::<BulletEntity>(missile) else {return};
+            let Some(warhead) = bullet.entity.missile.as_ref() else {return};
+            let preset = bullet.entity.explosion.clone();
+            let source = Some(bullet.entity.source);
+            let damage = Damage::new(warhead.damage, warhead.damage_type, position, source);
+            let blast_radius = warhead.guidance.blast_radius;
+            entity_manager.remove::<BulletEntity>(missile);
+            spawn_explosion(entity_manager, resource_manager, &preset, position, rng);
+
+            let victims: Vec<_> = entity_manager
                 .get_view()
-                .find(|(id, _): &(usize, &Unit)| *id == entity)
-            {
-                to_mut(unit).health -= damage;
-                if unit.health < 0.0 {
-                    deaths.push(id);
-                }
-            }
+                .filter(
+                    |(transform, _, _, collider, _): &(
+                        &Transform,
+                        usize,
+                        &Unit,
+                        &Collider,
+                        &PhysicalBody,
+                    )| {
+                        (transform.position - position).length() < blast_radius + collider.radius
+                    },
+                )
+                .map(|(_, id, _, _, _)| id)
+                .collect();
+            count_blast_hit(entity_manager, source, &victims);
+            victims
+                .into_iter()
+                .for_each(|victim| apply_damage(entity_manager, victim, &damage, &mut deaths));
+        }
+        BulletEvent::Impact(position, preset) => {
+            spawn_explosion(entity_manager, resource_manager, &preset, position, rng);
+        }
+        BulletEvent::Beam(start, end) => {
+            let spawner = Box::new(beam(start, end, rng.clone()));
+            add_explosion(entity_manager, resource_manager, 128, 0.3, spawner);
+        }
+        BulletEvent::Damaged(entity, damage) => {
+            apply_damage(entity_manager, entity, &damage, &mut deaths)
         }
         BulletEvent::Extinguished(explosion) => {
             entity_manager.remove::<Explosion>(explosion);
@@ -97,13 +128,9 @@ pub fn process_bullet_events(
         .iter_mut::<Explosion>()
         .for_each(|explosion| explosion.entity.lifetime -= delta);
 
-    let mut death_messages = HashSet::<usize>::new();
-    deaths.iter().for_each(|death| {
-        death_messages.insert(*death);
-    });
-    death_messages
-        .iter()
-        .for_each(|death| event_writer.write(UnitEvent::Destroyed(*death)));
+    deaths
+        .into_iter()
+        .for_each(|(death, killer)| event_writer.write(UnitEvent::Destroyed(death, killer)));
 
     let extinguished: Vec<_> = entity_manager
         .iter::<Explosion>()
@@ -120,8 +147,108 @@ pub fn process_bullet_events(
         entity_manager.remove::<Explosion>(id);
     })
 }
-fn create_spawner(pos: Vec3) -> impl Fn(&mut Particle) {
-    let rng = RefCell::new(rand::thread_rng());
+
+// A unit only reports its death once, later hits on the wreck are ignored
+fn apply_damage(
+    entity_manager: &mut EntityManager,
+    entity: usize,
+    damage: &Damage,
+    deaths: &mut Vec<(usize, Option<usize>)>,
+) {
+    for_each_unit(entity_manager, |id, transform, unit| {
+        if id == entity && unit.damage(damage, transform) {
+            deaths.push((id, unit.last_attacker));
+        }
+    });
+}
+
+pub fn update_units(entity_manager: &mut EntityManager, delta: f32) {
+    for_each_unit(entity_manager, |_, _, unit| unit.update(delta));
+}
+
+// Same entities as the unit views, one kind at a time since each needs the manager mutably
+pub fn for_each_unit(
+    entity_manager: &mut EntityManager,
+    mut visit: impl FnMut(usize, &Transform, &mut Unit),
+) {
+    entity_manager
+        .iter_mut::<EnemyShip>()
+        .for_each(|ship| visit(ship.id, &ship.transform, &mut ship.entity.info));
+    entity_manager
+        .iter_mut::<PlayerShip>()
+        .for_each(|player| visit(player.id, &player.transform, &mut player.entity.info));
+    entity_manager
+        .iter_mut::<AsteroidEntity>()
+        .for_each(|asteroid| visit(asteroid.id, &asteroid.transform, &mut asteroid.entity.info));
+}
+
+pub fn spawn_explosion(
+    entity_manager: &mut EntityManager,
+    resource_manager: &mut SceneResourceManager,
+    preset: &str,
+    position: Vec3,
+    rng: &RandomStream,
+) {
+    let rng = rng.clone();
+    let (count, lifetime, spawner): (_, _, Box<dyn Fn(&mut Particle)>) = match preset {
+        "fireball" => (512, 2.0, Box::new(fire_ball(position, rng))),
+        "ring" => (256, 1.0, Box::new(fire_ring(position, rng))),
+        "debris" => (128, 2.0, Box::new(debris(position, rng))),
+        _ => (256, 0.5, Box::new(create_spawner(position, rng))),
+    };
+    add_explosion(entity_manager, resource_manager, count, lifetime, spawner);
+}
+
+fn add_explosion(
+    entity_manager: &mut EntityManager,
+    resource_manager: &mut SceneResourceManager,
+    count: usize,
+    lifetime: f32,
+    spawner: Box<dyn Fn(&mut Particle)>,
+) {
+    let (vertices, indices) = generator::quad(1.0, 1.0);
+    let instanced_mesh = InstancedMesh::new(&vertices, &indices, &vec![]);
+    let material = resource_manager.get("explosion").res;
+    let explosion = ParticleEmitter::new(
+        ParticleEmitterDefinition {
+            count,
+            rate: 0.00005,
+        },
+        material,
+        instanced_mesh,
+        spawner,
+    );
+
+    entity_manager.add(Explosion {
+        lifetime,
+        explosion,
+    });
+}
+
+fn beam(start: Vec3, end: Vec3, rng: RandomStream) -> impl Fn(&mut Particle) {
+    let rng = RefCell::new(rng);
+    move |particle| {
+        let mut rng = rng.try_borrow_mut().unwrap();
+        particle.position = start.lerp(end, rng.gen_range(0.0..1.0)).to_array();
+        particle.velocity = [
+            rng.gen_range(-1.0..1.0),
+            rng.gen_range(-1.0..1.0),
+            rng.gen_range(-1.0..1.0),
+        ];
+        particle.color = [0.4, 0.7, 1.0, 0.75];
+
+        particle.tex = rng.gen_range(0..4) as f32 / 4.0;
+
+        particle.size = rng.gen_range(0.2..0.5);
+        particle.lifetime = 0.3;
+        particle.dampening = 0.1;
+
+        particle.opacity_delta = 3.0;
+    }
+}
+
+fn create_spawner(pos: Vec3, rng: RandomStream) -> impl Fn(&mut Particle) {
+    let rng = RefCell::new(rng);
     move |particle| {
         let mut rng = rng.try_borrow_mut().unwrap();
         particle.position = pos.to_array();
@@ -146,10 +273,3 @@ fn create_spawner(pos: Vec3) -> impl Fn(&mut Particle) {
         particle.opacity_delta = 2.5;
     }
 }
-
-fn to_mut<T>(val: &T) -> &mut T {
-    unsafe {
-        let ptr: *const T = val;
-        mem::transmute(ptr)
-    }
-}
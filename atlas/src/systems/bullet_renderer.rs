@@ -26,6 +26,7 @@ impl<'a> ComponentIteratorGenerator<'a, (&'a Transform, &'a BulletEntity)> for E
 
 pub struct BulletInstance {
     pub transform: [f32; 16],
+    pub colour: [f32; 4],
 }
 
 impl BufferElement for BulletInstance {
@@ -35,6 +36,7 @@ impl BufferElement for BulletInstance {
             Attribute::Float(4),
             Attribute::Float(4),
             Attribute::Float(4),
+            Attribute::Float(4),
         ]
     }
 }
@@ -82,9 +84,10 @@ impl BulletRenderer {
         self.bullet_instances.clear();
         entity_manager
             .get_view()
-            .for_each(|(transform, _): (&Transform, &BulletEntity)| {
+            .for_each(|(transform, bullet): (&Transform, &BulletEntity)| {
                 self.bullet_instances.push(BulletInstance {
                     transform: transform.model().to_cols_array(),
+                    colour: bullet.colour.extend(1.0).to_array(),
                 })
             });
     }
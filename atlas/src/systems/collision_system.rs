@@ -30,14 +30,17 @@ impl<'a> ComponentIteratorGenerator<'a, (usize, &'a Transform, &'a Collider, &'a
                 &enemy.entity.physical_body,
             )
         });
-        let players = self.iter::<PlayerShip>().map(|player| {
-            (
-                player.id,
-                &player.transform,
-                &player.entity.collider,
-                &player.entity.physical_body,
-            )
-        });
+        let players = self
+            .iter::<PlayerShip>()
+            .filter(|player| player.entity.lives.is_alive())
+            .map(|player| {
+                (
+                    player.id,
+                    &player.transform,
+                    &player.entity.collider,
+                    &player.entity.physical_body,
+                )
+            });
 
         let asteroids = self.iter::<AsteroidEntity>().map(|asteroid| {
             (
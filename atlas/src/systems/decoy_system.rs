@@ -0,0 +1,115 @@
+use glam::Vec3;
+use rand::Rng;
+
+use crate::{
+    components::{
+        countermeasures::Countermeasures,
+        particle_emitter::{ParticleEmitter, ParticleEmitterDefinition},
+        physical_body::PhysicalBody,
+        transform::Transform,
+    },
+    entity_manager::EntityManager,
+    game_entities::{decoy::Decoy, enemy_ship::EnemyShip, player_ship::PlayerShip},
+    graphics::{instanced_mesh::InstancedMesh, vertices::generator},
+    random::RandomStream,
+    resource_manager::{scene_resource_manager::SceneResourceManager, ResourceManager},
+};
+
+use super::particle_system::flare_spawner;
+
+const DECOY_LIFETIME: f32 = 3.0;
+const EJECTION_SPEED: f32 = 15.0;
+
+pub fn update_decoys(
+    entity_manager: &mut EntityManager,
+    resource_manager: &mut SceneResourceManager,
+    rng: &RandomStream,
+    delta: f32,
+) {
+    let mut launches = vec![];
+    let mut release =
+        |countermeasures: &mut Countermeasures, transform: &Transform, body: &PhysicalBody| {
+            let salvo = countermeasures.release(delta);
+            (0..salvo).for_each(|_| {
+                launches.push((
+                    transform.position,
+                    body.velocity(),
+                    countermeasures.signature,
+                ))
+            });
+        };
+    entity_manager
+        .iter_mut::<PlayerShip>()
+        .filter(|player| player.entity.lives.is_alive())
+        .for_each(|player| {
+            let ship = &mut player.entity;
+            release(
+                &mut ship.countermeasures,
+                &player.transform,
+                &ship.physical_body,
+            );
+        });
+    entity_manager.iter_mut::<EnemyShip>().for_each(|enemy| {
+        let ship = &mut enemy.entity;
+        release(
+            &mut ship.countermeasures,
+            &enemy.transform,
+            &ship.physical_body,
+        );
+    });
+
+    let mut rng = rng.clone();
+    launches
+        .into_iter()
+        .for_each(|(position, velocity, signature)| {
+            let ejection = Vec3::new(
+                rng.gen_range(-1.0..1.0),
+                rng.gen_range(-1.0..1.0),
+                rng.gen_range(-1.0..1.0),
+            )
+            .normalize_or_zero()
+                * EJECTION_SPEED;
+
+            let mut body = PhysicalBody::new(1.0, 1.0, 0.99);
+            body.momentum = velocity + ejection;
+
+            entity_manager.add_at(
+                Decoy {
+                    body,
+                    flare: create_flare(resource_manager, rng.clone()),
+                    lifetime: DECOY_LIFETIME,
+                    signature,
+                },
+                Transform::pos(position),
+            );
+        });
+
+    entity_manager
+        .iter_mut::<Decoy>()
+        .for_each(|decoy| decoy.entity.lifetime -= delta);
+
+    let burnt_out: Vec<_> = entity_manager
+        .iter::<Decoy>()
+        .filter(|decoy| decoy.entity.lifetime <= 0.0)
+        .map(|decoy| decoy.id)
+        .collect();
+
+    burnt_out.iter().for_each(|&id| {
+        entity_manager.remove::<Decoy>(id);
+    });
+}
+
+fn create_flare(resource_manager: &mut SceneResourceManager, rng: RandomStream) -> ParticleEmitter {
+    let (vertices, indices) = generator::quad(1.0, 1.0);
+    let instanced_mesh = InstancedMesh::new(&vertices, &indices, &vec![]);
+
+    ParticleEmitter::new(
+        ParticleEmitterDefinition {
+            count: 40,
+            rate: 0.01,
+        },
+        resource_manager.get("explosion").res,
+        instanced_mesh,
+        Box::new(flare_spawner(rng)),
+    )
+}
@@ -0,0 +1,93 @@
+use crate::{
+    components::unit::Unit,
+    entity_manager::{ComponentIteratorGenerator, EntityManager},
+    event_bus::{EventReader, EventSender},
+    game_entities::{enemy_ship::EnemyShip, player_ship::PlayerShip, session::Session},
+    game_mode::waves::Wave,
+};
+
+use super::bullet_detonator::{BulletEvent, UnitEvent};
+
+pub enum WaveEvent {
+    Spawn(usize, Wave),
+}
+
+// Has to run before the bullet events are processed, direct hits are counted from the pending damage
+pub fn track_accuracy(entity_manager: &mut EntityManager, event_reader: &EventReader) {
+    let Some(player) = entity_manager.iter::<PlayerShip>().next() else {return};
+    let (player, fired) = (player.id, player.entity.arsenal.fired);
+
+    let mut hits = 0;
+    event_reader.peek(|event: &BulletEvent| {
+        if let BulletEvent::Damaged(_, damage) = event {
+            if damage.source == Some(player) {
+                hits += 1;
+            }
+        }
+    });
+
+    entity_manager.iter_mut::<Session>().for_each(|session| {
+        let score = &mut session.entity.score;
+        score.shots = fired;
+        score.hits += hits;
+    });
+}
+
+// Blast damage is applied without a damage event, so a missile of the player's that caught
+// anyone besides the player counts as one hit here
+pub fn count_blast_hit(
+    entity_manager: &mut EntityManager,
+    source: Option<usize>,
+    victims: &[usize],
+) {
+    let player = entity_manager
+        .iter::<PlayerShip>()
+        .next()
+        .map(|player| player.id);
+    let others_hit = victims.iter().any(|victim| Some(*victim) != player);
+    if player.is_none() || source != player || !others_hit {
+        return;
+    }
+    entity_manager
+        .iter_mut::<Session>()
+        .for_each(|session| session.entity.score.hits += 1);
+}
+
+// Has to run after the bullet events and before the destroyed units are cleaned up
+pub fn update_game_mode(
+    entity_manager: &mut EntityManager,
+    event_reader: &EventReader,
+    event_sender: &EventSender,
+    delta: f32,
+) {
+    let player = entity_manager
+        .iter::<PlayerShip>()
+        .next()
+        .map(|player| player.id);
+
+    let mut kills = vec![];
+    event_reader.peek(|event: &UnitEvent| match event {
+        UnitEvent::Destroyed(id, killer) if player.is_some() && *killer == player => {
+            let unit_type = entity_manager
+                .get_view()
+                .find(|(unit, _): &(usize, &Unit)| unit == id)
+                .map(|(_, unit)| unit.unit_type.clone());
+            kills.extend(unit_type);
+        }
+        _ => {}
+    });
+
+    let enemies_left = entity_manager.iter::<EnemyShip>().next().is_some();
+    entity_manager.iter_mut::<Session>().for_each(|session| {
+        let session = &mut session.entity;
+        session.score.update(delta);
+        kills.iter().for_each(|unit_type| {
+            let points = session.waves.table.points(unit_type);
+            session.score.kill(points);
+        });
+
+        if let Some(wave) = session.waves.update(delta, enemies_left) {
+            event_sender.write(WaveEvent::Spawn(session.waves.spawned, wave));
+        }
+    });
+}
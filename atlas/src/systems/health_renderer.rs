@@ -14,6 +14,7 @@ impl<'a> ComponentIteratorGenerator<'a, (&'a Transform, &'a HealthRenderer)> for
     fn get_view(&'a self) -> Box<dyn Iterator<Item = (&'a Transform, &'a HealthRenderer)> + 'a> {
         let huds = self
             .iter::<HudEntity>()
+            .filter(|hud_entity| hud_entity.entity.visible)
             .map(|hud_entity| [(&hud_entity.transform, &hud_entity.entity.health)])
             .flatten();
 
@@ -41,6 +42,7 @@ impl HealthRendererSystem {
                         let mvp = projection * transform.model();
                         context.shader.projection_view_model(&mvp);
                         context.shader.health(bar.health);
+                        context.shader.shield(bar.shield);
                         bar.mesh.render();
                     });
             })
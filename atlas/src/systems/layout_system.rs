@@ -0,0 +1,80 @@
+use glam::{Vec2, Vec3};
+
+use crate::{
+    components::{button_trigger::Rect, transform::Transform},
+    entity_manager::EntityManager,
+    game_entities::{
+        hud::HudEntity, sprite::Sprite, ui_label::UiLabel, ui_layout::UiLayout, widget::Widget,
+    },
+};
+
+// Recomputes the layout for a new window size or UI scale and moves everything bound to it
+pub fn update_layout(entity_manager: &mut EntityManager, dimensions: (i32, i32), scale: f32) {
+    let Some(ui) = entity_manager.iter_mut::<UiLayout>().next() else {return};
+    ui.entity.resize(dimensions, scale);
+    apply_layout(entity_manager);
+}
+
+pub fn apply_layout(entity_manager: &mut EntityManager) {
+    let Some(ui) = entity_manager.iter::<UiLayout>().next() else {return};
+    let scale = ui.entity.scale();
+    let rects: Vec<Rect> = (0..ui.entity.node_count()).map(|node| ui.entity.rect(node)).collect();
+    let rect = |node: Option<usize>| node.and_then(|node| rects.get(node).copied());
+
+    entity_manager.iter_mut::<Widget>().for_each(|widget| {
+        let Some(rect) = rect(widget.entity.node) else {return};
+        widget.entity.place(rect, scale);
+    });
+
+    entity_manager.iter_mut::<UiLabel>().for_each(|label| {
+        let Some(rect) = rect(label.entity.node) else {return};
+        label.transform = scaled(baseline(rect), scale);
+    });
+
+    entity_manager.iter_mut::<Sprite>().for_each(|sprite| {
+        let Some(rect) = rect(sprite.entity.node) else {return};
+        sprite.transform = fill(rect);
+    });
+
+    // HUD text hangs off the crosshair, its offsets are in the HUD's unscaled units
+    entity_manager.iter_mut::<HudEntity>().for_each(|hud| {
+        let nodes = hud.entity.nodes;
+        let Some(crosshair) = rect(Some(nodes.crosshair)) else {return};
+        let center = crosshair.pos() + crosshair.size() * 0.5;
+        hud.transform = scaled(center, scale);
+
+        [
+            (nodes.status, &mut hud.entity.status),
+            (nodes.systems, &mut hud.entity.systems),
+            (nodes.score, &mut hud.entity.score),
+            (nodes.mission, &mut hud.entity.mission),
+        ]
+        .into_iter()
+        .for_each(|(node, text)| {
+            let Some(rect) = rect(Some(node)) else {return};
+            let offset = (baseline(rect) - center) / scale;
+            text.set_transform(Transform::pos(Vec3::new(offset.x, offset.y, 0.0)));
+        });
+    });
+}
+
+// Text sits on its baseline, so a label is drawn along the bottom edge of its rect
+fn baseline(rect: Rect) -> Vec2 {
+    rect.pos() + Vec2::new(0.0, rect.size().y)
+}
+
+fn scaled(pos: Vec2, scale: f32) -> Transform {
+    Transform {
+        scale: Vec3::new(scale, scale, 1.0),
+        ..Transform::pos(Vec3::new(pos.x, pos.y, 0.0))
+    }
+}
+
+// Stretches a unit quad over the rect, flipped on both axes so the texture ends up upright
+fn fill(rect: Rect) -> Transform {
+    let center = rect.pos() + rect.size() * 0.5;
+    Transform {
+        scale: Vec3::new(-rect.size().x, -rect.size().y, 1.0),
+        ..Transform::pos(Vec3::new(center.x, center.y, 0.0))
+    }
+}
@@ -0,0 +1,165 @@
+use glam::Vec3;
+use rand::Rng;
+
+use crate::{
+    components::{
+        collider::Collider,
+        particle_emitter::{ParticleEmitter, ParticleEmitterDefinition},
+        physical_body::PhysicalBody,
+        transform::Transform,
+        unit::Unit,
+    },
+    entity_manager::{ComponentIteratorGenerator, EntityManager},
+    event_bus::EventReader,
+    game_entities::{pickup::Pickup, player_ship::PlayerShip, session::Session},
+    game_mode::loot::{Loot, PickupKind},
+    graphics::{instanced_mesh::InstancedMesh, vertices::generator},
+    random::RandomStream,
+    resource_manager::{scene_resource_manager::SceneResourceManager, ResourceManager},
+};
+
+use super::{bullet_detonator::UnitEvent, particle_system::glow_spawner};
+
+const PICKUP_RADIUS: f32 = 1.5;
+const SCATTER_SPEED: f32 = 6.0;
+const MAGNET_ACCELERATION: f32 = 120.0;
+// Fraction of the drift speed a pickup keeps after a second
+const DRIFT_DAMPING: f32 = 0.5;
+
+// Has to run after the bullet events and before the destroyed units are cleaned up
+pub fn drop_loot(
+    entity_manager: &mut EntityManager,
+    resource_manager: &mut SceneResourceManager,
+    event_reader: &EventReader,
+    rng: &RandomStream,
+) {
+    let Some(session) = entity_manager.iter::<Session>().next() else {return};
+    let drops = &session.entity.drops;
+    let mut rng = rng.clone();
+
+    let mut loot = vec![];
+    event_reader.peek(|event: &UnitEvent| match event {
+        UnitEvent::Destroyed(id, _) => {
+            let wreck = entity_manager.get_view().find(
+                |(_, unit, _, _, _): &(&Transform, usize, &Unit, &Collider, &PhysicalBody)| {
+                    unit == id
+                },
+            );
+            let Some((transform, _, unit, _, body)) = wreck else {return};
+            drops.roll(&unit.unit_type, &mut rng).into_iter().for_each(|drop| {
+                loot.push((drop, transform.position, body.velocity()))
+            });
+        }
+    });
+    let (magnet, lifetime) = (drops.magnet, drops.lifetime);
+
+    loot.into_iter().for_each(|(drop, position, velocity)| {
+        let scatter = Vec3::new(
+            rng.gen_range(-1.0..1.0),
+            rng.gen_range(-1.0..1.0),
+            rng.gen_range(-1.0..1.0),
+        )
+        .normalize_or_zero()
+            * SCATTER_SPEED;
+
+        let mut body = PhysicalBody::new(1.0, 1.0, 1.0);
+        body.momentum = velocity + scatter;
+
+        entity_manager.add_at(
+            Pickup {
+                loot: drop,
+                body,
+                collider: Collider {
+                    toi: 0.0,
+                    last_impact: Vec3::ZERO,
+                    radius: PICKUP_RADIUS,
+                    callback: None,
+                },
+                glow: create_glow(resource_manager, rng.clone(), drop.kind.colour()),
+                magnet,
+                lifetime,
+            },
+            Transform::pos(position),
+        );
+    });
+}
+
+// Pickups drift towards a living player inside their magnet radius and are collected on contact
+pub fn update_pickups(entity_manager: &mut EntityManager, delta: f32) {
+    let player = entity_manager
+        .iter::<PlayerShip>()
+        .find(|player| player.entity.lives.is_alive())
+        .map(|player| (player.transform.position, player.entity.collider.radius));
+
+    let mut collected = vec![];
+    let mut expired = vec![];
+    entity_manager.iter_mut::<Pickup>().for_each(|pickup| {
+        let (id, position) = (pickup.id, pickup.transform.position);
+        let pickup = &mut pickup.entity;
+
+        pickup.lifetime -= delta;
+        pickup.body.momentum *= DRIFT_DAMPING.powf(delta);
+        if pickup.lifetime <= 0.0 {
+            expired.push(id);
+            return;
+        }
+
+        let Some((player, radius)) = player else {return};
+        let offset = player - position;
+        let distance = offset.length();
+        if distance < pickup.collider.radius + radius {
+            collected.push((id, pickup.loot));
+        } else if distance < pickup.magnet {
+            pickup.body.momentum +=
+                offset.normalize_or_zero() * MAGNET_ACCELERATION * pickup.body.mass * delta;
+        }
+    });
+
+    expired
+        .into_iter()
+        .chain(collected.iter().map(|(id, _)| *id))
+        .for_each(|id| entity_manager.remove::<Pickup>(id));
+
+    collected
+        .into_iter()
+        .for_each(|(_, loot)| collect(entity_manager, loot));
+}
+
+fn collect(entity_manager: &mut EntityManager, loot: Loot) {
+    if loot.kind == PickupKind::Ore {
+        entity_manager
+            .iter_mut::<Session>()
+            .for_each(|session| session.entity.ore += loot.amount as u32);
+        return;
+    }
+
+    let Some(player) = entity_manager.iter_mut::<PlayerShip>().next() else {return};
+    let ship = &mut player.entity;
+    match loot.kind {
+        PickupKind::Repair => {
+            ship.info.health = (ship.info.health + loot.amount).min(ship.info.max_health)
+        }
+        PickupKind::Ammo => ship.arsenal.resupply(loot.amount as u32),
+        PickupKind::Overcharge => ship.arsenal.overcharge(loot.amount),
+        PickupKind::Ore => {}
+    }
+}
+
+fn create_glow(
+    resource_manager: &mut SceneResourceManager,
+    rng: RandomStream,
+    colour: Vec3,
+) -> ParticleEmitter {
+    let (vertices, indices) = generator::quad(1.0, 1.0);
+    let instanced_mesh = InstancedMesh::new(&vertices, &indices, &vec![]);
+
+    ParticleEmitter::new(
+        ParticleEmitterDefinition {
+            count: 24,
+            rate: 0.03,
+        },
+        resource_manager.get("explosion").res,
+        instanced_mesh,
+        Box::new(glow_spawner(rng, colour)),
+    )
+}
@@ -0,0 +1,148 @@
+use glam::{Quat, Vec3};
+
+use crate::{
+    components::{physical_body::PhysicalBody, transform::Transform},
+    entity_manager::{ComponentIteratorGenerator, EntityManager},
+    event_bus::EventSender,
+    game_entities::{
+        bullet::{BulletEntity, Missile},
+        decoy::Decoy,
+    },
+};
+
+use super::bullet_detonator::BulletEvent;
+
+const SEEKER_CONE: f32 = 0.8;
+const TARGET_SIGNATURE: f32 = 1.0;
+
+struct Guide {
+    target: Option<usize>,
+    acceleration: Vec3,
+    detonate: bool,
+}
+
+pub fn guide_missiles(entity_manager: &mut EntityManager, event_sender: &EventSender, delta: f32) {
+    let decoys: Vec<_> = entity_manager
+        .iter::<Decoy>()
+        .map(|decoy| (decoy.id, decoy.transform.position, decoy.entity.signature))
+        .collect();
+
+    let guides: Vec<_> = entity_manager
+        .iter::<BulletEntity>()
+        .map(|bullet| {
+            let missile = bullet.entity.missile.as_ref()?;
+            let position = bullet.transform.position;
+            let velocity = bullet.entity.body.velocity();
+
+            let locked = missile.target.and_then(|target| {
+                track(entity_manager, target).map(|(position, _)| (target, position))
+            });
+            let target = seek(position, velocity, locked, &decoys);
+            let tracked = target.and_then(|target| track(entity_manager, target));
+
+            let detonate = tracked.map_or(false, |(target, _)| {
+                (target - position).length() < missile.guidance.proximity
+            });
+            let acceleration = match (tracked, missile.fuel > 0.0) {
+                (Some((target, target_velocity)), true) => {
+                    navigate(missile, position, velocity, target, target_velocity)
+                }
+                (None, true) => velocity.normalize_or_zero() * missile.guidance.thrust,
+                (_, false) => Vec3::ZERO,
+            };
+
+            Some(Guide {
+                target,
+                acceleration,
+                detonate,
+            })
+        })
+        .collect();
+
+    entity_manager
+        .iter_mut::<BulletEntity>()
+        .zip(guides)
+        .for_each(|(bullet, guide)| {
+            let (Some(missile), Some(guide)) = (bullet.entity.missile.as_mut(), guide) else {return};
+
+            missile.target = guide.target;
+            missile.fuel -= delta;
+            if missile.fuel <= 0.0 {
+                missile.exhaust.definition.count = 0;
+            }
+
+            let body = &mut bullet.entity.body;
+            body.momentum += guide.acceleration * body.mass * delta;
+            let heading = body.velocity().normalize_or_zero();
+            if heading != Vec3::ZERO {
+                bullet.transform.rotation = Quat::from_rotation_arc(Vec3::NEG_Z, heading);
+            }
+
+            if guide.detonate {
+                event_sender.write(BulletEvent::Detonated(bullet.id, bullet.transform.position));
+            }
+        });
+}
+
+// Decoys inside the seeker cone that outshine the current target steal the lock for good
+fn seek(
+    position: Vec3,
+    velocity: Vec3,
+    locked: Option<(usize, Vec3)>,
+    decoys: &[(usize, Vec3, f32)],
+) -> Option<usize> {
+    let strength = |target: Vec3, signature: f32| {
+        let offset = target - position;
+        match velocity.angle_between(offset) < SEEKER_CONE {
+            true => signature / offset.length_squared().max(1.0),
+            false => 0.0,
+        }
+    };
+
+    let locked_strength = locked.map_or(0.0, |(target, target_position)| {
+        let signature = decoys
+            .iter()
+            .find(|(id, _, _)| *id == target)
+            .map_or(TARGET_SIGNATURE, |(_, _, signature)| *signature);
+        strength(target_position, signature)
+    });
+
+    decoys
+        .iter()
+        .map(|(id, position, signature)| (*id, strength(*position, *signature)))
+        .filter(|(_, decoy_strength)| *decoy_strength > locked_strength)
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(id, _)| id)
+        .or(locked.map(|(target, _)| target))
+}
+
+fn track(entity_manager: &EntityManager, target: usize) -> Option<(Vec3, Vec3)> {
+    entity_manager
+        .get_view()
+        .find(|(id, _, _): &(usize, &Transform, &PhysicalBody)| *id == target)
+        .map(|(_, transform, body)| (transform.position, body.velocity()))
+}
+
+// Proportional navigation: lateral acceleration follows the rotation rate of the line of sight,
+// capped by the turn rate, while the motor keeps pushing along the current heading
+fn navigate(
+    missile: &Missile,
+    position: Vec3,
+    velocity: Vec3,
+    target: Vec3,
+    target_velocity: Vec3,
+) -> Vec3 {
+    let guidance = &missile.guidance;
+    let line_of_sight = target - position;
+    let closing = target_velocity - velocity;
+    let rotation = line_of_sight.cross(closing) / line_of_sight.length_squared().max(1.0);
+
+    let lateral = (guidance.navigation * rotation.cross(velocity))
+        .clamp_length_max(guidance.turn_rate * velocity.length());
+    let heading = match velocity.normalize_or_zero() {
+        Vec3::ZERO => line_of_sight.normalize_or_zero(),
+        heading => heading,
+    };
+
+    lateral + heading * guidance.thrust
+}
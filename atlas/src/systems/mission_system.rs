@@ -0,0 +1,141 @@
+use glam::Vec3;
+
+use crate::{
+    components::{collider::Collider, physical_body::PhysicalBody, transform::Transform, unit::Unit},
+    entity_manager::{ComponentIteratorGenerator, EntityManager},
+    event_bus::{EventReader, EventSender},
+    game_entities::{mission::MissionEntity, player_ship::PlayerShip},
+    game_mode::mission::{Action, Condition, Objective, ObjectiveState, Outcome},
+    scene::SceneEvent,
+};
+
+use super::bullet_detonator::UnitEvent;
+
+const MESSAGE_DURATION: f32 = 5.0;
+
+// Prefabs live in the game crate, missions and scripts only name them
+pub enum PrefabEvent {
+    Spawn(String, Vec3, u32),
+}
+
+// Has to run after the bullet events and before the destroyed units are cleaned up
+pub fn update_missions(
+    entity_manager: &mut EntityManager,
+    event_reader: &EventReader,
+    event_sender: &EventSender,
+    delta: f32,
+) {
+    let mut destroyed = vec![];
+    event_reader.peek(|event: &UnitEvent| match event {
+        UnitEvent::Destroyed(id, _) => {
+            let unit_type = entity_manager
+                .get_view()
+                .find(|(unit, _): &(usize, &Unit)| unit == id)
+                .map(|(_, unit)| unit.unit_type.clone());
+            destroyed.extend(unit_type);
+        }
+    });
+
+    let player = entity_manager
+        .iter::<PlayerShip>()
+        .find(|player| player.entity.lives.is_alive())
+        .map(|player| player.transform.position);
+    let units: Vec<_> = entity_manager
+        .get_view()
+        .map(
+            |(transform, _, unit, _, _): (&Transform, usize, &Unit, &Collider, &PhysicalBody)| {
+                (unit.name.clone(), transform.position)
+            },
+        )
+        .collect();
+
+    entity_manager.iter_mut::<MissionEntity>().for_each(|mission| {
+        let mission = &mut mission.entity;
+        mission.message = mission
+            .message
+            .take()
+            .map(|(message, timer)| (message, timer - delta))
+            .filter(|(_, timer)| *timer > 0.0);
+
+        let finished: Vec<_> = mission
+            .mission
+            .objectives
+            .iter()
+            .filter(|objective| objective.state == ObjectiveState::Completed)
+            .map(|objective| objective.id.clone())
+            .collect();
+
+        let mut outcomes = vec![];
+        mission.mission.objectives.iter_mut().for_each(|objective| {
+            if objective.state == ObjectiveState::Pending
+                && objective.after.as_ref().map_or(true, |after| finished.contains(after))
+            {
+                objective.state = ObjectiveState::Active;
+            }
+            if objective.state != ObjectiveState::Active {
+                return;
+            }
+
+            objective.elapsed += delta;
+            let outcome = evaluate(objective, &destroyed, player, &units).or(
+                match objective.deadline.map_or(false, |deadline| objective.elapsed > deadline) {
+                    true => Some(Outcome::Fail),
+                    false => None,
+                },
+            );
+            let Some(outcome) = outcome else {return};
+
+            objective.state = match outcome {
+                Outcome::Complete => ObjectiveState::Completed,
+                Outcome::Fail => ObjectiveState::Failed,
+            };
+            outcomes.push((objective.id.clone(), outcome));
+        });
+
+        outcomes.into_iter().for_each(|(objective, outcome)| {
+            mission
+                .mission
+                .actions(&objective, outcome)
+                .into_iter()
+                .for_each(|action| match action {
+                    Action::Message(message) => mission.message = Some((message, MESSAGE_DURATION)),
+                    Action::Spawn {
+                        prefab,
+                        position,
+                        count,
+                    } => event_sender.write(PrefabEvent::Spawn(prefab, position, count)),
+                    Action::Scene(scene) => event_sender.write(SceneEvent::NewScene(scene)),
+                });
+        });
+    });
+}
+
+fn evaluate(
+    objective: &mut Objective,
+    destroyed: &[String],
+    player: Option<Vec3>,
+    units: &[(String, Vec3)],
+) -> Option<Outcome> {
+    match &objective.condition {
+        Condition::Destroy { unit_type, count } => {
+            objective.progress += destroyed.iter().filter(|destroyed| *destroyed == unit_type).count() as u32;
+            (objective.progress >= *count).then_some(Outcome::Complete)
+        }
+        Condition::Reach { position, radius } => player
+            .filter(|player| player.distance(*position) < *radius)
+            .map(|_| Outcome::Complete),
+        // The escorted unit may show up later, it only counts as lost once it has been seen
+        Condition::Escort {
+            unit,
+            position,
+            radius,
+        } => match units.iter().find(|(name, _)| name == unit) {
+            Some((_, escorted)) => {
+                objective.progress = 1;
+                (escorted.distance(*position) < *radius).then_some(Outcome::Complete)
+            }
+            None => (objective.progress > 0).then_some(Outcome::Fail),
+        },
+        Condition::Survive(duration) => (objective.elapsed >= *duration).then_some(Outcome::Complete),
+    }
+}
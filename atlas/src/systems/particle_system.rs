@@ -1,4 +1,4 @@
-use std::{f32::consts::PI, mem};
+use std::{cell::RefCell, f32::consts::PI, mem};
 
 use glam::{Vec3, Vec4};
 use rand::Rng;
@@ -9,6 +9,7 @@ use crate::{
         transform::Transform,
     },
     entity_manager::{ComponentIteratorGenerator, EntityManager},
+    random::RandomStream,
 };
 
 pub fn update_particles(entity_manager: &mut EntityManager, delta: f32) {
@@ -37,21 +38,63 @@ pub fn update_particles(entity_manager: &mut EntityManager, delta: f32) {
         });
 }
 
-pub fn thruster_spawner(particle: &mut Particle) {
-    let mut rng = rand::thread_rng();
+pub fn thruster_spawner(rng: RandomStream) -> impl Fn(&mut Particle) {
+    let rng = RefCell::new(rng);
+    move |particle| {
+        let mut rng = rng.borrow_mut();
 
-    let radius = rng.gen_range(0.05..0.1);
-    let angle = rng.gen_range(0.0..(2.0 * PI));
+        let radius = rng.gen_range(0.05..0.1);
+        let angle = rng.gen_range(0.0..(2.0 * PI));
 
-    let position = [radius * angle.cos(), radius * angle.sin(), 0.8];
-    let velocity = [0.0, 0.0, rng.gen_range(4.0..5.0)];
+        let position = [radius * angle.cos(), radius * angle.sin(), 0.8];
+        let velocity = [0.0, 0.0, rng.gen_range(4.0..5.0)];
 
-    particle.lifetime = 0.5 * (0.05 / (radius) + 0.01).powi(2);
-    particle.position = position;
-    particle.velocity = velocity;
-    particle.color = [1.0, 1. - 10.0 * radius, 0.0, 1.0];
-    particle.opacity_delta = 2.0;
-    particle.size = 0.6 - radius * 3.;
+        particle.lifetime = 0.5 * (0.05 / (radius) + 0.01).powi(2);
+        particle.position = position;
+        particle.velocity = velocity;
+        particle.color = [1.0, 1. - 10.0 * radius, 0.0, 1.0];
+        particle.opacity_delta = 2.0;
+        particle.size = 0.6 - radius * 3.;
+    }
+}
+
+pub fn flare_spawner(rng: RandomStream) -> impl Fn(&mut Particle) {
+    let rng = RefCell::new(rng);
+    move |particle| {
+        let mut rng = rng.borrow_mut();
+
+        particle.position = [0.0, 0.0, 0.0];
+        particle.velocity = [
+            rng.gen_range(-2.0..2.0),
+            rng.gen_range(-2.0..2.0),
+            rng.gen_range(-2.0..2.0),
+        ];
+        particle.lifetime = rng.gen_range(0.2..0.4);
+        particle.color = [1.0, rng.gen_range(0.7..1.0), 0.6, 1.0];
+        particle.opacity_delta = 3.0;
+        particle.dampening = 0.5;
+        particle.size = rng.gen_range(0.3..0.8);
+    }
+}
+
+// Slow shimmer around a pickup, tinted by what it holds
+pub fn glow_spawner(rng: RandomStream, colour: Vec3) -> impl Fn(&mut Particle) {
+    let rng = RefCell::new(rng);
+    move |particle| {
+        let mut rng = rng.borrow_mut();
+
+        particle.position = [0.0, 0.0, 0.0];
+        particle.velocity = [
+            rng.gen_range(-0.5..0.5),
+            rng.gen_range(-0.5..0.5),
+            rng.gen_range(-0.5..0.5),
+        ];
+        particle.lifetime = rng.gen_range(0.4..0.8);
+        particle.color = [colour.x, colour.y, colour.z, 1.0];
+        particle.opacity_delta = 1.5;
+        particle.dampening = 0.8;
+        particle.size = rng.gen_range(0.4..0.9);
+    }
 }
 
 fn spawn_particles(emitter: &mut ParticleEmitter, _transform: &Transform) {
@@ -66,13 +109,14 @@ fn spawn_particles(emitter: &mut ParticleEmitter, _transform: &Transform) {
         lifetime: 0.0,
     };
 
-    while emitter.since_last_spawn > emitter.definition.rate
+    let rate = emitter.definition.rate / emitter.intensity;
+    while emitter.since_last_spawn > rate
         && emitter.particles.len() < emitter.definition.count
     {
         (emitter.spawner)(&mut particle);
         emitter.particles.push(particle.clone());
 
-        emitter.since_last_spawn -= emitter.definition.rate;
+        emitter.since_last_spawn -= rate;
     }
 }
 
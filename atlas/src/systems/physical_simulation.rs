@@ -7,8 +7,8 @@ use crate::{
     },
     entity_manager::{ComponentIteratorGenerator, EntityManager},
     game_entities::{
-        asteroid::AsteroidEntity, bullet::BulletEntity, enemy_ship::EnemyShip,
-        player_ship::PlayerShip,
+        asteroid::AsteroidEntity, bullet::BulletEntity, decoy::Decoy, enemy_ship::EnemyShip,
+        pickup::Pickup, player_ship::PlayerShip,
     },
 };
 
@@ -40,10 +40,50 @@ impl<'a> ComponentIteratorGenerator<'a, (usize, &'a Transform, &'a PhysicalBody)
             .iter::<BulletEntity>()
             .map(|bullet| (bullet.id, &bullet.transform, &bullet.entity.body));
 
-        Box::new(enemies.chain(players).chain(asteroids).chain(bullets))
+        let decoys = self
+            .iter::<Decoy>()
+            .map(|decoy| (decoy.id, &decoy.transform, &decoy.entity.body));
+
+        let pickups = self
+            .iter::<Pickup>()
+            .map(|pickup| (pickup.id, &pickup.transform, &pickup.entity.body));
+
+        Box::new(
+            enemies
+                .chain(players)
+                .chain(asteroids)
+                .chain(bullets)
+                .chain(decoys)
+                .chain(pickups),
+        )
     }
 }
 
+// Same entities as the body view, one kind at a time since each needs the manager mutably
+pub fn for_each_body(
+    entity_manager: &mut EntityManager,
+    mut visit: impl FnMut(usize, &mut Transform, &mut PhysicalBody),
+) {
+    entity_manager
+        .iter_mut::<EnemyShip>()
+        .for_each(|ship| visit(ship.id, &mut ship.transform, &mut ship.entity.physical_body));
+    entity_manager
+        .iter_mut::<PlayerShip>()
+        .for_each(|ship| visit(ship.id, &mut ship.transform, &mut ship.entity.physical_body));
+    entity_manager
+        .iter_mut::<AsteroidEntity>()
+        .for_each(|asteroid| visit(asteroid.id, &mut asteroid.transform, &mut asteroid.entity.body));
+    entity_manager
+        .iter_mut::<BulletEntity>()
+        .for_each(|bullet| visit(bullet.id, &mut bullet.transform, &mut bullet.entity.body));
+    entity_manager
+        .iter_mut::<Decoy>()
+        .for_each(|decoy| visit(decoy.id, &mut decoy.transform, &mut decoy.entity.body));
+    entity_manager
+        .iter_mut::<Pickup>()
+        .for_each(|pickup| visit(pickup.id, &mut pickup.transform, &mut pickup.entity.body));
+}
+
 impl PhysicalSimulation {
     pub fn new(delta: f32) -> Self {
         Self {
@@ -1,16 +1,24 @@
-use glam::{Quat, Vec3, Vec4, Vec4Swizzles};
+use glam::{Quat, Vec2, Vec3};
 
 use crate::{
     components::{
-        camera::Camera, collider::Collider, physical_body::PhysicalBody, transform::Transform,
+        camera::Camera, countermeasures::Countermeasures, physical_body::PhysicalBody,
+        ship_systems::ShipSystems, transform::Transform, weapon::Arsenal,
     },
     entity_manager::{ComponentMutIteratorGenerator, EntityManager},
-    event_bus::{EventReader, EventSender},
+    event_bus::EventReader,
     game_entities::{bullet::BulletEntity, player_ship::PlayerShip},
-    graphics::graphics_context::IoEvent,
+    input::input_map::InputMap,
 };
 
-use super::bullet_detonator::BulletEvent;
+// Fraction of the stick deflection kept every tick
+const STICK_RETURN: f32 = 0.92;
+// How quickly the rotational thrusters close the gap to the requested turn rate
+const ANGULAR_RESPONSE: f32 = 10.0;
+// Chase camera distance change per wheel notch and its limits
+const ZOOM_STEP: f32 = 1.15;
+const MIN_CAMERA_DISTANCE: f32 = 3.0;
+const MAX_CAMERA_DISTANCE: f32 = 40.0;
 
 pub enum GameEvent {
     ShootPlasmaBullet(Transform, BulletEntity),
@@ -18,146 +26,155 @@ pub enum GameEvent {
 }
 
 pub struct PlayerController {
-    buttons: Vec<char>,
-    thruster_force: f32,
-    mouse_speed: f32,
-    bullet_cooldown: u128,
+    input: InputMap,
+    pub mouse_speed: f32,
+    stick: Vec2,
 }
 
 impl<'a>
-    ComponentMutIteratorGenerator<'a, (&'a mut Transform, &'a mut PhysicalBody, &'a mut Camera)>
-    for EntityManager
+    ComponentMutIteratorGenerator<
+        'a,
+        (
+            &'a mut Transform,
+            &'a mut PhysicalBody,
+            &'a mut Camera,
+            &'a mut Arsenal,
+            &'a mut Countermeasures,
+            &'a mut ShipSystems,
+        ),
+    > for EntityManager
 {
     fn get_mut_view(
         &'a mut self,
-    ) -> Box<dyn Iterator<Item = (&'a mut Transform, &'a mut PhysicalBody, &'a mut Camera)> + 'a>
-    {
-        let player = self.iter_mut::<PlayerShip>().map(|player| {
-            (
-                &mut player.transform,
-                &mut player.entity.physical_body,
-                &mut player.entity.camera,
-            )
-        });
+    ) -> Box<
+        dyn Iterator<
+                Item = (
+                    &'a mut Transform,
+                    &'a mut PhysicalBody,
+                    &'a mut Camera,
+                    &'a mut Arsenal,
+                    &'a mut Countermeasures,
+                    &'a mut ShipSystems,
+                ),
+            > + 'a,
+    > {
+        let player = self
+            .iter_mut::<PlayerShip>()
+            .filter(|player| player.entity.lives.is_alive())
+            .map(|player| {
+                (
+                    &mut player.transform,
+                    &mut player.entity.physical_body,
+                    &mut player.entity.camera,
+                    &mut player.entity.arsenal,
+                    &mut player.entity.countermeasures,
+                    &mut player.entity.systems,
+                )
+            });
         Box::new(player)
     }
 }
 
 impl PlayerController {
-    pub fn new() -> Self {
+    pub fn new(input: InputMap) -> Self {
         Self {
-            buttons: vec![],
-            thruster_force: 2.5,
-            mouse_speed: 0.001,
-            bullet_cooldown: 0,
+            input,
+            mouse_speed: 0.01,
+            stick: Vec2::ZERO,
         }
     }
 
-    pub fn control(
-        &mut self,
-        time: u128,
-        entity_manager: &mut EntityManager,
-        event_reader: &mut EventReader,
-        event_sender: &mut EventSender,
-    ) {
+    // Starts over with nothing held, inputs released in the meantime never reached the old map
+    pub fn set_input(&mut self, input: InputMap) {
+        self.input = input;
+    }
+
+    pub fn control(&mut self, entity_manager: &mut EntityManager, event_reader: &mut EventReader) {
+        self.input.update(event_reader);
         entity_manager.get_mut_view().for_each(
-            |(transform, physical_body, camera): (
+            |(transform, physical_body, camera, arsenal, countermeasures, systems): (
                 &mut Transform,
                 &mut PhysicalBody,
                 &mut Camera,
+                &mut Arsenal,
+                &mut Countermeasures,
+                &mut ShipSystems,
             )| {
-                self.process_inputs(transform, camera, event_reader);
-                self.move_around(time, transform, physical_body, event_sender);
+                self.process_inputs(camera, systems);
+                self.move_around(transform, physical_body, arsenal, countermeasures, systems);
             },
         );
     }
 
-    fn process_inputs(
-        &mut self,
-        transform: &mut Transform,
-        camera: &mut Camera,
-        event_reader: &mut EventReader,
-    ) {
-        event_reader.read(|event| match event {
-            IoEvent::KeyPressed(key) => self.buttons.push(key),
-            IoEvent::KeyReleased(key) => {
-                if let Some(key) = self.buttons.iter().position(|&k| k == key) {
-                    self.buttons.swap_remove(key);
-                }
-            }
-            IoEvent::MouseMotion((x, y)) => {
-                //let x_rotation = Quat::from_rotation_x(-y * self.mouse_speed);
-                //let y_rotation = Quat::from_rotation_y(-x * self.mouse_speed);
-                //transform.rotation = transform.rotation * y_rotation * x_rotation;
-                let yaw = Quat::from_axis_angle(Vec3::new(1.0, 0.0, 0.0), y * self.mouse_speed);
-                let pitch = Quat::from_axis_angle(Vec3::new(0.0, 1.0, 0.0), x * self.mouse_speed);
-                camera.apply_rotation(pitch);
-                camera.apply_rotation(yaw);
-            }
-            _ => {}
-        });
+    // The mouse deflects a virtual stick that springs back to the centre
+    fn process_inputs(&mut self, camera: &mut Camera, systems: &mut ShipSystems) {
+        if self.input.pressed("flight_assist") {
+            systems.flight_assist = !systems.flight_assist;
+        }
+        let zoom = self.input.value("zoom_out");
+        if zoom != 0.0 {
+            let offset = camera.position() * ZOOM_STEP.powf(zoom);
+            camera.set_position(offset.clamp_length(MIN_CAMERA_DISTANCE, MAX_CAMERA_DISTANCE));
+        }
+        let motion = Vec2::new(self.input.value("yaw"), self.input.value("pitch"));
+        self.stick = (self.stick + motion * self.mouse_speed).clamp_length_max(1.0);
     }
 
     fn move_around(
         &mut self,
-        delta: u128,
         transform: &mut Transform,
         physical_body: &mut PhysicalBody,
-        event_sender: &mut EventSender,
+        arsenal: &mut Arsenal,
+        countermeasures: &mut Countermeasures,
+        systems: &mut ShipSystems,
     ) {
-        let mut force = Vec4::ZERO;
-
-        self.bullet_cooldown = if self.bullet_cooldown > delta {
-            self.bullet_cooldown - delta
-        } else {
-            0
-        };
-
-        let create_bullet = |pos: Vec4| {
-            let mut transform1 = *transform;
-            transform1.position += transform.to_global(pos).xyz();
-
-            let velocity = physical_body.velocity()
-                + transform.to_global(Vec4::new(0.0, 0.0, -256.0, 0.0)).xyz();
-
-            let mut body1 = PhysicalBody::new(1.0, 1.0, 1.0);
-
-            body1.momentum = velocity;
-
-            let sender = event_sender.clone();
+        let input = &self.input;
+        let thrust = Vec3::new(
+            input.value("strafe_right") - input.value("strafe_left"),
+            input.value("strafe_up") - input.value("strafe_down"),
+            input.value("thrust_back") - input.value("thrust_forward"),
+        );
+        let roll = (input.value("roll_left") - input.value("roll_right")).clamp(-1.0, 1.0);
+        let steer = Vec2::new(input.value("yaw_rate"), input.value("pitch_rate"));
+        systems.boost = input.held("boost");
+        countermeasures.trigger = input.held("countermeasures");
+        arsenal.current_mut().trigger = input.held("fire_primary");
+        if let Some(slot) = (0..9).find(|slot| input.pressed(&format!("weapon_{}", slot + 1))) {
+            arsenal.select(slot);
+        }
+        if input.pressed("weapon_next") {
+            arsenal.cycle(1);
+        }
+        if input.pressed("weapon_previous") {
+            arsenal.cycle(-1);
+        }
 
-            event_sender.write(GameEvent::ShootPlasmaBullet(
-                transform1,
-                BulletEntity {
-                    collider: Collider {
-                        toi: 0.0,
-                        last_impact: Vec3::ZERO,
-                        radius: 0.5,
-                        callback: Some(Box::new(move |id, entity, pos| {
-                            sender.write(BulletEvent::Exploded(id, pos));
-                            sender.write(BulletEvent::Damaged(entity, 10.0))
-                        })),
-                    },
-                    body: body1,
-                    lifetime: 5.0,
-                },
-            ));
-        };
+        let budget = Vec3::new(systems.strafe, systems.strafe, 1.0) * systems.thrust();
+        let mut force = thrust.clamp(Vec3::NEG_ONE, Vec3::ONE) * budget;
+        if systems.flight_assist {
+            force += counter_drift(transform.rotation, physical_body, thrust, budget);
+        }
+        physical_body.impulse(transform.rotation * force);
+
+        // Analog sticks steer directly, on top of whatever the mouse is doing
+        let stick = (self.stick + steer).clamp_length_max(1.0);
+        let turn = Vec3::new(-stick.y, -stick.x, roll) * systems.turn_rate;
+        let torque = rotational_thrust(transform.rotation, physical_body, turn, systems.torque);
+        physical_body.torque(torque);
+        self.stick *= STICK_RETURN;
+    }
+}
 
-        self.buttons.iter().for_each(|button| match button {
-            'S' => force += Vec4::new(0.0, 0.0, self.thruster_force, 0.0),
-            'W' => force += Vec4::new(0.0, 0.0, -self.thruster_force, 0.0),
-            '1' => {
-                if self.bullet_cooldown == 0 {
-                    self.bullet_cooldown = 2_00_000_000;
-                    create_bullet(Vec4::new(-1.5, -0.4, -2.5, 0.0));
-                    create_bullet(Vec4::new(1.5, -0.4, -2.5, 0.0));
-                }
-            }
-            _ => {}
-        });
+// Axes the pilot leaves alone get thrust against the drift along them, within the thruster budget
+fn counter_drift(rotation: Quat, body: &PhysicalBody, thrust: Vec3, budget: Vec3) -> Vec3 {
+    let momentum = rotation.inverse() * body.momentum;
+    let idle = Vec3::select(thrust.cmpeq(Vec3::ZERO), Vec3::ONE, Vec3::ZERO);
+    (-momentum).clamp(-budget, budget) * idle
+}
 
-        let force = transform.model() * force;
-        physical_body.impulse(Vec3::new(force.x, force.y, force.z));
-    }
+// Rotational thrusters chase the requested turn rate, which also stops any leftover spin
+fn rotational_thrust(rotation: Quat, body: &PhysicalBody, turn: Vec3, torque: f32) -> Vec3 {
+    let spin = rotation.inverse() * body.angular_velocity();
+    let correction = (turn - spin) * body.angular_inertia * ANGULAR_RESPONSE;
+    rotation * correction.clamp_length_max(torque)
 }
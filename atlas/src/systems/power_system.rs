@@ -0,0 +1,18 @@
+use crate::{entity_manager::EntityManager, game_entities::player_ship::PlayerShip};
+
+// Thruster particles while the afterburner is lit
+const BOOST_EXHAUST: f32 = 4.0;
+
+pub fn update_ship_systems(entity_manager: &mut EntityManager, delta: f32) {
+    entity_manager
+        .iter_mut::<PlayerShip>()
+        .filter(|player| player.entity.lives.is_alive())
+        .for_each(|player| {
+            let ship = &mut player.entity;
+            ship.systems.update(delta);
+            ship.thruster.intensity = match ship.systems.is_boosting() {
+                true => BOOST_EXHAUST,
+                false => 1.0,
+            };
+        });
+}
@@ -0,0 +1,90 @@
+use glam::Vec3;
+
+use crate::{
+    components::lives::PilotState,
+    entity_manager::EntityManager,
+    event_bus::EventSender,
+    game_entities::{
+        player_ship::{DetachedCamera, PlayerShip},
+        session::Session,
+    },
+    random::RandomStream,
+    resource_manager::scene_resource_manager::SceneResourceManager,
+    scene::Summary,
+};
+
+use super::bullet_detonator::spawn_explosion;
+
+// How far the camera pulls back from the wreck while the pilot waits for a new ship
+const WRECK_CAMERA_ZOOM: f32 = 6.0;
+
+pub enum PlayerEvent {
+    GameOver(Summary),
+}
+
+pub fn update_lives(
+    entity_manager: &mut EntityManager,
+    resource_manager: &mut SceneResourceManager,
+    event_sender: &EventSender,
+    rng: &RandomStream,
+    time: f32,
+    delta: f32,
+) {
+    let mut summary =
+        entity_manager
+            .iter::<Session>()
+            .next()
+            .map_or(Summary::default(), |session| {
+                let score = &session.entity.score;
+                Summary {
+                    score: score.points,
+                    wave: session.entity.waves.spawned,
+                    kills: score.kills,
+                    accuracy: score.accuracy(),
+                    ..Summary::default()
+                }
+            });
+
+    let Some(player) = entity_manager.iter_mut::<PlayerShip>().next() else {return};
+    let ship = &mut player.entity;
+
+    let mut wreck = None;
+    if ship.lives.is_alive() && ship.info.health < 0.0 {
+        ship.lives.kill();
+        ship.physical_body.momentum = Vec3::ZERO;
+        ship.physical_body.angular_momentum = Vec3::ZERO;
+        // The camera is left behind at the wreck and pulls back from it
+        ship.detached_camera = Some(DetachedCamera {
+            transform: player.transform,
+            offset: ship.camera.position(),
+        });
+        ship.camera
+            .set_position(ship.camera.position() * WRECK_CAMERA_ZOOM);
+        wreck = Some(player.transform.position);
+    }
+
+    let state = ship.lives.state();
+    if ship.lives.update(delta) {
+        match state {
+            PilotState::Respawning(_) => {
+                player.transform.position = ship.lives.checkpoint;
+                if let Some(detached) = ship.detached_camera.take() {
+                    ship.camera.set_position(detached.offset);
+                }
+                ship.info.restore();
+                ship.systems.restore();
+                ship.info.protect(ship.lives.spawn_protection);
+            }
+            PilotState::GameOver(_) => {
+                summary.survived = time;
+                summary.lives_lost = ship.lives.lost;
+                event_sender.write(PlayerEvent::GameOver(summary))
+            }
+            PilotState::Alive => {}
+        }
+    }
+
+    if let Some(position) = wreck {
+        spawn_explosion(entity_manager, resource_manager, "fireball", position, rng);
+    }
+}
@@ -0,0 +1,250 @@
+use std::{collections::HashMap, fs, mem, path::PathBuf, time::SystemTime};
+
+use rhai::{CallFnOptions, Dynamic, Engine, FuncArgs, Scope, AST};
+
+use crate::{
+    components::{physical_body::PhysicalBody, transform::Transform, unit::Unit},
+    entity_manager::{ComponentIteratorGenerator, EntityManager},
+    event_bus::{EventReader, EventSender},
+    game_entities::scripted::Scripted,
+    scripting::{
+        sandbox::create_engine,
+        world::{Command, World},
+    },
+};
+
+use super::{bullet_detonator::for_each_unit, physical_simulation::for_each_body};
+
+// How often the script files are checked for edits
+const RELOAD_INTERVAL: f32 = 1.0;
+
+// Delivered to the `on_signal` function of every script on the next tick
+pub enum ScriptEvent {
+    Signal(String),
+}
+
+// A script that fails to compile stays cached without an AST until its file changes
+struct Compiled {
+    ast: Option<AST>,
+    functions: Vec<String>,
+    modified: Option<SystemTime>,
+}
+
+pub struct ScriptSystem {
+    engine: Engine,
+    scripts: HashMap<PathBuf, Compiled>,
+    reload: f32,
+    // Off in deterministic runs, an edited file would make them diverge
+    hot_reload: bool,
+}
+
+impl ScriptSystem {
+    pub fn new(hot_reload: bool) -> Self {
+        Self {
+            engine: create_engine(),
+            scripts: HashMap::new(),
+            reload: RELOAD_INTERVAL,
+            hot_reload,
+        }
+    }
+
+    // Scripts get a snapshot of the world, their component writes are applied after all of them ran
+    pub fn run(
+        &mut self,
+        entity_manager: &mut EntityManager,
+        event_reader: &EventReader,
+        event_sender: &EventSender,
+        time: f32,
+        delta: f32,
+    ) {
+        let mut signals = vec![];
+        event_reader.read(|event| match event {
+            ScriptEvent::Signal(name) => signals.push(name),
+        });
+        if entity_manager.iter::<Scripted>().next().is_none() {
+            return;
+        }
+
+        self.reload -= delta;
+        if self.hot_reload && self.reload <= 0.0 {
+            self.reload = RELOAD_INTERVAL;
+            self.hot_reload(entity_manager);
+        }
+
+        let world = snapshot(entity_manager, event_sender, time);
+        let mut detached = vec![];
+        entity_manager.iter_mut::<Scripted>().for_each(|scripted| {
+            let (id, scripted) = (scripted.id, &mut scripted.entity);
+            if scripted.failed {
+                return;
+            }
+            if let Some(target) = scripted.target {
+                if !world.frame.borrow().transforms.contains_key(&target) {
+                    detached.push(id);
+                    return;
+                }
+            }
+
+            let script = &scripted.script;
+            let compiled = self
+                .scripts
+                .entry(script.path.clone())
+                .or_insert_with(|| load(&self.engine, &script.path, &script.source));
+            let Some(ast) = &compiled.ast else {
+                scripted.failed = true;
+                return;
+            };
+            world.frame.borrow_mut().entity = scripted.target;
+
+            let mut calls: Vec<(&str, Vec<Dynamic>)> = vec![];
+            if !scripted.initialised {
+                scripted.initialised = true;
+                calls.push(("init", vec![Dynamic::from(world.clone())]));
+            }
+            signals.iter().for_each(|signal| {
+                calls.push((
+                    "on_signal",
+                    vec![Dynamic::from(world.clone()), Dynamic::from(signal.clone())],
+                ))
+            });
+            calls.push((
+                "update",
+                vec![Dynamic::from(world.clone()), Dynamic::from(delta)],
+            ));
+
+            for (function, args) in calls {
+                if !compiled.functions.iter().any(|name| name == function) {
+                    continue;
+                }
+                if let Err(e) = call(&self.engine, ast, &mut scripted.state, function, args)
+                {
+                    println!(
+                        "Script '{}' failed in '{}': {}",
+                        scripted.script.path.display(),
+                        function,
+                        e
+                    );
+                    scripted.failed = true;
+                    break;
+                }
+            }
+        });
+
+        detached
+            .into_iter()
+            .for_each(|id| entity_manager.remove::<Scripted>(id));
+
+        let commands = mem::take(&mut world.frame.borrow_mut().commands);
+        apply(entity_manager, commands);
+    }
+
+    // Edited scripts are recompiled in place, scripts that failed get another chance
+    fn hot_reload(&mut self, entity_manager: &mut EntityManager) {
+        let mut reloaded = vec![];
+        self.scripts.iter_mut().for_each(|(path, compiled)| {
+            let modified = modified(path);
+            if modified.is_none() || modified == compiled.modified {
+                return;
+            }
+
+            let Ok(source) = fs::read_to_string(path) else {return};
+            *compiled = load(&self.engine, path, &source);
+            if compiled.ast.is_some() {
+                println!("Reloaded script '{}'", path.display());
+                reloaded.push((path.clone(), source));
+            }
+        });
+
+        entity_manager.iter_mut::<Scripted>().for_each(|scripted| {
+            let scripted = &mut scripted.entity;
+            if let Some((_, source)) = reloaded
+                .iter()
+                .find(|(path, _)| *path == scripted.script.path)
+            {
+                scripted.script.source = source.clone();
+                scripted.failed = false;
+            }
+        });
+    }
+}
+
+fn load(engine: &Engine, path: &PathBuf, source: &str) -> Compiled {
+    let ast = engine
+        .compile(source)
+        .map_err(|e| println!("Failed to compile script '{}': {}", path.display(), e))
+        .ok();
+    Compiled {
+        functions: ast.as_ref().map_or(vec![], |ast| {
+            ast.iter_functions()
+                .map(|function| String::from(function.name))
+                .collect()
+        }),
+        ast,
+        modified: modified(path),
+    }
+}
+
+fn modified(path: &PathBuf) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+// Script functions see their persistent state as `this`
+fn call(
+    engine: &Engine,
+    ast: &AST,
+    state: &mut Dynamic,
+    function: &str,
+    args: impl FuncArgs,
+) -> Result<(), String> {
+    let options = CallFnOptions::new()
+        .eval_ast(false)
+        .bind_this_ptr(state);
+    engine
+        .call_fn_with_options::<Dynamic>(options, &mut Scope::new(), ast, function, args)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+fn snapshot(entity_manager: &EntityManager, event_sender: &EventSender, time: f32) -> World {
+    let world = World::new(event_sender.clone(), time);
+    {
+        let mut frame = world.frame.borrow_mut();
+        entity_manager.get_view().for_each(
+            |(id, transform, body): (usize, &Transform, &PhysicalBody)| {
+                frame.transforms.insert(id, *transform);
+                frame.bodies.insert(id, body.clone());
+            },
+        );
+        entity_manager
+            .get_view()
+            .for_each(|(id, unit): (usize, &Unit)| {
+                frame.units.insert(id, unit.clone());
+            });
+    }
+    world
+}
+
+fn apply(entity_manager: &mut EntityManager, commands: Vec<Command>) {
+    if commands.is_empty() {
+        return;
+    }
+
+    for_each_body(entity_manager, |id, transform, body| {
+        commands.iter().for_each(|command| match command {
+            Command::Transform(target, value) if *target == id => *transform = *value,
+            Command::Body(target, momentum, angular_momentum) if *target == id => {
+                body.momentum = *momentum;
+                body.angular_momentum = *angular_momentum;
+            }
+            _ => {}
+        })
+    });
+    for_each_unit(entity_manager, |id, _, unit| {
+        commands.iter().for_each(|command| match command {
+            Command::Health(target, health) if *target == id => unit.health = *health,
+            _ => {}
+        })
+    });
+}
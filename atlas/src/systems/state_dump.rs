@@ -0,0 +1,22 @@
+use crate::{
+    components::{physical_body::PhysicalBody, transform::Transform},
+    entity_manager::{ComponentIteratorGenerator, EntityManager},
+};
+
+pub fn dump_state(entity_manager: &EntityManager) -> String {
+    entity_manager
+        .get_view()
+        .map(
+            |(id, transform, body): (usize, &Transform, &PhysicalBody)| {
+                format!(
+                    "{}: position {:?} rotation {:?} momentum {:?} angular momentum {:?}\n",
+                    id,
+                    transform.position.to_array(),
+                    transform.rotation.to_array(),
+                    body.momentum.to_array(),
+                    body.angular_momentum.to_array()
+                )
+            },
+        )
+        .collect()
+}
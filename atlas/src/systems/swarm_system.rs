@@ -0,0 +1,127 @@
+use glam::{Quat, Vec3};
+
+use crate::{
+    ai::steering::{
+        alignment, arrive, avoid_obstacles, cohesion, evade, flee, pursue, seek, separation, Boid,
+        Obstacle, SquadParameters,
+    },
+    components::{physical_body::PhysicalBody, swarm_agent::SteeringGoal, transform::Transform},
+    entity_manager::{ComponentIteratorGenerator, EntityManager},
+    game_entities::{asteroid::AsteroidEntity, enemy_ship::EnemyShip, squad::Squad},
+};
+
+const TURN_RATE: f32 = 4.0;
+
+pub fn update_swarms(entity_manager: &mut EntityManager, delta: f32) {
+    let obstacles: Vec<_> = entity_manager
+        .iter::<AsteroidEntity>()
+        .map(|asteroid| Obstacle {
+            position: asteroid.transform.position,
+            radius: asteroid.entity.collider.radius,
+        })
+        .collect();
+
+    let boids: Vec<_> = entity_manager
+        .iter::<EnemyShip>()
+        .map(|ship| {
+            (
+                ship.id,
+                ship.entity.agent.squad,
+                boid(&ship.transform, &ship.entity.physical_body),
+            )
+        })
+        .collect();
+
+    let accelerations: Vec<_> = entity_manager
+        .iter::<EnemyShip>()
+        .map(|ship| {
+            let agent = &ship.entity.agent;
+            let parameters = squad_parameters(entity_manager, agent.squad);
+            let boid = boid(&ship.transform, &ship.entity.physical_body);
+
+            let neighbours: Vec<_> = boids
+                .iter()
+                .filter(|(id, squad, other)| {
+                    *id != ship.id
+                        && *squad == agent.squad
+                        && (other.position - boid.position).length() < parameters.neighbour_radius
+                })
+                .map(|(_, _, other)| *other)
+                .collect();
+
+            let steering = separation(&boid, &neighbours, parameters.separation_radius)
+                * parameters.separation_weight
+                + alignment(&boid, &neighbours) * parameters.alignment_weight
+                + cohesion(&boid, &neighbours, parameters.max_speed) * parameters.cohesion_weight
+                + goal_steering(entity_manager, &boid, agent.goal, &parameters)
+                    * parameters.goal_weight
+                + avoid_obstacles(
+                    &boid,
+                    &obstacles,
+                    parameters.look_ahead,
+                    ship.entity.collider.radius,
+                ) * parameters.avoidance_weight;
+
+            steering.clamp_length_max(parameters.max_acceleration)
+        })
+        .collect();
+
+    entity_manager
+        .iter_mut::<EnemyShip>()
+        .zip(accelerations)
+        .for_each(|(ship, acceleration)| {
+            let body = &mut ship.entity.physical_body;
+            body.impulse(acceleration * body.mass * delta);
+
+            let heading = body.velocity() + acceleration * delta;
+            if heading.length() > 0.1 {
+                let facing = Quat::from_rotation_arc(Vec3::NEG_Z, heading.normalize());
+                ship.transform.rotation = ship
+                    .transform
+                    .rotation
+                    .slerp(facing, (TURN_RATE * delta).min(1.0));
+            }
+        });
+}
+
+fn goal_steering(
+    entity_manager: &EntityManager,
+    boid: &Boid,
+    goal: SteeringGoal,
+    parameters: &SquadParameters,
+) -> Vec3 {
+    let max_speed = parameters.max_speed;
+    match goal {
+        SteeringGoal::Idle => -boid.velocity,
+        SteeringGoal::Seek(target) => seek(boid, target, max_speed),
+        SteeringGoal::Arrive(target) => {
+            arrive(boid, target, max_speed, parameters.neighbour_radius)
+        }
+        SteeringGoal::Flee(threat) => flee(boid, threat, max_speed),
+        SteeringGoal::Pursue(target) => find_boid(entity_manager, target)
+            .map_or(Vec3::ZERO, |target| pursue(boid, &target, max_speed)),
+        SteeringGoal::Evade(threat) => find_boid(entity_manager, threat)
+            .map_or(Vec3::ZERO, |threat| evade(boid, &threat, max_speed)),
+    }
+}
+
+fn find_boid(entity_manager: &EntityManager, entity: usize) -> Option<Boid> {
+    entity_manager
+        .get_view()
+        .find(|(id, _, _): &(usize, &Transform, &PhysicalBody)| *id == entity)
+        .map(|(_, transform, body)| boid(transform, body))
+}
+
+fn squad_parameters(entity_manager: &EntityManager, squad: usize) -> SquadParameters {
+    entity_manager
+        .get::<Squad>(squad)
+        .map(|squad| squad.entity.parameters)
+        .unwrap_or_default()
+}
+
+fn boid(transform: &Transform, body: &PhysicalBody) -> Boid {
+    Boid {
+        position: transform.position,
+        velocity: body.velocity(),
+    }
+}
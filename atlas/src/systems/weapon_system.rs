@@ -0,0 +1,307 @@
+use glam::{Quat, Vec3, Vec4Swizzles};
+use rand::Rng;
+
+use crate::{
+    ai::aiming::lead_target,
+    components::{
+        collider::Collider,
+        particle_emitter::{ParticleEmitter, ParticleEmitterDefinition},
+        physical_body::PhysicalBody,
+        transform::Transform,
+        unit::Damage,
+        weapon::{Projectile, ProjectileKind, Weapon},
+    },
+    entity_manager::{ComponentIteratorGenerator, EntityManager},
+    event_bus::EventSender,
+    game_entities::{
+        bullet::{BulletEntity, Missile},
+        enemy_ship::EnemyShip,
+        player_ship::PlayerShip,
+    },
+    graphics::{instanced_mesh::InstancedMesh, vertices::generator},
+    random::RandomStream,
+    resource_manager::{scene_resource_manager::SceneResourceManager, ResourceManager},
+};
+
+use super::{
+    bullet_detonator::BulletEvent, particle_system::thruster_spawner, player_controller::GameEvent,
+};
+
+const FIRE_CONE: f32 = 0.3;
+const LOCK_CONE: f32 = 0.5;
+const OVERCHARGE_DAMAGE: f32 = 1.5;
+
+struct Shot {
+    shooter: usize,
+    origin: Vec3,
+    direction: Vec3,
+    velocity: Vec3,
+    projectile: Projectile,
+    target: Option<usize>,
+}
+
+pub fn update_weapons(
+    entity_manager: &mut EntityManager,
+    resource_manager: &mut SceneResourceManager,
+    event_sender: &mut EventSender,
+    rng: &RandomStream,
+    delta: f32,
+) {
+    aim_enemy_weapons(entity_manager);
+
+    let mut rng = rng.clone();
+    let mut shots = vec![];
+    entity_manager
+        .iter_mut::<PlayerShip>()
+        .filter(|player| player.entity.lives.is_alive())
+        .for_each(|player| {
+            let ship = &mut player.entity;
+            ship.arsenal.update(delta);
+            ship.arsenal.current_mut().target = ship.arsenal.lock;
+            let weapon = ship.arsenal.current_mut();
+            if weapon.is_firing() && !ship.systems.draw(weapon.energy) {
+                weapon.trigger = false;
+            }
+            let mut volley = fire(
+                player.id,
+                ship.arsenal.current_mut(),
+                &player.transform,
+                &ship.physical_body,
+                &mut rng,
+            );
+            if ship.arsenal.is_overcharged() {
+                volley
+                    .iter_mut()
+                    .for_each(|shot| shot.projectile.damage *= OVERCHARGE_DAMAGE);
+            }
+            ship.arsenal.fired += volley.len() as u32;
+            shots.extend(volley);
+        });
+    entity_manager.iter_mut::<EnemyShip>().for_each(|enemy| {
+        let ship = &mut enemy.entity;
+        ship.weapon.update(delta);
+        shots.extend(fire(
+            enemy.id,
+            &mut ship.weapon,
+            &enemy.transform,
+            &ship.physical_body,
+            &mut rng,
+        ));
+    });
+
+    shots
+        .into_iter()
+        .for_each(|shot| match shot.projectile.kind {
+            ProjectileKind::Rail => hitscan(entity_manager, event_sender, shot),
+            ProjectileKind::Plasma => launch(event_sender, shot, None),
+            ProjectileKind::Missile => {
+                let target = shot
+                    .target
+                    .or_else(|| acquire_target(entity_manager, &shot));
+                let missile = Missile {
+                    target,
+                    guidance: shot.projectile.guidance,
+                    fuel: shot.projectile.guidance.fuel,
+                    damage: shot.projectile.damage,
+                    damage_type: shot.projectile.damage_type,
+                    exhaust: create_exhaust(resource_manager, rng.clone()),
+                };
+                launch(event_sender, shot, Some(missile))
+            }
+        });
+}
+
+fn aim_enemy_weapons(entity_manager: &mut EntityManager) {
+    let aims: Vec<_> = entity_manager
+        .iter::<EnemyShip>()
+        .map(|ship| {
+            let blackboard = &ship.entity.behaviour.blackboard;
+            if !blackboard.bool("engaged") {
+                return None;
+            }
+            let target = blackboard.entity("target")?;
+            let (_, target_transform, target_body) = entity_manager
+                .get_view()
+                .find(|(id, _, _): &(usize, &Transform, &PhysicalBody)| *id == target)?;
+
+            let aim = lead_target(
+                ship.transform.position,
+                ship.entity.physical_body.velocity(),
+                target_transform.position,
+                target_body.velocity(),
+                ship.entity.weapon.projectile.speed,
+            )?;
+            Some((target, aim))
+        })
+        .collect();
+
+    entity_manager
+        .iter_mut::<EnemyShip>()
+        .zip(aims)
+        .for_each(|(ship, aim)| {
+            let facing = ship.transform.rotation * Vec3::NEG_Z;
+            let weapon = &mut ship.entity.weapon;
+            weapon.target = aim.map(|(target, _)| target);
+            weapon.aim = aim.map(|(_, aim)| aim);
+            weapon.trigger = aim.map_or(false, |(_, aim)| facing.angle_between(aim) < FIRE_CONE);
+        });
+}
+
+fn fire(
+    shooter: usize,
+    weapon: &mut Weapon,
+    transform: &Transform,
+    body: &PhysicalBody,
+    rng: &mut RandomStream,
+) -> Vec<Shot> {
+    if !weapon.fire() {
+        return vec![];
+    }
+
+    let direction = weapon
+        .aim
+        .unwrap_or_else(|| transform.rotation * Vec3::NEG_Z);
+
+    let mut shots = vec![];
+    weapon.muzzles.iter().for_each(|muzzle| {
+        (0..weapon.projectile.pellets.max(1)).for_each(|_| {
+            shots.push(Shot {
+                shooter,
+                origin: transform.position + transform.to_global(muzzle.extend(0.0)).xyz(),
+                direction: scatter(direction, weapon.spread, rng),
+                velocity: body.velocity(),
+                projectile: weapon.projectile.clone(),
+                target: weapon.target,
+            })
+        })
+    });
+    shots
+}
+
+fn launch(event_sender: &mut EventSender, shot: Shot, missile: Option<Missile>) {
+    let projectile = &shot.projectile;
+    let mut body = PhysicalBody::new(1.0, 1.0, 1.0);
+    body.momentum = shot.velocity + shot.direction * projectile.speed;
+
+    let (damage, damage_type, source) = (projectile.damage, projectile.damage_type, shot.shooter);
+    let sender = event_sender.clone();
+    let bullet = BulletEntity {
+        source,
+        collider: Collider {
+            toi: 0.0,
+            last_impact: Vec3::ZERO,
+            radius: projectile.radius,
+            callback: Some(Box::new(move |id, entity, pos| {
+                sender.write(BulletEvent::Exploded(id, pos));
+                sender.write(BulletEvent::Damaged(
+                    entity,
+                    Damage::new(damage, damage_type, pos, Some(source)),
+                ))
+            })),
+        },
+        body,
+        lifetime: projectile.lifetime,
+        colour: projectile.colour,
+        explosion: projectile.explosion.clone(),
+        missile,
+    };
+
+    let transform = Transform {
+        position: shot.origin,
+        scale: Vec3::ONE,
+        rotation: Quat::from_rotation_arc(Vec3::NEG_Z, shot.direction),
+    };
+    event_sender.write(GameEvent::ShootPlasmaBullet(transform, bullet));
+}
+
+fn hitscan(entity_manager: &EntityManager, event_sender: &mut EventSender, shot: Shot) {
+    let range = shot.projectile.range();
+    let hit = entity_manager
+        .get_view()
+        .filter(
+            |(id, _, _, _): &(usize, &Transform, &Collider, &PhysicalBody)| {
+                *id != shot.shooter && entity_manager.get::<BulletEntity>(*id).is_none()
+            },
+        )
+        .filter_map(|(id, transform, collider, _)| {
+            let distance = ray_sphere(
+                shot.origin,
+                shot.direction,
+                transform.position,
+                collider.radius,
+            )?;
+            (distance <= range).then_some((id, distance))
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b));
+
+    let end = shot.origin + shot.direction * hit.map_or(range, |(_, distance)| distance);
+    event_sender.write(BulletEvent::Beam(shot.origin, end));
+    if let Some((target, _)) = hit {
+        let projectile = shot.projectile;
+        let damage = Damage::new(
+            projectile.damage,
+            projectile.damage_type,
+            end,
+            Some(shot.shooter),
+        );
+        event_sender.write(BulletEvent::Impact(end, projectile.explosion));
+        event_sender.write(BulletEvent::Damaged(target, damage));
+    }
+}
+
+fn acquire_target(entity_manager: &EntityManager, shot: &Shot) -> Option<usize> {
+    let targets = entity_manager
+        .iter::<EnemyShip>()
+        .map(|ship| (ship.id, ship.transform.position))
+        .filter(|(id, _)| *id != shot.shooter);
+
+    targets
+        .filter(|(_, position)| shot.direction.angle_between(*position - shot.origin) < LOCK_CONE)
+        .min_by(|(_, a), (_, b)| {
+            (*a - shot.origin)
+                .length()
+                .total_cmp(&(*b - shot.origin).length())
+        })
+        .map(|(id, _)| id)
+}
+
+fn create_exhaust(
+    resource_manager: &mut SceneResourceManager,
+    rng: RandomStream,
+) -> ParticleEmitter {
+    let (vertices, indices) = generator::quad(1.0, 1.0);
+    let instanced_mesh = InstancedMesh::new(&vertices, &indices, &vec![]);
+
+    ParticleEmitter::new(
+        ParticleEmitterDefinition {
+            count: 50,
+            rate: 0.005,
+        },
+        resource_manager.get("explosion").res,
+        instanced_mesh,
+        Box::new(thruster_spawner(rng)),
+    )
+}
+
+fn ray_sphere(origin: Vec3, direction: Vec3, centre: Vec3, radius: f32) -> Option<f32> {
+    let offset = centre - origin;
+    let along = offset.dot(direction);
+    let miss = offset.length_squared() - along * along;
+    if miss > radius * radius {
+        return None;
+    }
+    let distance = along - (radius * radius - miss).sqrt();
+    (distance >= 0.0).then_some(distance)
+}
+
+fn scatter(direction: Vec3, spread: f32, rng: &mut RandomStream) -> Vec3 {
+    if spread == 0.0 {
+        return direction;
+    }
+    let offset = Vec3::new(
+        rng.gen_range(-1.0..1.0),
+        rng.gen_range(-1.0..1.0),
+        rng.gen_range(-1.0..1.0),
+    ) * spread;
+    (direction + offset).normalize()
+}
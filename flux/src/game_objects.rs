@@ -1,2 +1,5 @@
 pub mod asteroids;
 pub mod menu;
+pub mod options;
+pub mod pause;
+pub mod ui_page;
@@ -1,25 +1,38 @@
 use atlas::{
+    ai::{behaviour_tree::BehaviourTree, steering::SquadParameters},
     components::{
+        behaviour::Behaviour,
         camera::{Camera, Frustrum},
         collider::Collider,
+        countermeasures::Countermeasures,
         health_renderer::HealthRenderer,
+        layout::{Layout, Length, BOTTOM_LEFT, CENTER, TOP},
+        lives::Lives,
         particle_emitter::{ParticleEmitter, ParticleEmitterDefinition},
         physical_body::PhysicalBody,
         skybox_renderer::SkyboxRenderer,
         sprite_renderer::SpriteRenderer,
+        swarm_agent::{SteeringGoal, SwarmAgent},
         text_renderer::TextRenderer,
         transform::Transform,
-        unit::Unit,
+        unit::{Armour, HitZones, Shield, Unit},
+        weapon::{Arsenal, Weapon},
     },
     entity_manager::EntityManager,
     game_entities::{
         asteroid::{generate_asteroid, AsteroidEntity},
         enemy_ship::EnemyShip,
-        hud::HudEntity,
+        hud::{HudEntity, HudNodes},
+        mission::MissionEntity,
+        scripted::Scripted,
         player_ship::PlayerShip,
+        session::Session,
         space_box::SpaceBox,
+        squad::Squad,
         starlight::Starlight,
+        ui_layout::UiLayout,
     },
+    game_mode::waves::Wave,
     game_root::GameError,
     graphics::{
         graphics_context::GraphicsContext,
@@ -32,40 +45,51 @@ use atlas::{
         model::Model,
         vertices::generator,
     },
+    random::{Random, RandomStream},
     resource_manager::{font::Font, scene_resource_manager::SceneResourceManager, ResourceManager},
-    systems::particle_system::thruster_spawner,
+    settings::Settings,
+    systems::{layout_system::update_layout, particle_system::thruster_spawner},
 };
 use glam::{Quat, Vec3};
 use rand::Rng;
 
+const SQUAD_SIZE: usize = 5;
+const PLAYER_LIVES: u32 = 3;
+const WAVE_DISTANCE: f32 = 250.0;
+const PLAYER_WEAPONS: [&str; 4] = ["plasma", "spread", "rail", "missile"];
+
 pub fn asteroids(
     entity_manager: &mut EntityManager,
     resource_manager: &mut SceneResourceManager,
     graphics_context: &mut GraphicsContext,
+    random: &Random,
+    settings: &Settings,
 ) -> Result<(), GameError> {
-    create_asteroids(entity_manager, resource_manager);
+    create_asteroids(
+        entity_manager,
+        resource_manager,
+        &mut random.stream("asteroids"),
+    );
 
     let skybox_material: SkyboxMaterial = resource_manager.get("space1").res;
     let skybox = SkyboxRenderer::new(1.0, skybox_material);
 
-    let meshes = ["spaceship3"];
-
-    let meshes: Vec<Model> = meshes
-        .iter()
-        .map(|mesh| {
-            return resource_manager.get(mesh).res;
-        })
-        .collect();
-
     let (width, height) = graphics_context.dimensions();
 
     let camera = Camera::new(
-        Frustrum::perspective(width as f32, height as f32, 0.1, 1000.0),
+        Frustrum::perspective_fov(
+            settings.fov.to_radians(),
+            width as f32,
+            height as f32,
+            0.1,
+            1000.0,
+        ),
         Vec3::new(0.0, 1.5, 5.0),
         Quat::from_axis_angle(Vec3::new(0.0, 0.0, 1.0), 0.0),
     );
 
-    let thruster = create_thruster(resource_manager);
+    let thrusters = random.stream("thrusters");
+    let thruster = create_thruster(resource_manager, thrusters.clone());
 
     let player_id = entity_manager.add_at(
         PlayerShip {
@@ -79,6 +103,17 @@ pub fn asteroids(
             },
             thruster,
             mesh: resource_manager.get("spaceship3").res,
+            arsenal: Arsenal::new(
+                PLAYER_WEAPONS
+                    .iter()
+                    .map(|weapon| resource_manager.get(weapon).res)
+                    .collect(),
+            ),
+            countermeasures: Countermeasures::new(12),
+            systems: resource_manager.get("player").res,
+            info: pilot_unit(),
+            lives: Lives::new(PLAYER_LIVES, Vec3::ZERO),
+            detached_camera: None,
         },
         Transform {
             position: Vec3::new(0.0, 0.0, 0.0),
@@ -87,8 +122,141 @@ pub fn asteroids(
         },
     );
 
-    meshes.iter().enumerate().for_each(|(_i, mesh)| {
-        let thruster = create_thruster(resource_manager);
+    spawn_squad(
+        entity_manager,
+        resource_manager,
+        &thrusters,
+        Vec3::new(0.0, 0.0, -60.0),
+        SQUAD_SIZE,
+    );
+
+    entity_manager.add(Session::new(
+        resource_manager.get("arena").res,
+        resource_manager.get("arena").res,
+    ));
+    entity_manager.add(MissionEntity::new(resource_manager.get("first").res));
+    entity_manager.add(Scripted::new(resource_manager.get("arena").res, None));
+    entity_manager.add(Scripted::new(
+        resource_manager.get("repair").res,
+        Some(player_id),
+    ));
+    entity_manager.add(SpaceBox { renderer: skybox });
+    create_lights(entity_manager, resource_manager);
+    create_hud(
+        player_id,
+        entity_manager,
+        resource_manager,
+        graphics_context,
+    );
+
+    Ok(())
+}
+
+fn pilot_unit() -> Unit {
+    let mut unit = Unit::new("Player", "Player", 200.0);
+    unit.shield = Shield::new(100.0, 20.0, 4.0);
+    unit.armour = Armour::new(0.1, 0.1, 0.1);
+    unit
+}
+
+// Enemies of a wave come in squads from a random direction, the extra asteroids are scattered around
+// the player so the field never runs dry
+pub fn spawn_wave(
+    entity_manager: &mut EntityManager,
+    resource_manager: &mut SceneResourceManager,
+    rng: &mut RandomStream,
+    wave: Wave,
+) {
+    let centre = entity_manager
+        .iter::<PlayerShip>()
+        .next()
+        .map_or(Vec3::ZERO, |player| player.transform.position);
+    let arrival = centre + random_direction(rng) * WAVE_DISTANCE;
+    let mut enemies = wave.enemies as usize;
+    let mut offset = Vec3::ZERO;
+    while enemies > 0 {
+        let count = enemies.min(SQUAD_SIZE);
+        spawn_squad(
+            entity_manager,
+            resource_manager,
+            &rng.clone(),
+            arrival + offset,
+            count,
+        );
+        enemies -= count;
+        offset += Vec3::new(0.0, 15.0, 0.0);
+    }
+
+    let material: PhongMaterial = resource_manager.get("perlin").res;
+    (0..wave.asteroids).for_each(|_| {
+        let position = centre + random_direction(rng) * WAVE_DISTANCE;
+        let radius = rng.gen_range(5.0..20.0);
+        entity_manager.add_at(
+            AsteroidEntity::prefab(material.clone(), radius, rng),
+            Transform::pos(position),
+        );
+    });
+}
+
+// Prefabs that mission scripts are allowed to spawn
+pub fn spawn_prefab(
+    entity_manager: &mut EntityManager,
+    resource_manager: &mut SceneResourceManager,
+    rng: &mut RandomStream,
+    prefab: &str,
+    position: Vec3,
+    count: u32,
+) {
+    match prefab {
+        "squad" => spawn_squad(
+            entity_manager,
+            resource_manager,
+            &rng.clone(),
+            position,
+            count as usize,
+        ),
+        "asteroid" => {
+            let material: PhongMaterial = resource_manager.get("perlin").res;
+            (0..count).for_each(|_| {
+                let offset = random_direction(rng) * rng.gen_range(0.0..50.0);
+                let radius = rng.gen_range(5.0..20.0);
+                entity_manager.add_at(
+                    AsteroidEntity::prefab(material.clone(), radius, rng),
+                    Transform::pos(position + offset),
+                );
+            });
+        }
+        prefab => println!("Unknown prefab '{}'", prefab),
+    }
+}
+
+fn random_direction(rng: &mut RandomStream) -> Vec3 {
+    Vec3::new(
+        rng.gen_range(-1.0..1.0),
+        rng.gen_range(-1.0..1.0),
+        rng.gen_range(-1.0..1.0),
+    )
+    .try_normalize()
+    .unwrap_or(Vec3::Z)
+}
+
+fn spawn_squad(
+    entity_manager: &mut EntityManager,
+    resource_manager: &mut SceneResourceManager,
+    rng: &RandomStream,
+    origin: Vec3,
+    count: usize,
+) {
+    let squad = entity_manager.add(Squad {
+        parameters: SquadParameters::default(),
+    });
+
+    let mesh: Model = resource_manager.get("spaceship3").res;
+    let behaviour: BehaviourTree = resource_manager.get("ravager").res;
+    let weapon: Weapon = resource_manager.get("ravager").res;
+
+    (0..count).for_each(|i| {
+        let thruster = create_thruster(resource_manager, rng.clone());
 
         entity_manager.add_at(
             EnemyShip {
@@ -101,29 +269,36 @@ pub fn asteroids(
                 physical_body: PhysicalBody::new(100., 100., 0.995),
                 thruster,
                 mesh: mesh.clone(),
-                info: Unit::new("Ravager A", "Enemy", 256.0),
+                info: ravager_unit(),
+                agent: SwarmAgent::new(squad, SteeringGoal::Idle),
+                behaviour: Behaviour::new(behaviour.clone()),
+                weapon: weapon.clone(),
+                countermeasures: Countermeasures::new(6),
             },
-            Transform::pos(Vec3::new(0.0, 0.0, -10.0)),
+            Transform::pos(origin + Vec3::new(i as f32 * 10.0 - 20.0, 0.0, 0.0)),
         );
     });
+}
 
-    entity_manager.add(SpaceBox { renderer: skybox });
-    create_lights(entity_manager, resource_manager);
-    create_hud(
-        player_id,
-        entity_manager,
-        resource_manager,
-        graphics_context,
-    );
-
-    Ok(())
+// Light shields and a hardened nose, the engines at the back are the weak spot
+fn ravager_unit() -> Unit {
+    let mut unit = Unit::new("Ravager A", "Enemy", 256.0);
+    unit.shield = Shield::new(96.0, 24.0, 3.0);
+    unit.armour = Armour::new(0.3, 0.1, 0.0);
+    unit.hit_zones = HitZones {
+        front: 0.75,
+        side: 1.0,
+        rear: 1.5,
+    };
+    unit
 }
 
 fn create_asteroids(
     entity_manager: &mut EntityManager,
     resource_manager: &mut SceneResourceManager,
+    rnd: &mut RandomStream,
 ) {
-    let _ = generate_asteroid((200, 200))
+    let _ = generate_asteroid((200, 200), rnd)
         .map(|texture| resource_manager.register("perlin", PhongMaterial { diffuse: texture }));
 
     let material: &PhongMaterial = &resource_manager.get("perlin").res;
@@ -131,10 +306,10 @@ fn create_asteroids(
     (-2..3).for_each(|x| {
         (-2..3).for_each(|y| {
             (-2..3).for_each(|z| {
-                let mut rnd = rand::thread_rng();
+                let radius = rnd.gen_range(5.0..20.0);
 
                 entity_manager.add_at(
-                    AsteroidEntity::prefab(material.clone(), rnd.gen_range(5.0..20.0)),
+                    AsteroidEntity::prefab(material.clone(), radius, rnd),
                     Transform::pos(Vec3::new(
                         x as f32 * 100.0 + rnd.gen_range(-10.0..10.0),
                         y as f32 * 100.0 + rnd.gen_range(-10.0..10.0),
@@ -152,32 +327,54 @@ fn create_hud(
     resource_manager: &mut SceneResourceManager,
     graphics_context: &mut GraphicsContext,
 ) -> usize {
-    let (width, height) = graphics_context.dimensions();
     let font: Font = resource_manager.get("main").res;
     let mat: SpriteMaterial = resource_manager.get("white").res;
 
+    let mut ui = UiLayout::new();
+    let nodes = HudNodes {
+        crosshair: ui.add(None, Layout::new(CENTER, (Length::Px(0.0), Length::Px(0.0)))),
+        score: ui.add(None, Layout::anchored(TOP, (0.0, 20.0), (700.0, 32.0))),
+        mission: ui.add(None, Layout::anchored(TOP, (0.0, 60.0), (700.0, 32.0))),
+        status: ui.add(None, Layout::anchored(BOTTOM_LEFT, (30.0, -70.0), (500.0, 32.0))),
+        systems: ui.add(None, Layout::anchored(BOTTOM_LEFT, (30.0, -30.0), (500.0, 32.0))),
+    };
+    entity_manager.add(ui);
+
     let crosshair = SpriteRenderer::crosshair(mat.clone());
 
     let health = HealthRenderer::health_bar(100.0);
 
-    entity_manager.add_at(
-        HudEntity {
-            health,
-            player_id,
-            crosshair,
-            velocity: new_text("Velocitty", font.clone(), 100.0, -30.0),
-            mass: new_text("Unit: []", font.clone(), 110.0, 0.0),
-            unit: new_text("Unit allometry", font.clone(), 100.0, 30.0),
-        },
-        Transform::pos(Vec3::new(width as f32 * 0.5, height as f32 * 0.5, 0.)),
-    )
+    let hud = entity_manager.add(HudEntity {
+        health,
+        player_id,
+        nodes,
+        crosshair,
+        visible: true,
+        // Target readouts stay next to the crosshair, the rest is placed by the layout
+        velocity: new_text("Velocitty", font.clone(), 100.0, -30.0),
+        mass: new_text("Unit: []", font.clone(), 110.0, 0.0),
+        status: new_text("", font.clone(), 0.0, 0.0),
+        systems: new_text("", font.clone(), 0.0, 0.0),
+        score: new_text("", font.clone(), 0.0, 0.0),
+        mission: new_text("", font.clone(), 0.0, 0.0),
+        unit: new_text("Unit allometry", font.clone(), 100.0, 30.0),
+    });
+    update_layout(
+        entity_manager,
+        graphics_context.dimensions(),
+        graphics_context.ui_scale(),
+    );
+    hud
 }
 
 fn new_text(text: &str, font: Font, x: f32, y: f32) -> TextRenderer {
     TextRenderer::new(Transform::pos(Vec3::new(x, y, 0.)), text, font)
 }
 
-fn create_thruster(resource_manager: &mut SceneResourceManager) -> ParticleEmitter {
+fn create_thruster(
+    resource_manager: &mut SceneResourceManager,
+    rng: RandomStream,
+) -> ParticleEmitter {
     let thruster_material = resource_manager.get("explosion").res;
     let (vertices, indices) = generator::quad(1.0, 1.0);
     let instanced_mesh = InstancedMesh::new(&vertices, &indices, &vec![]);
@@ -191,7 +388,7 @@ fn create_thruster(resource_manager: &mut SceneResourceManager) -> ParticleEmitt
         emitter_definition,
         thruster_material,
         instanced_mesh,
-        Box::new(thruster_spawner),
+        Box::new(thruster_spawner(rng)),
     )
 }
 
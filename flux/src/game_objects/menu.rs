@@ -1,48 +1,81 @@
 use atlas::{
     components::{
-        button_handler::ButtonHandler,
+        button_handler::{ButtonHandler, NoHandler},
+        button_trigger::ButtonTrigger,
+        layout::{Direction, Layout, Length, CENTER, TOP},
+        sprite_renderer::SpriteRenderer,
+        text_renderer::TextRenderer,
+        transform::Transform,
+    },
+    entity_manager::EntityManager,
+    event_bus::EventSender,
+    game_entities::{
+        sprite::Sprite,
+        ui_label::UiLabel,
+        ui_layout::UiLayout,
+        widget::{Widget, WidgetStyle},
     },
-    entity_manager::{EntityManager},
     game_root::GameError,
-    graphics::material::sprite_material::SpriteMaterial,
     resource_manager::{
-        resource::Resource, scene_resource_manager::SceneResourceManager, ResourceManager,
+        font::Font, scene_resource_manager::SceneResourceManager, ResourceManager,
     },
-    scene::SceneEvent,
+    scene::{SceneEvent, Summary},
 };
-use glam::Vec3;
 
 pub fn create_main_menu(
     component_manager: &mut EntityManager,
     resource_manager: &mut SceneResourceManager,
-    (width, height): (i32, i32),
+    summary: Option<Summary>,
 ) -> Result<(), GameError> {
-    let mut buttons = ["play", "options", "exit"];
-
-    let button_gap = 128.0;
-    let vertical_offset = button_gap * 0.5 * (buttons.len() - 1) as f32;
-    let center = (width as f32 / 2.0, height as f32 / 2.0 - vertical_offset);
-
-    buttons
-        .iter_mut()
-        .enumerate()
-        .for_each(|(index, button_name)| {
-            let material = resource_manager.get(button_name);
-            add_menu_entry(
-                Vec3::new(center.0, center.1 + index as f32 * button_gap, 1.0),
-                component_manager,
-                &material,
-            );
-        });
+    let mut ui = UiLayout::new();
+
+    let main_screen = resource_manager.get("background").res;
+    component_manager.add(Sprite {
+        renderer: SpriteRenderer::quad((1.0, 1.0), main_screen),
+        node: Some(ui.add(None, Layout::full())),
+    });
+
+    // A button without a handler stays greyed out
+    let buttons: [(&str, Option<Box<dyn ButtonHandler>>); 3] = [
+        ("play", Some(Box::new(PlayHandler::new()))),
+        ("options", Some(Box::new(OptionsHandler::new()))),
+        ("exit", Some(Box::new(ExitHandler::new()))),
+    ];
+
+    let (button_size, button_gap) = ((256.0, 64.0), 64.0);
+    let count = buttons.len() as f32;
+    let stack_height = button_size.1 * count + button_gap * (count - 1.0);
+    let stack = ui.add(
+        None,
+        Layout::stack(
+            CENTER,
+            (Length::Px(button_size.0), Length::Px(stack_height)),
+            Direction::Vertical,
+            button_gap,
+        ),
+    );
+    let font: Font = resource_manager.get("main").res;
 
-    //let main_screen = resource_manager.get("main_menu");
-    /*component_manager.add_entity((
-        Transform::pos(Vec3::new(width as f32 * 0.5, height as f32 * 0.5, 0.0)),
-        SpriteRenderer::quad((width as f32 * 0.5, height as f32 * 0.5), main_screen.res),
-        ButtonTrigger::new(0, (0.0, 0.0), (width as f32, height as f32)),
-        Box::new(BackgroundHandler::new()) as Box<dyn ButtonHandler>,
-    ));*/
+    buttons.into_iter().for_each(|(button_name, handler)| {
+        let style = WidgetStyle {
+            font: font.clone(),
+            material: resource_manager.get(button_name).res,
+        };
+        let node = ui.add(Some(stack), Layout::anchored(TOP, (0.0, 0.0), button_size));
+        add_menu_entry(button_name, node, handler, component_manager, &style);
+    });
 
+    // After a game over the menu shows how the last game went above the buttons
+    if let Some(summary) = summary {
+        let node = ui.add(None, Layout::anchored(TOP, (0.0, 64.0), (1280.0, 48.0)));
+        let text = format!("Game over: {}", summary);
+        component_manager.add(UiLabel {
+            renderer: TextRenderer::new(Transform::new(), &text, font),
+            node: Some(node),
+        });
+    }
+
+    component_manager.add(ui);
     Ok(())
 }
 
@@ -55,34 +88,52 @@ impl PlayHandler {
 }
 
 impl ButtonHandler for PlayHandler {
-    fn on_click(&self, event_sender: &mut atlas::event_bus::EventSender) {
-        event_sender.write(SceneEvent::NewScene("first_scene"))
+    fn on_click(&self, event_sender: &mut EventSender) {
+        event_sender.write(SceneEvent::NewScene(String::from("first_scene")))
     }
 }
 
-struct BackgroundHandler;
+struct OptionsHandler;
 
-impl BackgroundHandler {
+impl OptionsHandler {
     pub fn new() -> Self {
-        BackgroundHandler {}
+        OptionsHandler {}
     }
 }
 
-impl ButtonHandler for BackgroundHandler {
-    fn on_click(&self, _: &mut atlas::event_bus::EventSender) {
-        println!("Background");
+impl ButtonHandler for OptionsHandler {
+    fn on_click(&self, event_sender: &mut EventSender) {
+        event_sender.write(SceneEvent::NewScene(String::from("options")))
     }
 }
 
+struct ExitHandler;
+
+impl ExitHandler {
+    pub fn new() -> Self {
+        ExitHandler {}
+    }
+}
+
+impl ButtonHandler for ExitHandler {
+    fn on_click(&self, event_sender: &mut EventSender) {
+        event_sender.write(SceneEvent::Exit)
+    }
+}
+
+// The layout moves the button into place, the trigger starts out empty
 fn add_menu_entry(
-    _pos: Vec3,
-    _component_manager: &mut EntityManager,
-    _material: &Resource<SpriteMaterial>,
+    name: &str,
+    node: usize,
+    handler: Option<Box<dyn ButtonHandler>>,
+    component_manager: &mut EntityManager,
+    style: &WidgetStyle,
 ) {
-    /*component_manager.add_entity((
-        Transform::pos(pos),
-        SpriteRenderer::quad((128.0, 32.0), material.res.clone()),
-        ButtonTrigger::new(1, (pos.x, pos.y), (128.0, 32.0)),
-        Box::new(PlayHandler::new()) as Box<dyn ButtonHandler>,
-    ));*/
+    let enabled = handler.is_some();
+    let handler = handler.unwrap_or(Box::new(NoHandler));
+    let trigger = ButtonTrigger::new(1, (0.0, 0.0), (0.0, 0.0));
+    let mut button = Widget::button(name, "", handler, trigger, style);
+    button.node = Some(node);
+    button.set_enabled(enabled);
+    component_manager.add(button);
 }
@@ -0,0 +1,322 @@
+use atlas::{
+    components::{
+        button_handler::NoHandler,
+        button_trigger::{ButtonTrigger, UiEvent},
+        layout::{Direction, Layout, Length, CENTER, TOP},
+        sprite_renderer::SpriteRenderer,
+    },
+    entity_manager::EntityManager,
+    event_bus::EventReader,
+    game_entities::{
+        sprite::Sprite,
+        widget::{Control, Widget, WidgetStyle, ROW_HEIGHT},
+    },
+    game_root::GameError,
+    graphics::{
+        graphics_context::{GraphicsContext, IoEvent},
+        material::sprite_material::SpriteMaterial,
+        texture::Texture,
+    },
+    input::{
+        input_map::{Binding, Input, InputMap},
+        key::Key,
+    },
+    resource_manager::{
+        font::Font, scene_resource_manager::SceneResourceManager, ResourceManager,
+    },
+    settings::{Settings, QUALITIES},
+};
+
+use super::ui_page::UiPage;
+
+pub const INPUT_MAP: &str = "flux.input";
+pub const FOV_RANGE: (f32, f32) = (40.0, 110.0);
+pub const MOUSE_SPEED_RANGE: (f32, f32) = (0.001, 0.05);
+// Widgets are a shade lighter than the panel behind them
+pub const PANEL_COLOR: (f32, f32, f32) = (0.08, 0.09, 0.13);
+pub const WIDGET_COLOR: (f32, f32, f32) = (0.16, 0.19, 0.27);
+// Rows of the binding list shown at once, the rest scrolls
+const BINDING_ROWS: usize = 6;
+const ENTRY_SIZE: (f32, f32) = (720.0, 48.0);
+const ENTRY_GAP: f32 = 8.0;
+const PANEL_PADDING: f32 = 24.0;
+
+// The options panel, shared by the options scene and the pause overlay
+pub struct OptionsMenu {
+    settings: Settings,
+    resolutions: Vec<(u32, u32)>,
+    input: InputMap,
+    // Binding waiting for the next key, button or stick push
+    listening: Option<usize>,
+}
+
+impl OptionsMenu {
+    pub fn new(
+        settings: &Settings,
+        graphics_context: &mut GraphicsContext,
+    ) -> Result<Self, GameError> {
+        // The saved resolution stays on the list even if the monitor does not report it
+        let mut resolutions = graphics_context.resolutions();
+        if !resolutions.contains(&settings.resolution) {
+            resolutions.insert(0, settings.resolution);
+        }
+        Ok(OptionsMenu {
+            settings: settings.clone(),
+            resolutions,
+            input: InputMap::load(INPUT_MAP)?,
+            listening: None,
+        })
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    pub fn is_listening(&self) -> bool {
+        self.listening.is_some()
+    }
+
+    // Puts the panel in the middle of the scene's UI layout
+    pub fn create(
+        &self,
+        entity_manager: &mut EntityManager,
+        resource_manager: &mut SceneResourceManager,
+    ) -> UiPage {
+        let mut page = UiPage::new(entity_manager);
+        let font: Font = resource_manager.get("main").res;
+        let style = WidgetStyle {
+            font,
+            material: SpriteMaterial {
+                tex: Texture::from_color(WIDGET_COLOR),
+            },
+        };
+        let panel = SpriteMaterial {
+            tex: Texture::from_color(PANEL_COLOR),
+        };
+        let widgets = self.widgets(&style);
+
+        // The binding list is taller than the other entries
+        let list_height = BINDING_ROWS as f32 * ROW_HEIGHT;
+        let count = widgets.len() as f32;
+        let stack_size = (
+            ENTRY_SIZE.0 + PANEL_PADDING * 2.0,
+            ENTRY_SIZE.1 * (count - 1.0)
+                + list_height
+                + ENTRY_GAP * (count - 1.0)
+                + PANEL_PADDING * 2.0,
+        );
+        let stack = page.node(
+            entity_manager,
+            None,
+            Layout {
+                padding: PANEL_PADDING,
+                ..Layout::stack(
+                    CENTER,
+                    (Length::Px(stack_size.0), Length::Px(stack_size.1)),
+                    Direction::Vertical,
+                    ENTRY_GAP,
+                )
+            },
+        );
+        page.add_sprite(
+            entity_manager,
+            Sprite {
+                renderer: SpriteRenderer::quad((1.0, 1.0), panel),
+                node: Some(stack),
+            },
+        );
+
+        widgets.into_iter().enumerate().for_each(|(tab_order, mut widget)| {
+            let height = match widget.control {
+                Control::List { .. } => list_height,
+                _ => ENTRY_SIZE.1,
+            };
+            widget.node = Some(page.node(
+                entity_manager,
+                Some(stack),
+                Layout::anchored(TOP, (0.0, 0.0), (ENTRY_SIZE.0, height)),
+            ));
+            widget.tab_order = tab_order;
+            page.add_widget(entity_manager, widget);
+        });
+        page
+    }
+
+    fn widgets(&self, style: &WidgetStyle) -> Vec<Widget> {
+        let settings = &self.settings;
+        let trigger = || ButtonTrigger::new(1, (0.0, 0.0), (0.0, 0.0));
+
+        let resolution_names: Vec<String> = self
+            .resolutions
+            .iter()
+            .map(|(width, height)| format!("{}x{}", width, height))
+            .collect();
+        let resolution_names: Vec<&str> = resolution_names.iter().map(String::as_str).collect();
+        let resolution = self
+            .resolutions
+            .iter()
+            .position(|resolution| *resolution == settings.resolution)
+            .unwrap_or(0);
+        let quality_names: Vec<&str> = QUALITIES.iter().map(|quality| quality.name()).collect();
+        let quality = QUALITIES
+            .iter()
+            .position(|quality| *quality == settings.quality)
+            .unwrap_or(0);
+        let bindings: Vec<String> = self.input.bindings().iter().map(binding_label).collect();
+        let bindings: Vec<&str> = bindings.iter().map(String::as_str).collect();
+
+        vec![
+            Widget::dropdown(
+                "resolution",
+                "Resolution",
+                (&resolution_names, resolution),
+                trigger(),
+                style,
+            ),
+            Widget::checkbox("fullscreen", "Fullscreen", settings.fullscreen, trigger(), style),
+            Widget::checkbox("vsync", "Vertical sync", settings.vsync, trigger(), style),
+            Widget::dropdown(
+                "quality",
+                "Quality (after restart)",
+                (&quality_names, quality),
+                trigger(),
+                style,
+            ),
+            Widget::slider(
+                "fov",
+                "Field of view",
+                (settings.fov, FOV_RANGE.0, FOV_RANGE.1),
+                trigger(),
+                style,
+            ),
+            Widget::slider(
+                "mouse_speed",
+                "Mouse speed",
+                (settings.mouse_speed, MOUSE_SPEED_RANGE.0, MOUSE_SPEED_RANGE.1),
+                trigger(),
+                style,
+            ),
+            Widget::slider(
+                "master_volume",
+                "Master volume",
+                (settings.master_volume, 0.0, 1.0),
+                trigger(),
+                style,
+            ),
+            Widget::slider(
+                "music_volume",
+                "Music volume",
+                (settings.music_volume, 0.0, 1.0),
+                trigger(),
+                style,
+            ),
+            Widget::slider(
+                "effects_volume",
+                "Effects volume",
+                (settings.effects_volume, 0.0, 1.0),
+                trigger(),
+                style,
+            ),
+            Widget::list("bindings", (&bindings, BINDING_ROWS), trigger(), style),
+            Widget::button("back", "Back", Box::new(NoHandler), trigger(), style),
+        ]
+    }
+
+    // Video settings take effect right away, the rest waits for whoever reads the settings next.
+    // Events that are not about an option, like the back button, are left to the caller
+    pub fn handle(
+        &mut self,
+        event: &UiEvent,
+        entity_manager: &mut EntityManager,
+        graphics_context: &mut GraphicsContext,
+    ) -> bool {
+        let settings = &mut self.settings;
+        match event {
+            UiEvent::Selected(name, index) if name == "resolution" => {
+                let Some(resolution) = self.resolutions.get(*index) else {return true};
+                settings.resolution = *resolution;
+                graphics_context.apply_settings(settings);
+            }
+            UiEvent::Selected(name, index) if name == "quality" => {
+                let Some(quality) = QUALITIES.get(*index) else {return true};
+                settings.quality = *quality;
+            }
+            UiEvent::Toggled(name, checked) => {
+                match name.as_str() {
+                    "fullscreen" => settings.fullscreen = *checked,
+                    "vsync" => settings.vsync = *checked,
+                    _ => return false,
+                }
+                graphics_context.apply_settings(settings);
+            }
+            UiEvent::Changed(name, value) => match name.as_str() {
+                "fov" => settings.fov = *value,
+                "mouse_speed" => settings.mouse_speed = *value,
+                "master_volume" => settings.master_volume = *value,
+                "music_volume" => settings.music_volume = *value,
+                "effects_volume" => settings.effects_volume = *value,
+                _ => return false,
+            },
+            UiEvent::Activated(name, index) if name == "bindings" => {
+                self.listening = Some(*index);
+                set_binding_row(entity_manager, *index, "press a key, Escape cancels");
+            }
+            UiEvent::Selected(name, _) if name == "bindings" => {}
+            _ => return false,
+        }
+        true
+    }
+
+    // Sliders change on every frame of a drag, so the file is written once the panel closes
+    pub fn save(&self) {
+        if let Err(e) = self.settings.save() {
+            println!("Failed to save settings: {}", e);
+        }
+    }
+
+    // Takes the input events while a binding waits for its new input
+    pub fn listen(&mut self, entity_manager: &mut EntityManager, event_reader: &mut EventReader) {
+        let Some(binding) = self.listening else {return};
+        let mut pressed = None;
+        event_reader.read(|event: IoEvent| {
+            if pressed.is_some() {
+                return;
+            }
+            pressed = match event {
+                IoEvent::KeyPressed(Key::Escape) => Some(None),
+                event => Input::pressed_by(&event).map(Some),
+            };
+        });
+        let Some(input) = pressed else {return};
+
+        self.listening = None;
+        if let Some(input) = input {
+            self.input.rebind(binding, input);
+            if let Err(e) = self.input.save() {
+                println!("Failed to save bindings: {}", e);
+            }
+        }
+        let label = self.input.bindings().get(binding).map(binding_label);
+        set_binding_row(entity_manager, binding, &label.unwrap_or_default());
+    }
+}
+
+pub fn binding_label(binding: &Binding) -> String {
+    let modifier = binding
+        .modifier
+        .map_or(String::new(), |modifier| format!(" with {}", modifier.name()));
+    format!("{}: {}{}", binding.action, binding.input.name(), modifier)
+}
+
+fn set_binding_row(entity_manager: &mut EntityManager, binding: usize, label: &str) {
+    let list = entity_manager
+        .iter_mut::<Widget>()
+        .find(|widget| widget.entity.name == "bindings");
+    let Some(list) = list else {return};
+    if let Control::List { items, .. } = &mut list.entity.control {
+        if let Some(item) = items.get_mut(binding) {
+            *item = String::from(label);
+        }
+    }
+    list.entity.refresh();
+}
@@ -0,0 +1,262 @@
+use atlas::{
+    components::{
+        button_handler::NoHandler,
+        button_trigger::{ButtonTrigger, ButtonTriggerSystem, UiEvent},
+        layout::{Direction, Layout, Length, CENTER, TOP},
+        sprite_renderer::SpriteRenderer,
+        text_renderer::TextRenderer,
+        transform::Transform,
+    },
+    entity_manager::EntityManager,
+    event_bus::{EventReader, EventSender},
+    game_entities::{
+        sprite::Sprite,
+        ui_label::UiLabel,
+        widget::{Widget, WidgetStyle},
+    },
+    game_root::GameError,
+    graphics::{
+        graphics_context::GraphicsContext, material::sprite_material::SpriteMaterial,
+        texture::Texture,
+    },
+    resource_manager::{
+        font::Font, scene_resource_manager::SceneResourceManager, ResourceManager,
+    },
+    scene::SceneEvent,
+    settings::Settings,
+    systems::layout_system::apply_layout,
+};
+
+use super::{
+    options::{OptionsMenu, PANEL_COLOR, WIDGET_COLOR},
+    ui_page::UiPage,
+};
+
+const BUTTON_SIZE: (f32, f32) = (320.0, 56.0);
+const TITLE_HEIGHT: f32 = 48.0;
+const BUTTON_GAP: f32 = 12.0;
+const PANEL_PADDING: f32 = 24.0;
+
+const MAIN_BUTTONS: [(&str, &str); 4] = [
+    ("resume", "Resume"),
+    ("restart", "Restart"),
+    ("options", "Options"),
+    ("quit", "Quit to menu"),
+];
+const CONFIRM_BUTTONS: [(&str, &str); 2] = [("no", "Stay"), ("yes", "Quit")];
+
+// Read by the simulation once the game goes on, with the settings changed in the meantime
+pub enum PauseEvent {
+    Resumed(Settings),
+}
+
+pub enum PauseAction {
+    Resume,
+    Scene(SceneEvent),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum PausePage {
+    Main,
+    Options,
+    ConfirmQuit,
+}
+
+// Menu over a frozen game, the scene stops its fixed step while this exists
+pub struct PauseMenu {
+    page: PausePage,
+    ui: Option<UiPage>,
+    button_system: ButtonTriggerSystem,
+    options: OptionsMenu,
+}
+
+impl PauseMenu {
+    pub fn new(
+        settings: &Settings,
+        entity_manager: &mut EntityManager,
+        resource_manager: &mut SceneResourceManager,
+        graphics_context: &mut GraphicsContext,
+    ) -> Result<Self, GameError> {
+        let mut pause_menu = PauseMenu {
+            page: PausePage::Main,
+            ui: None,
+            button_system: ButtonTriggerSystem::new(),
+            options: OptionsMenu::new(settings, graphics_context)?,
+        };
+        pause_menu.open(PausePage::Main, entity_manager, resource_manager);
+        Ok(pause_menu)
+    }
+
+    pub fn settings(&self) -> &Settings {
+        self.options.settings()
+    }
+
+    pub fn update(
+        &mut self,
+        entity_manager: &mut EntityManager,
+        resource_manager: &mut SceneResourceManager,
+        event_reader: &mut EventReader,
+        event_sender: &mut EventSender,
+        graphics_context: &mut GraphicsContext,
+    ) -> Option<PauseAction> {
+        match self.options.is_listening() {
+            true => self.options.listen(entity_manager, event_reader),
+            false => self.button_system.check_buttons(
+                entity_manager,
+                event_reader,
+                event_sender,
+                graphics_context,
+            ),
+        }
+
+        let (mut next, mut action) = (None, None);
+        let (page, options) = (self.page, &mut self.options);
+        event_reader.read(|event: UiEvent| match page {
+            PausePage::Main => match event {
+                UiEvent::Pressed(name) => match name.as_str() {
+                    "resume" => action = Some(PauseAction::Resume),
+                    "restart" => action = Some(PauseAction::Scene(SceneEvent::RestartScene)),
+                    "options" => next = Some(PausePage::Options),
+                    "quit" => next = Some(PausePage::ConfirmQuit),
+                    _ => {}
+                },
+                UiEvent::Back => action = Some(PauseAction::Resume),
+                _ => {}
+            },
+            PausePage::Options => {
+                if options.handle(&event, entity_manager, graphics_context) {
+                    return;
+                }
+                match event {
+                    UiEvent::Pressed(name) if name == "back" => next = Some(PausePage::Main),
+                    UiEvent::Back => next = Some(PausePage::Main),
+                    _ => {}
+                }
+            }
+            PausePage::ConfirmQuit => match event {
+                UiEvent::Pressed(name) if name == "yes" => {
+                    let scene = SceneEvent::NewScene(String::from("main"));
+                    action = Some(PauseAction::Scene(scene))
+                }
+                UiEvent::Pressed(name) if name == "no" => next = Some(PausePage::Main),
+                UiEvent::Back => next = Some(PausePage::Main),
+                _ => {}
+            },
+        });
+
+        if let Some(next) = next {
+            if self.page == PausePage::Options {
+                self.options.save();
+            }
+            self.open(next, entity_manager, resource_manager);
+        }
+        action
+    }
+
+    pub fn close(mut self, entity_manager: &mut EntityManager) {
+        if let Some(ui) = self.ui.take() {
+            ui.close(entity_manager);
+        }
+    }
+
+    fn open(
+        &mut self,
+        page: PausePage,
+        entity_manager: &mut EntityManager,
+        resource_manager: &mut SceneResourceManager,
+    ) {
+        if let Some(ui) = self.ui.take() {
+            ui.close(entity_manager);
+        }
+        let ui = match page {
+            PausePage::Main => menu_page("Paused", &MAIN_BUTTONS, entity_manager, resource_manager),
+            PausePage::Options => self.options.create(entity_manager, resource_manager),
+            PausePage::ConfirmQuit => menu_page(
+                "Quit to the main menu?",
+                &CONFIRM_BUTTONS,
+                entity_manager,
+                resource_manager,
+            ),
+        };
+        apply_layout(entity_manager);
+
+        self.page = page;
+        self.ui = Some(ui);
+        // Focus and hover belonged to the widgets that are gone
+        self.button_system = ButtonTriggerSystem::new();
+    }
+}
+
+// A title over a column of buttons on a panel in the middle of the screen
+fn menu_page(
+    title: &str,
+    buttons: &[(&str, &str)],
+    entity_manager: &mut EntityManager,
+    resource_manager: &mut SceneResourceManager,
+) -> UiPage {
+    let mut page = UiPage::new(entity_manager);
+    let font: Font = resource_manager.get("main").res;
+    let style = WidgetStyle {
+        font: font.clone(),
+        material: SpriteMaterial {
+            tex: Texture::from_color(WIDGET_COLOR),
+        },
+    };
+
+    let count = buttons.len() as f32;
+    let panel_size = (
+        BUTTON_SIZE.0 + PANEL_PADDING * 2.0,
+        TITLE_HEIGHT + (BUTTON_SIZE.1 + BUTTON_GAP) * count + PANEL_PADDING * 2.0,
+    );
+    let panel = page.node(
+        entity_manager,
+        None,
+        Layout {
+            padding: PANEL_PADDING,
+            ..Layout::stack(
+                CENTER,
+                (Length::Px(panel_size.0), Length::Px(panel_size.1)),
+                Direction::Vertical,
+                BUTTON_GAP,
+            )
+        },
+    );
+    page.add_sprite(
+        entity_manager,
+        Sprite {
+            renderer: SpriteRenderer::quad(
+                (1.0, 1.0),
+                SpriteMaterial {
+                    tex: Texture::from_color(PANEL_COLOR),
+                },
+            ),
+            node: Some(panel),
+        },
+    );
+
+    let node = page.node(
+        entity_manager,
+        Some(panel),
+        Layout::anchored(TOP, (0.0, 0.0), (BUTTON_SIZE.0, TITLE_HEIGHT)),
+    );
+    page.add_label(
+        entity_manager,
+        UiLabel {
+            renderer: TextRenderer::new(Transform::new(), title, font),
+            node: Some(node),
+        },
+    );
+
+    buttons.iter().enumerate().for_each(|(tab_order, (name, caption))| {
+        let trigger = ButtonTrigger::new(1, (0.0, 0.0), (0.0, 0.0));
+        let mut button = Widget::button(name, caption, Box::new(NoHandler), trigger, &style);
+        button.node = Some(page.node(
+            entity_manager,
+            Some(panel),
+            Layout::anchored(TOP, (0.0, 0.0), BUTTON_SIZE),
+        ));
+        button.tab_order = tab_order;
+        page.add_widget(entity_manager, button);
+    });
+    page
+}
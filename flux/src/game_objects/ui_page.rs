@@ -0,0 +1,74 @@
+use atlas::{
+    components::layout::Layout,
+    entity_manager::EntityManager,
+    game_entities::{sprite::Sprite, ui_label::UiLabel, ui_layout::UiLayout, widget::Widget},
+};
+
+// One screen of a menu added on top of the scene's UI, closing it takes away
+// its entities and the layout nodes added after it was opened
+pub struct UiPage {
+    first_node: usize,
+    widgets: Vec<usize>,
+    sprites: Vec<usize>,
+    labels: Vec<usize>,
+}
+
+impl UiPage {
+    pub fn new(entity_manager: &EntityManager) -> Self {
+        let first_node = entity_manager
+            .iter::<UiLayout>()
+            .next()
+            .map_or(0, |ui| ui.entity.node_count());
+        UiPage {
+            first_node,
+            widgets: vec![],
+            sprites: vec![],
+            labels: vec![],
+        }
+    }
+
+    pub fn node(
+        &self,
+        entity_manager: &mut EntityManager,
+        parent: Option<usize>,
+        layout: Layout,
+    ) -> usize {
+        entity_manager
+            .iter_mut::<UiLayout>()
+            .next()
+            .map_or(0, |ui| ui.entity.add(parent, layout))
+    }
+
+    pub fn add_widget(&mut self, entity_manager: &mut EntityManager, widget: Widget) -> usize {
+        let id = entity_manager.add(widget);
+        self.widgets.push(id);
+        id
+    }
+
+    pub fn add_sprite(&mut self, entity_manager: &mut EntityManager, sprite: Sprite) -> usize {
+        let id = entity_manager.add(sprite);
+        self.sprites.push(id);
+        id
+    }
+
+    pub fn add_label(&mut self, entity_manager: &mut EntityManager, label: UiLabel) -> usize {
+        let id = entity_manager.add(label);
+        self.labels.push(id);
+        id
+    }
+
+    pub fn close(self, entity_manager: &mut EntityManager) {
+        self.widgets
+            .iter()
+            .for_each(|id| entity_manager.remove::<Widget>(*id));
+        self.sprites
+            .iter()
+            .for_each(|id| entity_manager.remove::<Sprite>(*id));
+        self.labels
+            .iter()
+            .for_each(|id| entity_manager.remove::<UiLabel>(*id));
+        if let Some(ui) = entity_manager.iter_mut::<UiLayout>().next() {
+            ui.entity.truncate(self.first_node);
+        }
+    }
+}
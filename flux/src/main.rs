@@ -1,8 +1,11 @@
+use std::{env, str::FromStr};
+
 use atlas::{
     game_root::{GameError, GameRoot},
+    random::SimulationMode,
     resource_manager::scene_manager::{SceneInitializer, SceneManager},
 };
-use scene::{first_scene::FirstScene, main_menu_scene::MainMenuScene};
+use scene::{first_scene::FirstScene, main_menu_scene::MainMenuScene, options_scene::OptionsScene};
 
 mod game_objects;
 mod scene;
@@ -17,16 +20,39 @@ fn main() {
 }
 
 fn start_game() -> Result<(), GameError> {
-    let mut game_root = GameRoot::new("Flux")?;
+    let args: Vec<String> = env::args().collect();
+    let mode =
+        arg_value(&args, "--seed").map_or(SimulationMode::Realtime, SimulationMode::Deterministic);
+
+    if let Some(ticks) = arg_value(&args, "--headless") {
+        let scene = arg_value(&args, "--scene").unwrap_or(String::from("first_scene"));
+        return run_headless(&scene, ticks, mode);
+    }
+
+    let mut game_root = GameRoot::new("Flux", mode)?;
     let scene_manager = game_root.scene_manager();
     load_scenes(scene_manager)?;
     game_root.run();
     Ok(())
 }
 
+fn run_headless(scene: &str, ticks: u64, mode: SimulationMode) -> Result<(), GameError> {
+    let mut game_root = GameRoot::headless("Flux", mode)?;
+    load_scenes(game_root.scene_manager())?;
+    print!("{}", game_root.run_headless(scene, ticks)?);
+    Ok(())
+}
+
+fn arg_value<T: FromStr>(args: &[String], flag: &str) -> Option<T> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|i| args.get(i + 1)?.parse().ok())
+}
+
 fn load_scenes(root_resource_manager: &mut SceneManager) -> Result<(), GameError> {
-    let scenes: [(_, SceneInitializer); 2] = [
+    let scenes: [(_, SceneInitializer); 3] = [
         ("main", MainMenuScene::new),
+        ("options", OptionsScene::new),
         ("first_scene", FirstScene::new),
     ];
 
@@ -53,3 +79,47 @@ fn load_scenes(root_resource_manager: &mut SceneManager) -> Result<(), GameError
         )))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    const GOLDEN_TICKS: u64 = 600;
+    const GOLDEN_SEED: u64 = 7;
+
+    // The world recorded after GOLDEN_TICKS of GOLDEN_SEED. A change that is meant to move the
+    // simulation records it again with `FLUX_BLESS=1 cargo test -p flux` and commits the diff
+    const GOLDEN_PATH: &str =
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/first_scene.dump");
+    const GOLDEN_DUMP: &str = include_str!("../tests/fixtures/first_scene.dump");
+
+    fn simulate(seed: u64) -> String {
+        let mut game_root = GameRoot::headless("Flux", SimulationMode::Deterministic(seed))
+            .expect("Headless game root starts");
+        load_scenes(game_root.scene_manager()).expect("Scenes register");
+        game_root
+            .run_headless("first_scene", GOLDEN_TICKS)
+            .expect("Scene simulates")
+    }
+
+    // The same seed has to give the same world, down to the last bit of every dumped value
+    #[test]
+    fn deterministic_runs_are_identical() {
+        let (first, second) = (simulate(GOLDEN_SEED), simulate(GOLDEN_SEED));
+        assert!(!first.is_empty());
+        assert_eq!(first, second);
+    }
+
+    // Catches drift that both runs would share, like a changed system order or integration step
+    #[test]
+    fn simulation_matches_recorded_world() {
+        let dump = simulate(GOLDEN_SEED);
+        if env::var_os("FLUX_BLESS").is_some() {
+            fs::write(GOLDEN_PATH, &dump).expect("Fixture is written");
+            return;
+        }
+        assert_eq!(dump, GOLDEN_DUMP);
+    }
+}
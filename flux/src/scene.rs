@@ -1,2 +1,3 @@
 pub mod first_scene;
 pub mod main_menu_scene;
+pub mod options_scene;
@@ -3,6 +3,7 @@ use std::time::Instant;
 use atlas::{
     components::{
         camera::{Camera, Frustrum},
+        layout::{Layout, TOP_LEFT},
         mesh_renderer::MeshRendererSystem,
         particle_renderer::ParticleRenderer,
         skybox_renderer::SkyboxRendererSystem,
@@ -13,37 +14,64 @@ use atlas::{
     entity_manager::EntityManager,
     event_bus::{create_event_queue, EventReader, EventSender},
     game_entities::{
-        bullet::BulletEntity, hud::update_hud, player_ship::PlayerShip, ui_label::UiLabel,
-        GameEntity,
+        bullet::BulletEntity,
+        hud::{update_hud, HudEntity}, player_ship::PlayerShip, ui_label::UiLabel,
+        ui_layout::UiLayout, GameEntity,
     },
+    game_mode::high_scores::HighScores,
     game_root::GameError,
-    graphics::graphics_context::{ContextEvent, GraphicsContext},
+    graphics::graphics_context::{ContextEvent, GraphicsContext, IoEvent},
+    input::{
+        input_map::InputMap,
+        key::{GamepadButton, Key},
+    },
+    random::Random,
     resource_manager::{font::Font, scene_resource_manager::SceneResourceManager, ResourceManager},
-    scene::{Scene, SceneEvent},
+    scene::{Scene, SceneEvent, Summary},
+    settings::Settings,
     systems::{
-        asteroid_detonator::detonate_asteroids,
-        bullet_detonator::process_bullet_events,
+        asteroid_detonator::detonate_units,
+        bullet_detonator::{process_bullet_events, update_units},
         bullet_renderer::BulletRenderer,
         bullet_system::update_bullets,
         collider_renderer::CollisionRenderer,
         collision_system::CollisionSystem,
+        decoy_system::update_decoys,
         health_renderer::HealthRendererSystem,
+        layout_system::{apply_layout, update_layout},
+        loot_system::{drop_loot, update_pickups},
+        missile_system::guide_missiles,
         particle_system::update_particles,
         physical_simulation::PhysicalSimulation,
+        power_system::update_ship_systems,
         player_controller::{GameEvent, PlayerController},
+        game_mode_system::{track_accuracy, update_game_mode, WaveEvent},
+        mission_system::{update_missions, PrefabEvent},
+        respawn_system::{update_lives, PlayerEvent},
+        script_system::ScriptSystem,
+        state_dump::dump_state,
+        behaviour_system::update_behaviours,
+        swarm_system::update_swarms,
         text_update::{update_text, TextChangeEvent},
-        trail_renderer::TrailRenderer, player_follower::follow_player,
+        trail_renderer::{TrailEvent, TrailRenderer}, player_follower::follow_player,
+        weapon_system::update_weapons,
     },
 };
-use glam::{Quat, Vec3};
+use crate::game_objects::{
+    asteroids::{asteroids, spawn_prefab, spawn_wave},
+    options::INPUT_MAP,
+    pause::{PauseAction, PauseEvent, PauseMenu},
+};
+
+const HIGH_SCORES: &str = "flux.highscores";
 
-use crate::game_objects::asteroids::asteroids;
+type RenderFn = Box<dyn FnMut(f32, &GraphicsContext, &mut EntityManager, &EventReader)>;
 
 pub struct FirstScene {
     entity_manager: EntityManager,
     resource_manager: SceneResourceManager,
 
-    render_fn: Box<dyn FnMut(f32, &GraphicsContext, &mut EntityManager, &EventReader)>,
+    render_fn: RenderFn,
     physical_fn: Box<
         dyn FnMut(
             f32,
@@ -57,6 +85,10 @@ pub struct FirstScene {
 
     event_reader: EventReader,
     event_sender: EventSender,
+    ticks: u64,
+    settings: Settings,
+    pause: Option<PauseMenu>,
+    headless: bool,
 }
 
 impl Scene for FirstScene {
@@ -64,8 +96,11 @@ impl Scene for FirstScene {
         &mut self,
         graphics_context: &mut atlas::graphics::graphics_context::GraphicsContext,
     ) -> SceneEvent {
-        let fps_counter = self.create_label(Vec3::new(50.0, 50.0, 0.0));
-        let physics_counter = self.create_label(Vec3::new(50.0, 100.0, 0.0));
+        let fps_counter =
+            self.create_label(Layout::anchored(TOP_LEFT, (50.0, 18.0), (300.0, 32.0)));
+        let physics_counter =
+            self.create_label(Layout::anchored(TOP_LEFT, (50.0, 68.0), (300.0, 32.0)));
+        apply_layout(&mut self.entity_manager);
 
         let (mut now, mut prev_phys) = (Instant::now(), Instant::now());
 
@@ -97,7 +132,7 @@ impl Scene for FirstScene {
             ));
 
             (self.render_fn)(
-                time as f32 / 1_000_000_000.0,
+                Self::simulation_time(self.ticks),
                 &graphics_context,
                 &mut self.entity_manager,
                 &self.event_reader,
@@ -107,42 +142,84 @@ impl Scene for FirstScene {
 
             graphics_context.display();
 
+            // Paused time is dropped instead of caught up on afterwards
+            if self.pause.is_some() {
+                physics_delta = 0;
+            }
             while physics_delta > PHYSICS_DELTA {
                 physics_delta -= PHYSICS_DELTA;
                 physics_fps = physics_fps * 0.9
                     + 0.1 * (1_000_000_000.0 / prev_phys.elapsed().as_nanos() as f32);
 
-                (self.physical_fn)(
-                    time as f32 / 1_000_000_000.0,
-                    1.0 / 120.0,
-                    &mut self.entity_manager,
-                    &mut self.resource_manager,
-                    &mut self.event_reader,
-                    &mut self.event_sender,
-                );
+                self.fixed_update();
             }
 
             self.poll_events(graphics_context);
 
+            if let Some(result) = self.update_pause(graphics_context) {
+                return result;
+            }
             if let Some(result) = self.process_events(graphics_context) {
                 return result;
             }
         }
     }
+
+    fn fixed_update(&mut self) {
+        (self.physical_fn)(
+            Self::simulation_time(self.ticks),
+            PHYSICS_STEP,
+            &mut self.entity_manager,
+            &mut self.resource_manager,
+            &mut self.event_reader,
+            &mut self.event_sender,
+        );
+        self.process_game_events();
+        if self.headless {
+            self.drop_frame_events();
+        }
+        self.ticks += 1;
+    }
+
+    fn world_state(&self) -> String {
+        dump_state(&self.entity_manager)
+    }
 }
 
+const PHYSICS_STEP: f32 = 1.0 / 120.0;
+
 impl FirstScene {
-    pub fn new(graphics_context: &mut GraphicsContext) -> Result<Box<dyn Scene>, GameError> {
+    pub fn new(
+        graphics_context: &mut GraphicsContext,
+        random: &Random,
+        settings: &Settings,
+        _summary: Option<Summary>,
+    ) -> Result<Box<dyn Scene>, GameError> {
         let mut entity_manager = EntityManager::new();
         let mut resource_manager = SceneResourceManager::build("first")?;
 
-        let render_fn = Box::new(Self::create_renderer(
+        // Headless runs only step the simulation and have no shaders to build renderers from
+        let render_fn: RenderFn = match graphics_context.is_headless() {
+            true => Box::new(|_, _, _, _| {}),
+            false => Box::new(Self::create_renderer(
+                &mut resource_manager,
+                graphics_context,
+            )),
+        };
+        // The player's bindings stay out of headless runs like the rest of their settings
+        let input = match graphics_context.is_headless() {
+            true => InputMap::default(),
+            false => InputMap::load(INPUT_MAP)?,
+        };
+        let physical_fn = Box::new(Self::create_physics(random, input, settings.mouse_speed));
+
+        asteroids(
+            &mut entity_manager,
             &mut resource_manager,
             graphics_context,
-        ));
-        let physical_fn = Box::new(Self::create_physics());
-
-        asteroids(&mut entity_manager, &mut resource_manager, graphics_context)?;
+            random,
+            settings,
+        )?;
 
         graphics_context.cursor_lock(true);
 
@@ -155,10 +232,22 @@ impl FirstScene {
             event_reader,
             render_fn,
             physical_fn,
+            ticks: 0,
+            settings: settings.clone(),
+            pause: None,
+            headless: graphics_context.is_headless(),
         }))
     }
 
-    fn create_physics() -> impl FnMut(
+    fn simulation_time(ticks: u64) -> f32 {
+        (ticks as f64 * PHYSICS_STEP as f64) as f32
+    }
+
+    fn create_physics(
+        random: &Random,
+        input: InputMap,
+        mouse_speed: f32,
+    ) -> impl FnMut(
         f32,
         f32,
         &mut EntityManager,
@@ -166,8 +255,16 @@ impl FirstScene {
         &mut EventReader,
         &mut EventSender,
     ) {
-        let mut player_controller = PlayerController::new();
-        let mut physical_simulation = PhysicalSimulation::new(1.0 / 120.0);
+        let mut player_controller = PlayerController::new(input);
+        player_controller.mouse_speed = mouse_speed;
+        let mut physical_simulation = PhysicalSimulation::new(PHYSICS_STEP);
+        let mut script_system = ScriptSystem::new(!random.is_deterministic());
+        let explosions = random.stream("explosions");
+        let weapons = random.stream("weapons");
+        let countermeasures = random.stream("countermeasures");
+        let mut wave_spawns = random.stream("waves");
+        let mut mission_spawns = random.stream("missions");
+        let loot = random.stream("loot");
 
         move |time: f32,
               delta: f32,
@@ -177,30 +274,76 @@ impl FirstScene {
               event_sender: &mut EventSender| {
             CollisionSystem::resolve_collisions(time, delta, event_sender, entity_manager);
 
+            update_behaviours(entity_manager);
+            update_swarms(entity_manager, delta);
+            script_system.run(entity_manager, event_reader, event_sender, time, delta);
             physical_simulation.integrate_movement(entity_manager);
 
+            guide_missiles(entity_manager, event_sender, delta);
             update_bullets(entity_manager, event_sender, delta);
             update_hud(entity_manager, event_sender);
 
+            track_accuracy(entity_manager, event_reader);
             process_bullet_events(
                 event_reader,
                 event_sender,
                 resource_manager,
                 entity_manager,
+                &explosions,
+                delta,
+            );
+            update_units(entity_manager, delta);
+            update_lives(
+                entity_manager,
+                resource_manager,
+                event_sender,
+                &explosions,
+                time,
                 delta,
             );
+            update_game_mode(entity_manager, event_reader, event_sender, delta);
+            event_reader.read(|event| match event {
+                WaveEvent::Spawn(_, wave) => {
+                    spawn_wave(entity_manager, resource_manager, &mut wave_spawns, wave)
+                }
+            });
+            update_missions(entity_manager, event_reader, event_sender, delta);
+            event_reader.read(|event| match event {
+                PrefabEvent::Spawn(prefab, position, count) => spawn_prefab(
+                    entity_manager,
+                    resource_manager,
+                    &mut mission_spawns,
+                    &prefab,
+                    position,
+                    count,
+                ),
+            });
 
-            detonate_asteroids(entity_manager, resource_manager, event_reader);
+            drop_loot(entity_manager, resource_manager, event_reader, &loot);
+            detonate_units(entity_manager, resource_manager, event_reader, &explosions);
 
             update_particles(entity_manager, delta);
-
-
-            player_controller.control(
-                physical_simulation.delta(),
+            update_pickups(entity_manager, delta);
+
+            event_reader.read(|event| match event {
+                PauseEvent::Resumed(settings) => {
+                    player_controller.mouse_speed = settings.mouse_speed;
+                    match InputMap::load(INPUT_MAP) {
+                        Ok(input) => player_controller.set_input(input),
+                        Err(e) => println!("Failed to reload bindings: {}", e),
+                    }
+                }
+            });
+            player_controller.control(entity_manager, event_reader);
+            update_ship_systems(entity_manager, delta);
+            update_weapons(
                 entity_manager,
-                event_reader,
+                resource_manager,
                 event_sender,
+                &weapons,
+                delta,
             );
+            update_decoys(entity_manager, resource_manager, &countermeasures, delta);
         }
     }
 
@@ -218,13 +361,8 @@ impl FirstScene {
         let mut trail_renderer = TrailRenderer::new(res_man.get("trail").res);
         let health_renderer = HealthRendererSystem::new(res_man.get("bar").res);
 
-        let (width, height) = graphics_context.dimensions();
-
-        let ui_camera = Camera::new(
-            Frustrum::orthogonal(width as f32, height as f32),
-            Vec3::new(0.0, 0.0, 0.0),
-            Quat::from_axis_angle(Vec3::new(0.0, 0.0, 1.0), 0.0),
-        );
+        let mut ui_dimensions = graphics_context.dimensions();
+        let mut ui_camera = Camera::ui(ui_dimensions);
 
         move |time: f32,
               graphics_context: &GraphicsContext,
@@ -233,18 +371,24 @@ impl FirstScene {
             let mut context = graphics_context.new_context();
             follow_player(entity_manager);
 
+            if graphics_context.dimensions() != ui_dimensions {
+                ui_dimensions = graphics_context.dimensions();
+                ui_camera = Camera::ui(ui_dimensions);
+            }
+
             let camera_kit = entity_manager
                 .iter()
                 .map(|player: &GameEntity<PlayerShip>| {
+                    let detached = player.entity.detached_camera.as_ref();
                     (
-                        &player.transform,
+                        detached.map_or(&player.transform, |detached| &detached.transform),
                         &player.entity.camera,
                         &player.entity.physical_body,
                     )
                 })
                 .next();
 
-            if let Some((camera_transform, camera, _body)) = camera_kit {
+            if let Some((camera_transform, camera, body)) = camera_kit {
                 graphics_context.depth_write(false);
                 skybox_renderer.render(&mut context, entity_manager, camera, camera_transform);
                 graphics_context.depth_write(true);
@@ -268,24 +412,24 @@ impl FirstScene {
                     camera_transform,
                 );
 
-                let player = entity_manager.iter::<PlayerShip>().next().unwrap();
-
                 trail_renderer.render(
                     &mut context,
                     event_reader,
-                    &player.entity.physical_body,
+                    body,
                     &entity_manager,
                     camera,
                     camera_transform,
                 );
 
                 graphics_context.depth_write(true);
-
-                text_renderer.render(&mut context, entity_manager, &ui_camera);
-
-                sprite_renderer.render(&mut context, entity_manager, &ui_camera);
-                health_renderer.render(&mut context, entity_manager, &ui_camera);
             }
+
+            // The UI stays up while the player waits to respawn, text goes over its panels
+            graphics_context.depth_write(false);
+            sprite_renderer.render(&mut context, entity_manager, &ui_camera);
+            health_renderer.render(&mut context, entity_manager, &ui_camera);
+            text_renderer.render(&mut context, entity_manager, &ui_camera);
+            graphics_context.depth_write(true);
         }
     }
 
@@ -293,31 +437,148 @@ impl FirstScene {
         graphics_context.poll_events(&mut self.event_sender);
     }
 
-    fn process_events(&mut self, graphics_context: &mut GraphicsContext) -> Option<SceneEvent> {
+    // Escape or Start freezes the game under the pause menu
+    fn update_pause(&mut self, graphics_context: &mut GraphicsContext) -> Option<SceneEvent> {
+        let Some(pause) = self.pause.as_mut() else {
+            let mut paused = false;
+            self.event_reader.peek(|event: &IoEvent| {
+                paused |= matches!(
+                    event,
+                    IoEvent::KeyPressed(Key::Escape) | IoEvent::GamepadPressed(GamepadButton::Start)
+                )
+            });
+            if paused {
+                self.open_pause(graphics_context);
+            }
+            return None;
+        };
+
+        let action = pause.update(
+            &mut self.entity_manager,
+            &mut self.resource_manager,
+            &mut self.event_reader,
+            &mut self.event_sender,
+            graphics_context,
+        );
+        match action? {
+            PauseAction::Resume => {
+                self.resume(graphics_context);
+                None
+            }
+            PauseAction::Scene(event) => Some(event),
+        }
+    }
+
+    fn open_pause(&mut self, graphics_context: &mut GraphicsContext) {
+        let pause = PauseMenu::new(
+            &self.settings,
+            &mut self.entity_manager,
+            &mut self.resource_manager,
+            graphics_context,
+        );
+        let pause = match pause {
+            Ok(pause) => pause,
+            Err(e) => return println!("Failed to open the pause menu: {}", e),
+        };
+        // The key that paused the game would otherwise go on to the menu
+        self.event_reader.read(|_: IoEvent| {});
+        self.set_hud_visible(false);
+        graphics_context.cursor_lock(false);
+        self.pause = Some(pause);
+    }
+
+    fn resume(&mut self, graphics_context: &mut GraphicsContext) {
+        let Some(pause) = self.pause.take() else {return};
+        self.settings = pause.settings().clone();
+        pause.close(&mut self.entity_manager);
+
+        let (width, height) = graphics_context.dimensions();
+        let frustrum = Frustrum::perspective_fov(
+            self.settings.fov.to_radians(),
+            width as f32,
+            height as f32,
+            0.1,
+            1000.0,
+        );
+        self.entity_manager
+            .iter_mut::<PlayerShip>()
+            .for_each(|player| player.entity.camera.set_frustrum(frustrum));
+
+        self.event_sender
+            .write(PauseEvent::Resumed(self.settings.clone()));
+        self.set_hud_visible(true);
+        graphics_context.cursor_lock(true);
+    }
+
+    fn set_hud_visible(&mut self, visible: bool) {
+        self.entity_manager
+            .iter_mut::<HudEntity>()
+            .for_each(|hud| hud.entity.visible = visible);
+    }
+
+    fn process_game_events(&mut self) {
         self.event_reader.read(|event| match event {
             GameEvent::ShootPlasmaBullet(transform, bullet) => {
                 self.entity_manager.add_at(bullet, transform);
             }
             GameEvent::RemoveBullet(entity) => self.entity_manager.remove::<BulletEntity>(entity),
         });
+    }
 
+    fn process_events(&mut self, graphics_context: &mut GraphicsContext) -> Option<SceneEvent> {
         let mut action = None;
         self.event_reader.read(|event| match event {
             ContextEvent::Resized(width, height) => {
                 graphics_context.set_viewport(width, height);
+                let scale = graphics_context.ui_scale();
+                update_layout(&mut self.entity_manager, (width, height), scale);
             }
             ContextEvent::Close => action = Some(SceneEvent::Exit),
         });
+        self.event_reader.read(|event| match event {
+            PlayerEvent::GameOver(summary) => {
+                record_high_score(summary);
+                action = Some(SceneEvent::GameOver(summary))
+            }
+        });
+        self.event_reader.read(|event| match event {
+            SceneEvent::NewScene(scene) => action = Some(SceneEvent::NewScene(scene)),
+            _ => {}
+        });
         action
     }
 
-    fn create_label(&mut self, position: Vec3) -> usize {
+    // Headless runs have no frame loop to read these, so they are dropped every tick instead
+    fn drop_frame_events(&mut self) {
+        self.event_reader.read(|_: ContextEvent| {});
+        self.event_reader.read(|_: PlayerEvent| {});
+        self.event_reader.read(|_: SceneEvent| {});
+        self.event_reader.read(|_: TextChangeEvent| {});
+        self.event_reader.read(|_: TrailEvent| {});
+    }
+
+    fn create_label(&mut self, layout: Layout) -> usize {
         let font: Font = self.resource_manager.get("main").res;
-        self.entity_manager.add_at(
-            UiLabel {
-                renderer: TextRenderer::new(Transform::new(), "", font),
-            },
-            Transform::pos(position),
-        )
+        let node = self
+            .entity_manager
+            .iter_mut::<UiLayout>()
+            .next()
+            .map(|ui| ui.entity.add(None, layout));
+        self.entity_manager.add(UiLabel {
+            renderer: TextRenderer::new(Transform::new(), "", font),
+            node,
+        })
+    }
+}
+
+fn record_high_score(summary: Summary) {
+    let result = HighScores::load(HIGH_SCORES).and_then(|mut high_scores| {
+        if let Some(rank) = high_scores.record(summary) {
+            println!("New high score, place {}: {}", rank + 1, summary);
+        }
+        high_scores.save()
+    });
+    if let Err(e) = result {
+        println!("Failed to save high scores: {}", e);
     }
 }
@@ -2,21 +2,24 @@ use std::time::Instant;
 
 use atlas::{
     components::{
-        button_trigger::ButtonTriggerSystem,
-        camera::{Camera, Frustrum},
+        button_trigger::{ButtonTriggerSystem, UiEvent},
+        camera::Camera,
         sprite_renderer::SpriteRendererSystem,
+        text_renderer::TextRendererSystem,
     },
     entity_manager::EntityManager,
     event_bus::{create_event_queue, EventReader, EventSender},
     game_root::GameError,
     graphics::{
         graphics_context::{ContextEvent, GraphicsContext},
-        shaders::sprite_shader::SpriteShaderDefinition,
+        shaders::{sprite_shader::SpriteShaderDefinition, text_shader::TextShaderDefinition},
     },
+    random::Random,
     resource_manager::{scene_resource_manager::SceneResourceManager, ResourceManager},
-    scene::{Scene, SceneEvent},
+    scene::{Scene, SceneEvent, Summary},
+    settings::Settings,
+    systems::layout_system::update_layout,
 };
-use glam::{Vec3, Quat};
 
 use crate::game_objects::menu::create_main_menu;
 
@@ -25,6 +28,7 @@ pub struct MainMenuScene {
     entity_manager: EntityManager,
     resource_manager: SceneResourceManager,
     shape_rendering_system: SpriteRendererSystem,
+    text_rendering_system: TextRendererSystem,
     button_system: ButtonTriggerSystem,
 
     event_sender: EventSender,
@@ -48,13 +52,22 @@ impl Scene for MainMenuScene {
                 &mut self.entity_manager,
                 &mut self.event_reader,
                 &mut self.event_sender,
+                graphics_context,
             );
 
+            // Widgets are layered by drawing order, not by depth
+            graphics_context.depth_write(false);
             self.shape_rendering_system.render(
                 &mut context,
                 &mut self.entity_manager,
                 &self.camera,
             );
+            self.text_rendering_system.render(
+                &mut context,
+                &mut self.entity_manager,
+                &self.camera,
+            );
+            graphics_context.depth_write(true);
 
             if let Some(scene_action) = self.get_scene_action() {
                 return scene_action;
@@ -70,8 +83,9 @@ impl MainMenuScene {
         self.event_reader.read(|event| match event {
             ContextEvent::Resized(width, height) => {
                 graphics_context.set_viewport(width, height);
-                /*self.camera
-                .new(Frustrum::orthogonal(*width as f32, *height as f32));*/
+                self.camera = Camera::ui((width, height));
+                let scale = graphics_context.ui_scale();
+                update_layout(&mut self.entity_manager, (width, height), scale);
             }
             _ => {}
         });
@@ -84,35 +98,40 @@ impl MainMenuScene {
             SceneEvent::Exit => action = Some(SceneEvent::Exit),
             _ => (),
         });
+        // Buttons act through their handlers and the main menu has nothing to go back to
+        self.event_reader.read(|_: UiEvent| {});
         action
     }
 
-    pub fn new(graphics_context: &mut GraphicsContext) -> Result<Box<dyn Scene>, GameError> {
+    pub fn new(
+        graphics_context: &mut GraphicsContext,
+        _random: &Random,
+        _settings: &Settings,
+        summary: Option<Summary>,
+    ) -> Result<Box<dyn Scene>, GameError> {
         let mut entity_manager = EntityManager::new();
         let mut resource_manager = SceneResourceManager::build("main")?;
 
-        let ui_shader: SpriteShaderDefinition = resource_manager.get("basic_ui").res;
+        let ui_shader: SpriteShaderDefinition = resource_manager.get("basic").res;
         let shape_rendering_system = SpriteRendererSystem::new(ui_shader);
+        let text_shader: TextShaderDefinition = resource_manager.get("basic").res;
+        let text_rendering_system = TextRendererSystem::new(text_shader);
 
-        create_main_menu(
+        create_main_menu(&mut entity_manager, &mut resource_manager, summary)?;
+        update_layout(
             &mut entity_manager,
-            &mut resource_manager,
             graphics_context.dimensions(),
-        )?;
-
-        let (width, height) = graphics_context.dimensions();
+            graphics_context.ui_scale(),
+        );
 
         let (event_sender, event_reader) = create_event_queue();
 
         let main_scene = MainMenuScene {
-            camera: Camera::new(
-                Frustrum::orthogonal(width as f32, height as f32),
-                Vec3::new(0.0, 0.0, 0.0),
-                Quat::from_axis_angle(Vec3::new(0.0, 0.0, 1.0), 0.0),
-            ),
+            camera: Camera::ui(graphics_context.dimensions()),
             entity_manager,
             resource_manager,
             shape_rendering_system,
+            text_rendering_system,
             button_system: ButtonTriggerSystem::new(),
             event_sender,
             event_reader,
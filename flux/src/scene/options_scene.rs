@@ -0,0 +1,171 @@
+use atlas::{
+    components::{
+        button_trigger::{ButtonTriggerSystem, UiEvent},
+        camera::Camera,
+        layout::Layout,
+        sprite_renderer::{SpriteRenderer, SpriteRendererSystem},
+        text_renderer::TextRendererSystem,
+    },
+    entity_manager::EntityManager,
+    event_bus::{create_event_queue, EventReader, EventSender},
+    game_entities::{sprite::Sprite, ui_layout::UiLayout},
+    game_root::GameError,
+    graphics::{
+        graphics_context::{ContextEvent, GraphicsContext},
+        shaders::{sprite_shader::SpriteShaderDefinition, text_shader::TextShaderDefinition},
+    },
+    random::Random,
+    resource_manager::{scene_resource_manager::SceneResourceManager, ResourceManager},
+    scene::{Scene, SceneEvent, Summary},
+    settings::Settings,
+    systems::layout_system::update_layout,
+};
+
+use crate::game_objects::options::OptionsMenu;
+
+pub struct OptionsScene {
+    camera: Camera,
+    entity_manager: EntityManager,
+    shape_rendering_system: SpriteRendererSystem,
+    text_rendering_system: TextRendererSystem,
+    button_system: ButtonTriggerSystem,
+    options: OptionsMenu,
+
+    event_sender: EventSender,
+    event_reader: EventReader,
+}
+
+impl Scene for OptionsScene {
+    fn run(&mut self, graphics_context: &mut GraphicsContext) -> SceneEvent {
+        let mut context = graphics_context.new_context();
+
+        loop {
+            self.poll_events(graphics_context);
+
+            graphics_context.display();
+
+            match self.options.is_listening() {
+                true => self
+                    .options
+                    .listen(&mut self.entity_manager, &mut self.event_reader),
+                false => self.button_system.check_buttons(
+                    &mut self.entity_manager,
+                    &mut self.event_reader,
+                    &mut self.event_sender,
+                    graphics_context,
+                ),
+            }
+            self.apply_changes(graphics_context);
+
+            // Widgets are layered by drawing order, not by depth
+            graphics_context.depth_write(false);
+            self.shape_rendering_system.render(
+                &mut context,
+                &mut self.entity_manager,
+                &self.camera,
+            );
+            self.text_rendering_system.render(
+                &mut context,
+                &mut self.entity_manager,
+                &self.camera,
+            );
+            graphics_context.depth_write(true);
+
+            if let Some(scene_action) = self.get_scene_action() {
+                self.options.save();
+                return scene_action;
+            }
+        }
+    }
+}
+
+impl OptionsScene {
+    fn poll_events(&mut self, graphics_context: &mut GraphicsContext) {
+        graphics_context.poll_events(&mut self.event_sender);
+
+        self.event_reader.read(|event| match event {
+            ContextEvent::Resized(width, height) => {
+                graphics_context.set_viewport(width, height);
+                self.camera = Camera::ui((width, height));
+                let scale = graphics_context.ui_scale();
+                update_layout(&mut self.entity_manager, (width, height), scale);
+            }
+            _ => {}
+        });
+    }
+
+    fn apply_changes(&mut self, graphics_context: &mut GraphicsContext) {
+        let mut back = false;
+        let (options, entity_manager) = (&mut self.options, &mut self.entity_manager);
+        self.event_reader.read(|event: UiEvent| {
+            if options.handle(&event, entity_manager, graphics_context) {
+                return;
+            }
+            match event {
+                UiEvent::Pressed(name) if name == "back" => back = true,
+                UiEvent::Back => back = true,
+                _ => {}
+            }
+        });
+        if back {
+            self.event_sender
+                .write(SceneEvent::NewScene(String::from("main")));
+        }
+    }
+
+    fn get_scene_action(&mut self) -> Option<SceneEvent> {
+        let mut action = None;
+        self.event_reader.read(|event| match event {
+            SceneEvent::NewScene(new_scene) => action = Some(SceneEvent::NewScene(new_scene)),
+            SceneEvent::Exit => action = Some(SceneEvent::Exit),
+            _ => (),
+        });
+        action
+    }
+
+    pub fn new(
+        graphics_context: &mut GraphicsContext,
+        _random: &Random,
+        settings: &Settings,
+        _summary: Option<Summary>,
+    ) -> Result<Box<dyn Scene>, GameError> {
+        let mut entity_manager = EntityManager::new();
+        let mut resource_manager = SceneResourceManager::build("main")?;
+
+        let ui_shader: SpriteShaderDefinition = resource_manager.get("basic").res;
+        let shape_rendering_system = SpriteRendererSystem::new(ui_shader);
+        let text_shader: TextShaderDefinition = resource_manager.get("basic").res;
+        let text_rendering_system = TextRendererSystem::new(text_shader);
+
+        let mut ui = UiLayout::new();
+        let main_screen = resource_manager.get("background").res;
+        entity_manager.add(Sprite {
+            renderer: SpriteRenderer::quad((1.0, 1.0), main_screen),
+            node: Some(ui.add(None, Layout::full())),
+        });
+        entity_manager.add(ui);
+
+        let options = OptionsMenu::new(settings, graphics_context)?;
+        options.create(&mut entity_manager, &mut resource_manager);
+        update_layout(
+            &mut entity_manager,
+            graphics_context.dimensions(),
+            graphics_context.ui_scale(),
+        );
+
+        let (event_sender, event_reader) = create_event_queue();
+
+        let options_scene = OptionsScene {
+            camera: Camera::ui(graphics_context.dimensions()),
+            entity_manager,
+            shape_rendering_system,
+            text_rendering_system,
+            button_system: ButtonTriggerSystem::new(),
+            options,
+            event_sender,
+            event_reader,
+        };
+
+        Ok(Box::new(options_scene))
+    }
+}